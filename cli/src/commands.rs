@@ -0,0 +1,465 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::sysvar;
+use anchor_lang::Discriminator;
+use anchor_spl::token_interface::TokenAccount;
+use anyhow::{anyhow, Context, Result};
+use pricelocker::{CircuitBreaker, Fees, Lock, LockCondition, Locker, PriceReading};
+use pricelocker_client as client;
+use pricelocker_client::{CrankLocker, PriceFeedAccounts, TokenAsset};
+use solana_account_decoder::UiAccountEncoding;
+use solana_client::rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig};
+use solana_client::rpc_filter::{Memcmp, RpcFilterType};
+use solana_sdk::account::from_account;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::signature::{Keypair, Signer};
+use solana_sdk::transaction::Transaction;
+
+use crate::view::{locker_view, ConfigView, CrankView, CrankedLockerView, Report, TransactionView};
+use crate::Session;
+
+
+// Maximum number of lockers cranked in one transaction
+const CRANK_BATCH_SIZE: usize = 5;
+
+
+pub fn create_locker(session: &Session, locker_name: &str) -> Result<Report> {
+    let instruction = client::create_new_locker(&session.program_id, &session.payer.pubkey(), locker_name);
+    transaction_report(session, "create-locker", locker_name, instruction)
+}
+
+// The price locks of the locker are bound to the price feed, which is required when it has price locks
+pub fn migrate_locker(session: &Session, locker_name: &str, price_feed: Option<&str>) -> Result<Report> {
+    let feed_accounts = price_feed.map(|alias| fetch_feed_accounts(session, alias)).transpose()?;
+    let instruction = client::migrate_locker(&session.program_id, &session.payer.pubkey(), locker_name, feed_accounts.as_ref().map(|feed_accounts| &feed_accounts.price_feed));
+    transaction_report(session, "migrate-locker", locker_name, instruction)
+}
+
+pub fn heartbeat(session: &Session, locker_name: &str) -> Result<Report> {
+    let instruction = client::heartbeat(&session.program_id, &session.payer.pubkey(), locker_name);
+    transaction_report(session, "heartbeat", locker_name, instruction)
+}
+
+pub fn revoke_inheritance(session: &Session, locker_name: &str, lock_id: u8) -> Result<Report> {
+    let instruction = client::revoke_inactivity_lock(&session.program_id, &session.payer.pubkey(), locker_name, lock_id);
+    transaction_report(session, "revoke-inheritance", locker_name, instruction)
+}
+
+pub fn deposit(session: &Session, locker_name: &str, amount: u32, mint: Option<Pubkey>, wrap: bool, price_feed: Option<&str>) -> Result<Report> {
+    let feed_accounts = price_feed.map(|alias| fetch_feed_accounts(session, alias)).transpose()?;
+    let instruction = if wrap {
+        client::deposit_wrapped_sol(&session.program_id, &session.payer.pubkey(), locker_name, amount, feed_accounts.as_ref())
+    } else {
+        let token = mint.map(|mint| fetch_token_asset(session, &mint)).transpose()?;
+        client::deposit_funds(&session.program_id, &session.payer.pubkey(), locker_name, amount, token.as_ref(), feed_accounts.as_ref())
+    };
+    transaction_report(session, "deposit", locker_name, instruction)
+}
+
+pub fn deposit_lock(session: &Session, locker_name: &str, amount: u32, conditions: &[LockCondition], mint: Option<Pubkey>, price_feed: Option<&str>, beneficiary: Option<Pubkey>) -> Result<Report> {
+    let feed_accounts = price_feed.map(|alias| fetch_feed_accounts(session, alias)).transpose()?;
+    let token = mint.map(|mint| fetch_token_asset(session, &mint)).transpose()?;
+    let instruction = client::deposit_and_lock(&session.program_id, &session.payer.pubkey(), locker_name, amount, token.as_ref(), feed_accounts.as_ref(), conditions, beneficiary);
+    transaction_report(session, "deposit-lock", locker_name, instruction)
+}
+
+pub fn lock_lot(session: &Session, locker_name: &str, lot_id: u16, amount: u32, conditions: &[LockCondition], mint: Option<Pubkey>, beneficiary: Option<Pubkey>) -> Result<Report> {
+    let token = mint.map(|mint| fetch_token_asset(session, &mint)).transpose()?;
+    let instruction = client::lock_lot(&session.program_id, &session.payer.pubkey(), locker_name, lot_id, amount, token.as_ref(), conditions, beneficiary);
+    transaction_report(session, "lock-lot", locker_name, instruction)
+}
+
+pub fn deposit_nft(session: &Session, locker_name: &str, mint: &Pubkey, amount: u32, conditions: &[LockCondition], beneficiary: Option<Pubkey>) -> Result<Report> {
+    let nft = fetch_token_asset(session, mint)?;
+    let instruction = client::deposit_nft(&session.program_id, &session.payer.pubkey(), locker_name, &nft, amount, conditions, beneficiary);
+    transaction_report(session, "deposit-nft", locker_name, instruction)
+}
+
+pub fn withdraw_nft(session: &Session, locker_name: &str, mint: &Pubkey, amount: u32) -> Result<Report> {
+    let nft = fetch_token_asset(session, mint)?;
+    let instruction = client::withdraw_nft(&session.program_id, &session.payer.pubkey(), locker_name, &nft, amount);
+    transaction_report(session, "withdraw-nft", locker_name, instruction)
+}
+
+pub fn lock_time(session: &Session, locker_name: &str, amount: u32, strike_time: u32, mint: Option<Pubkey>, join: Option<u8>) -> Result<Report> {
+    let token = mint.map(|mint| fetch_token_asset(session, &mint)).transpose()?;
+    let instruction = client::time_lock_funds(&session.program_id, &session.payer.pubkey(), locker_name, strike_time, amount, token, join);
+    transaction_report(session, "lock-time", locker_name, instruction)
+}
+
+pub fn lock_price(session: &Session, locker_name: &str, amount: u32, strike_price: u32, price_feed: &str, mint: Option<Pubkey>, join: Option<u8>) -> Result<Report> {
+    let token = mint.map(|mint| fetch_token_asset(session, &mint)).transpose()?;
+    let feed_accounts = fetch_feed_accounts(session, price_feed)?;
+    let instruction = client::price_lock_funds(&session.program_id, &session.payer.pubkey(), locker_name, &feed_accounts.price_feed, strike_price, amount, token, join);
+    transaction_report(session, "lock-price", locker_name, instruction)
+}
+
+// Without a destination, the funds are withdrawn to the keypair
+pub fn withdraw(session: &Session, locker_name: &str, amount: u32, mint: Option<Pubkey>, unwrap: bool, destination: Option<Pubkey>) -> Result<Report> {
+    let instruction = if unwrap {
+        client::withdraw_unwrapped_sol(&session.program_id, &session.payer.pubkey(), locker_name, amount)
+    } else {
+        let token = mint.map(|mint| fetch_token_asset(session, &mint)).transpose()?;
+        match destination {
+            Some(destination) => client::withdraw_to_destination(&session.program_id, &session.payer.pubkey(), locker_name, amount, token.as_ref(), &destination),
+            None => client::withdraw_unlocked_funds(&session.program_id, &session.payer.pubkey(), locker_name, amount, token.as_ref()),
+        }
+    };
+    transaction_report(session, "withdraw", locker_name, instruction)
+}
+
+pub fn set_cooldown(session: &Session, locker_name: &str, cooldown_secs: u32) -> Result<Report> {
+    let instruction = client::set_withdrawal_cooldown(&session.program_id, &session.payer.pubkey(), locker_name, cooldown_secs);
+    transaction_report(session, "set-cooldown", locker_name, instruction)
+}
+
+// Without a destination, the funds are requested for the keypair
+pub fn request_withdrawal(session: &Session, locker_name: &str, amount: u32, mint: Option<Pubkey>, destination: Option<Pubkey>) -> Result<Report> {
+    let token = mint.map(|mint| fetch_token_asset(session, &mint)).transpose()?;
+    let destination = destination.unwrap_or_else(|| session.payer.pubkey());
+    let instruction = client::request_withdrawal(&session.program_id, &session.payer.pubkey(), locker_name, amount, token.as_ref(), &destination);
+    transaction_report(session, "request-withdrawal", locker_name, instruction)
+}
+
+pub fn execute_withdrawal(session: &Session, locker_name: &str) -> Result<Report> {
+    // The asset and the destination are read from the request
+    let (address, _bump) = client::find_locker_address(&session.program_id, &session.payer.pubkey(), locker_name);
+    let account = session.rpc.get_account(&address).with_context(|| format!("fetching locker {address}"))?;
+    let locker = client::decode_locker(&account.data).context("decoding locker")?;
+    let request = locker.withdrawal_request.ok_or_else(|| anyhow!("locker {locker_name} has no withdrawal request"))?;
+    let token = request.token_mint.map(|mint| fetch_token_asset(session, &mint)).transpose()?;
+
+    let instruction = client::execute_withdrawal(&session.program_id, &session.payer.pubkey(), locker_name, token.as_ref(), &request.destination);
+    transaction_report(session, "execute-withdrawal", locker_name, instruction)
+}
+
+// The keypair signs as the authority, or as a guardian of the locker of another authority
+pub fn cancel_withdrawal(session: &Session, locker_name: &str, authority: Option<Pubkey>) -> Result<Report> {
+    let authority = authority.unwrap_or_else(|| session.payer.pubkey());
+    let instruction = client::cancel_withdrawal(&session.program_id, &session.payer.pubkey(), &authority, locker_name);
+
+    // Not necessarily the locker of the keypair, see transaction_report
+    let (locker, _bump) = client::find_locker_address(&session.program_id, &authority, locker_name);
+    let signature = send(session, instruction)?;
+
+    Ok(Report::Transaction(TransactionView {
+        action: "cancel-withdrawal".to_string(),
+        locker: locker.to_string(),
+        signature: signature.to_string(),
+    }))
+}
+
+pub fn set_allowlist(session: &Session, locker_name: &str, destinations: &[Pubkey]) -> Result<Report> {
+    let instruction = client::set_withdrawal_allowlist(&session.program_id, &session.payer.pubkey(), locker_name, destinations);
+    transaction_report(session, "set-allowlist", locker_name, instruction)
+}
+
+pub fn cancel_allowlist_change(session: &Session, locker_name: &str) -> Result<Report> {
+    let instruction = client::cancel_withdrawal_allowlist_change(&session.program_id, &session.payer.pubkey(), locker_name);
+    transaction_report(session, "cancel-allowlist-change", locker_name, instruction)
+}
+
+// The keypair signs as the beneficiary, the locker is derived from the authority
+pub fn claim(session: &Session, locker_name: &str, lock_id: u8, authority: &Pubkey, amount: u32, mint: Option<Pubkey>) -> Result<Report> {
+    let token = mint.map(|mint| fetch_token_asset(session, &mint)).transpose()?;
+    let instruction = client::claim_beneficiary_funds(&session.program_id, &session.payer.pubkey(), authority, locker_name, lock_id, amount, token.as_ref());
+
+    // Not the locker of the keypair, see transaction_report
+    let (locker, _bump) = client::find_locker_address(&session.program_id, authority, locker_name);
+    let signature = send(session, instruction)?;
+
+    Ok(Report::Transaction(TransactionView {
+        action: "claim".to_string(),
+        locker: locker.to_string(),
+        signature: signature.to_string(),
+    }))
+}
+
+
+pub fn exit_early(session: &Session, locker_name: &str, lock_id: u8) -> Result<Report> {
+    // The penalty destination and the asset are read from the lock
+    let (address, _bump) = client::find_locker_address(&session.program_id, &session.payer.pubkey(), locker_name);
+    let account = session.rpc.get_account(&address).with_context(|| format!("fetching locker {address}"))?;
+    let locker = client::decode_locker(&account.data).context("decoding locker")?;
+    let lock_item = locker.locks.iter().find(|lock_item| lock_item.id() == lock_id).ok_or_else(|| anyhow!("locker {locker_name} has no lock {lock_id}"))?;
+    let early_exit = lock_item.early_exit().ok_or_else(|| anyhow!("lock {lock_id} has no early exit"))?;
+    let token = lock_item.token_mint().map(|mint| fetch_token_asset(session, &mint)).transpose()?;
+
+    let instruction = client::exit_lock_early(&session.program_id, &session.payer.pubkey(), locker_name, lock_id, &early_exit.penalty_destination, token.as_ref());
+    transaction_report(session, "exit-early", locker_name, instruction)
+}
+
+pub fn set_guardians(session: &Session, locker_name: &str, guardians: &[Pubkey], threshold: u8, penalty_bps: u16, penalty_destination: &Pubkey) -> Result<Report> {
+    let instruction = client::set_guardians(&session.program_id, &session.payer.pubkey(), locker_name, guardians, threshold, penalty_bps, penalty_destination);
+    transaction_report(session, "set-guardians", locker_name, instruction)
+}
+
+pub fn guardian_release(session: &Session, locker_name: &str, lock_id: u8, guardians: &[Keypair]) -> Result<Report> {
+    // The penalty destination and the asset of the lock are read from the locker
+    let (address, _bump) = client::find_locker_address(&session.program_id, &session.payer.pubkey(), locker_name);
+    let account = session.rpc.get_account(&address).with_context(|| format!("fetching locker {address}"))?;
+    let locker = client::decode_locker(&account.data).context("decoding locker")?;
+    let config = locker.guardians.ok_or_else(|| anyhow!("locker {locker_name} has no guardians"))?;
+    let lock_item = locker.locks.iter().find(|lock_item| lock_item.id() == lock_id).ok_or_else(|| anyhow!("locker {locker_name} has no lock {lock_id}"))?;
+    let token = lock_item.token_mint().map(|mint| fetch_token_asset(session, &mint)).transpose()?;
+
+    let guardian_keys: Vec<Pubkey> = guardians.iter().map(|guardian| guardian.pubkey()).collect();
+    let instruction = client::guardian_release(&session.program_id, &session.payer.pubkey(), locker_name, lock_id, &guardian_keys, &config.penalty_destination, token.as_ref());
+    let signature = send_with_signers(session, instruction, guardians)?;
+
+    Ok(Report::Transaction(TransactionView {
+        action: "guardian-release".to_string(),
+        locker: address.to_string(),
+        signature: signature.to_string(),
+    }))
+}
+
+
+pub fn init_config(session: &Session, fees: Fees) -> Result<Report> {
+    let instruction = client::init_config(&session.program_id, &session.payer.pubkey(), fees);
+    config_report(session, "init-config", instruction)
+}
+
+// Without a new admin, the keypair stays the admin
+pub fn update_config(session: &Session, fees: Fees, new_admin: Option<Pubkey>) -> Result<Report> {
+    let new_admin = new_admin.unwrap_or_else(|| session.payer.pubkey());
+    let instruction = client::update_config(&session.program_id, &session.payer.pubkey(), &new_admin, fees);
+    config_report(session, "update-config", instruction)
+}
+
+pub fn set_paused(session: &Session, paused: bool) -> Result<Report> {
+    let instruction = client::set_paused(&session.program_id, &session.payer.pubkey(), paused);
+    config_report(session, if paused { "pause" } else { "resume" }, instruction)
+}
+
+pub fn set_circuit_breaker(session: &Session, circuit_breaker: CircuitBreaker) -> Result<Report> {
+    let instruction = client::set_circuit_breaker(&session.program_id, &session.payer.pubkey(), circuit_breaker);
+    config_report(session, "set-circuit-breaker", instruction)
+}
+
+pub fn show_config(session: &Session) -> Result<Report> {
+    let (address, _bump) = client::find_config_address(&session.program_id);
+    let account = session.rpc.get_account(&address).with_context(|| format!("fetching program config {address}"))?;
+    let config = client::decode_program_config(&account.data).context("decoding program config")?;
+
+    let (treasury, _bump) = client::find_treasury_address(&session.program_id);
+    Ok(Report::Config(ConfigView {
+        config: address.to_string(),
+        admin: config.admin.to_string(),
+        deposit_fee_bps: config.fees.deposit_bps,
+        early_exit_fee_bps: config.fees.early_exit_bps,
+        staking_reward_fee_bps: config.fees.staking_reward_bps,
+        paused: config.paused,
+        max_price_move_bps: config.circuit_breaker.max_price_move_bps,
+        circuit_breaker_window_secs: config.circuit_breaker.window_secs,
+        treasury: treasury.to_string(),
+        treasury_sol: fetch_treasury_sol(session, &treasury)?,
+    }))
+}
+
+// Without an amount, all collected fees of the asset are withdrawn
+pub fn withdraw_fees(session: &Session, amount: Option<u64>, mint: Option<Pubkey>) -> Result<Report> {
+    let (treasury, _bump) = client::find_treasury_address(&session.program_id);
+    let amount = match (amount, mint) {
+        (Some(amount), _) => amount,
+        (None, Some(mint)) => fetch_token_balance(session, &treasury, &mint)?,
+        (None, None) => fetch_treasury_sol(session, &treasury)?,
+    };
+
+    let token = mint.map(|mint| fetch_token_asset(session, &mint)).transpose()?;
+    let instruction = client::withdraw_fees(&session.program_id, &session.payer.pubkey(), amount, token.as_ref());
+    config_report(session, "withdraw-fees", instruction)
+}
+
+// Collected SOL of the treasury, without the rent-exempt minimum which can never be withdrawn
+fn fetch_treasury_sol(session: &Session, treasury: &Pubkey) -> Result<u64> {
+    let account = session.rpc.get_account(treasury).with_context(|| format!("fetching treasury {treasury}"))?;
+    let rent_exempt_minimum = session.rpc.get_minimum_balance_for_rent_exemption(account.data.len())?;
+    Ok(account.lamports.saturating_sub(rent_exempt_minimum))
+}
+
+
+pub fn show(session: &Session, locker_name: &str, authority: Option<Pubkey>, price_feed: Option<&str>) -> Result<Report> {
+    let authority = authority.unwrap_or_else(|| session.payer.pubkey());
+    let (address, _bump) = client::find_locker_address(&session.program_id, &authority, locker_name);
+
+    let account = session.rpc.get_account(&address).with_context(|| format!("fetching locker {address}"))?;
+    let locker = client::decode_locker(&account.data).context("decoding locker")?;
+
+    let clock = fetch_clock(session)?;
+    let price = match price_feed {
+        Some(alias) => fetch_feed(session, alias, clock.unix_timestamp)?.1,
+        None => None,
+    };
+
+    // SOL balance without the rent-exempt minimum, followed by the balance of every locked token
+    let rent_exempt_minimum = session.rpc.get_minimum_balance_for_rent_exemption(account.data.len())?;
+    let mut balances = vec![(None, account.lamports.saturating_sub(rent_exempt_minimum))];
+    for lock_item in &locker.locks {
+        if let Some(token_mint) = lock_item.token_mint() {
+            if !balances.iter().any(|(balance_mint, _)| *balance_mint == Some(token_mint)) {
+                balances.push((Some(token_mint), fetch_token_balance(session, &address, &token_mint)?));
+            }
+        }
+    }
+
+    let view = locker_view(&address, locker_name, &locker, &balances, clock.unix_timestamp as u32, price);
+    Ok(Report::Locker(view))
+}
+
+
+pub fn crank(session: &Session, locker_names: &[String], price_feed: Option<&str>) -> Result<Report> {
+    let authority = session.payer.pubkey();
+
+    // Crank the given lockers, or all lockers of the keypair
+    let lockers: Vec<(Pubkey, Locker)> = if locker_names.is_empty() {
+        fetch_lockers_of(session, &authority)?
+    } else {
+        locker_names.iter().map(|locker_name| {
+            let (address, _bump) = client::find_locker_address(&session.program_id, &authority, locker_name);
+            let account = session.rpc.get_account(&address).with_context(|| format!("fetching locker {locker_name}"))?;
+            Ok((address, client::decode_locker(&account.data)?))
+        }).collect::<Result<_>>()?
+    };
+
+    let clock = fetch_clock(session)?;
+    let (feed_accounts, price) = match price_feed {
+        Some(alias) => {
+            let (accounts, price) = fetch_feed(session, alias, clock.unix_timestamp)?;
+            (Some(accounts), price)
+        },
+        None => (None, None),
+    };
+
+    // Only crank the lockers with at least one lock whose condition is met
+    let mut cranked = Vec::new();
+    let mut crank_lockers = Vec::new();
+    for (address, locker) in &lockers {
+        let unlockable: Vec<&Lock> = locker.locks.iter().filter(|lock_item| client::is_unlockable(lock_item, clock.unix_timestamp as u32, price, locker.last_activity_ts)).collect();
+        if unlockable.is_empty() {
+            continue;
+        }
+
+        let needs_price_feed = unlockable.iter().any(|lock_item| matches!(lock_item, Lock::PriceLock { .. }));
+        crank_lockers.push(CrankLocker {
+            locker: *address,
+            price_feed: feed_accounts.filter(|_| needs_price_feed),
+        });
+        cranked.push(CrankedLockerView {
+            locker: address.to_string(),
+            lock_ids: unlockable.iter().map(|lock_item| lock_item.id()).collect(),
+        });
+    }
+
+    let mut signatures = Vec::new();
+    for batch in crank_lockers.chunks(CRANK_BATCH_SIZE) {
+        let instruction = client::crank_unlocks(&session.program_id, &authority, batch);
+        signatures.push(send(session, instruction)?.to_string());
+    }
+
+    Ok(Report::Crank(CrankView { lockers: cranked, signatures }))
+}
+
+
+// Sign and send a transaction with a single instruction, waiting for confirmation
+fn send(session: &Session, instruction: Instruction) -> Result<solana_sdk::signature::Signature> {
+    send_with_signers(session, instruction, &[])
+}
+
+// Like send, with more signers than the payer, e.g. the guardians of a release
+fn send_with_signers(session: &Session, instruction: Instruction, signers: &[Keypair]) -> Result<solana_sdk::signature::Signature> {
+    let blockhash = session.rpc.get_latest_blockhash().context("fetching latest blockhash")?;
+    let signers: Vec<&Keypair> = std::iter::once(&session.payer).chain(signers).collect();
+    let transaction = Transaction::new_signed_with_payer(&[instruction], Some(&session.payer.pubkey()), &signers, blockhash);
+    session.rpc.send_and_confirm_transaction(&transaction).context("sending transaction")
+}
+
+fn transaction_report(session: &Session, action: &str, locker_name: &str, instruction: Instruction) -> Result<Report> {
+    let (locker, _bump) = client::find_locker_address(&session.program_id, &session.payer.pubkey(), locker_name);
+    let signature = send(session, instruction)?;
+
+    Ok(Report::Transaction(TransactionView {
+        action: action.to_string(),
+        locker: locker.to_string(),
+        signature: signature.to_string(),
+    }))
+}
+
+
+// Like transaction_report, for an instruction of the program config instead of a locker
+fn config_report(session: &Session, action: &str, instruction: Instruction) -> Result<Report> {
+    let (config, _bump) = client::find_config_address(&session.program_id);
+    let signature = send(session, instruction)?;
+
+    Ok(Report::Transaction(TransactionView {
+        action: action.to_string(),
+        locker: config.to_string(),
+        signature: signature.to_string(),
+    }))
+}
+
+
+// Locks are evaluated against the cluster time and not the local time
+fn fetch_clock(session: &Session) -> Result<Clock> {
+    let account = session.rpc.get_account(&sysvar::clock::ID).context("fetching clock sysvar")?;
+    from_account::<Clock, _>(&account).ok_or_else(|| anyhow!("invalid clock sysvar"))
+}
+
+// Fetch a registered price feed by its alias, with the current price of the Pyth account it points to
+// The price is only evaluated against the price locks created on this price feed
+fn fetch_feed(session: &Session, alias: &str, time_now: i64) -> Result<(PriceFeedAccounts, Option<PriceReading>)> {
+    let feed_accounts = fetch_feed_accounts(session, alias)?;
+
+    let pyth_account = session.rpc.get_account(&feed_accounts.pyth_price_account).context("fetching pyth price account")?;
+    let price = client::pyth_price(&feed_accounts.pyth_price_account, &pyth_account.data, time_now)
+        .map(|price| PriceReading { price_feed: feed_accounts.price_feed, price });
+
+    Ok((feed_accounts, price))
+}
+
+fn fetch_feed_accounts(session: &Session, alias: &str) -> Result<PriceFeedAccounts> {
+    let (price_feed, _bump) = client::find_pricefeed_address(&session.program_id, alias);
+    let account = session.rpc.get_account(&price_feed).with_context(|| format!("fetching price feed {alias}"))?;
+    let pyth_price_account = client::decode_pricefeed_account(&account.data)?.pricefeed_id;
+
+    Ok(PriceFeedAccounts { price_feed, pyth_price_account })
+}
+
+// The token program of a mint is the owner of the mint account, SPL Token or Token-2022
+fn fetch_token_asset(session: &Session, mint: &Pubkey) -> Result<TokenAsset> {
+    let account = session.rpc.get_account(mint).with_context(|| format!("fetching token mint {mint}"))?;
+    Ok(TokenAsset { mint: *mint, token_program: account.owner })
+}
+
+// Balance of the token account of the locker, 0 when the token account doesn't exist
+fn fetch_token_balance(session: &Session, locker: &Pubkey, token_mint: &Pubkey) -> Result<u64> {
+    let token = fetch_token_asset(session, token_mint)?;
+    let token_address = client::locker_token_address(locker, &token);
+    let account = session.rpc.get_account_with_commitment(&token_address, session.rpc.commitment())?.value;
+
+    match account {
+        Some(account) => Ok(TokenAccount::try_deserialize(&mut account.data.as_slice())?.amount),
+        None => Ok(0),
+    }
+}
+
+// All lockers of an authority, the authority is the first field after the discriminator
+fn fetch_lockers_of(session: &Session, authority: &Pubkey) -> Result<Vec<(Pubkey, Locker)>> {
+    let config = RpcProgramAccountsConfig {
+        filters: Some(vec![
+            RpcFilterType::Memcmp(Memcmp::new_base58_encoded(0, &Locker::DISCRIMINATOR)),
+            RpcFilterType::Memcmp(Memcmp::new_base58_encoded(8, authority.as_ref())),
+        ]),
+        account_config: RpcAccountInfoConfig {
+            encoding: Some(UiAccountEncoding::Base64),
+            ..RpcAccountInfoConfig::default()
+        },
+        ..RpcProgramAccountsConfig::default()
+    };
+
+    let accounts = session.rpc.get_program_accounts_with_config(&session.program_id, config).context("fetching lockers")?;
+
+    accounts
+        .into_iter()
+        .map(|(address, account)| Ok((address, client::decode_locker(&account.data)?)))
+        .collect()
+}
@@ -0,0 +1,533 @@
+// Command-line tool to manage pricelocker lockers
+// Built on the pricelocker-client SDK, every command prints a human-readable table or JSON (--output json)
+
+mod commands;
+mod output;
+mod view;
+
+use anchor_lang::prelude::Pubkey;
+use anyhow::{anyhow, Result};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use pricelocker::{CircuitBreaker, Fees, LockCondition};
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::signature::{read_keypair_file, Keypair};
+use std::path::PathBuf;
+
+
+#[derive(Parser, Debug)]
+#[command(name = "pricelocker", version, about = "Create lockers, lock funds on time or price, and withdraw them again")]
+struct Cli {
+    /// RPC endpoint of the cluster
+    #[arg(long, short = 'u', global = true, env = "PRICELOCKER_RPC_URL", default_value = "http://127.0.0.1:8899")]
+    url: String,
+
+    /// Keypair of the locker authority, which signs and pays for the transactions
+    #[arg(long, short = 'k', global = true, env = "PRICELOCKER_KEYPAIR", default_value = "~/.config/solana/id.json")]
+    keypair: PathBuf,
+
+    /// Program id of the deployed locker program
+    #[arg(long, global = true, env = "PRICELOCKER_PROGRAM_ID", default_value_t = pricelocker::ID)]
+    program_id: Pubkey,
+
+    /// Output format
+    #[arg(long, short = 'o', global = true, value_enum, default_value_t = OutputFormat::Table)]
+    output: OutputFormat,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    Table,
+    Json,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Create a new locker
+    CreateLocker {
+        /// Name of the locker
+        locker_name: String,
+    },
+    /// Migrate a locker created before lots were added to the current layout
+    MigrateLocker {
+        /// Name of the locker
+        locker_name: String,
+        /// Alias of the price feed the price locks of the locker are bound to, required when it has price locks
+        #[arg(long)]
+        price_feed: Option<String>,
+    },
+    /// Check in as the authority, which keeps the inactivity locks locked for another period
+    Heartbeat {
+        /// Name of the locker
+        locker_name: String,
+    },
+    /// Revoke a locked inactivity lock, its funds are no longer inherited by the heir
+    RevokeInheritance {
+        /// Name of the locker
+        locker_name: String,
+        /// Id of the inactivity lock
+        lock_id: u8,
+    },
+    /// Deposit SOL (lamports) or SPL tokens (base units, with --mint) into a locker
+    Deposit {
+        /// Name of the locker
+        locker_name: String,
+        /// Amount in lamports, or token base units with --mint
+        amount: u32,
+        /// Mint of the SPL token, SOL when not set
+        #[arg(long)]
+        mint: Option<Pubkey>,
+        /// Wrap the SOL into a native mint token account of the locker
+        #[arg(long, conflicts_with = "mint")]
+        wrap: bool,
+        /// Alias of the price feed to record the price at the deposit
+        #[arg(long)]
+        price_feed: Option<String>,
+    },
+    /// Deposit funds and lock that deposit until any of the conditions is met
+    DepositLock {
+        /// Name of the locker
+        locker_name: String,
+        /// Amount in lamports, or token base units with --mint
+        amount: u32,
+        #[command(flatten)]
+        conditions: ConditionArgs,
+        /// Mint of the SPL token, SOL when not set
+        #[arg(long)]
+        mint: Option<Pubkey>,
+        /// Alias of the price feed to record the price at the deposit
+        #[arg(long)]
+        price_feed: Option<String>,
+        /// Wallet that claims the funds once unlocked, instead of the authority
+        #[arg(long)]
+        beneficiary: Option<Pubkey>,
+    },
+    /// Lock an amount of an earlier deposit (lot) until any of the conditions is met
+    LockLot {
+        /// Name of the locker
+        locker_name: String,
+        /// Id of the lot, see the lots of the show command
+        lot_id: u16,
+        /// Amount in lamports, or token base units with --mint
+        amount: u32,
+        #[command(flatten)]
+        conditions: ConditionArgs,
+        /// Mint of the SPL token of the lot, SOL when not set
+        #[arg(long)]
+        mint: Option<Pubkey>,
+        /// Wallet that claims the funds once unlocked, instead of the authority
+        #[arg(long)]
+        beneficiary: Option<Pubkey>,
+    },
+    /// Deposit an NFT (or an amount of a semi-fungible token), locked until any of the conditions is met
+    DepositNft {
+        /// Name of the locker
+        locker_name: String,
+        /// Mint of the NFT
+        mint: Pubkey,
+        /// Amount, 1 for an NFT
+        #[arg(long, default_value_t = 1)]
+        amount: u32,
+        /// Without conditions the NFT is deposited unlocked
+        #[command(flatten)]
+        conditions: ConditionArgs,
+        /// Wallet that claims the funds once unlocked, instead of the authority
+        #[arg(long)]
+        beneficiary: Option<Pubkey>,
+    },
+    /// Withdraw an NFT (or an amount of a semi-fungible token) that is not locked
+    WithdrawNft {
+        /// Name of the locker
+        locker_name: String,
+        /// Mint of the NFT
+        mint: Pubkey,
+        /// Amount, 1 for an NFT
+        #[arg(long, default_value_t = 1)]
+        amount: u32,
+    },
+    /// Lock funds until a unix timestamp
+    LockTime {
+        /// Name of the locker
+        locker_name: String,
+        /// Amount in lamports, or token base units with --mint
+        amount: u32,
+        /// Unix timestamp from which the funds can be unlocked
+        strike_time: u32,
+        /// Mint of the SPL token, SOL when not set
+        #[arg(long)]
+        mint: Option<Pubkey>,
+        /// Id of another lock on the same funds, the funds unlock when either lock unlocks
+        #[arg(long)]
+        join: Option<u8>,
+    },
+    /// Lock funds until the oracle price reaches a strike price
+    LockPrice {
+        /// Name of the locker
+        locker_name: String,
+        /// Amount in lamports, or token base units with --mint
+        amount: u32,
+        /// Oracle price from which the funds can be unlocked
+        strike_price: u32,
+        /// Alias of the price feed the strike price is read from
+        #[arg(long)]
+        price_feed: String,
+        /// Mint of the SPL token, SOL when not set
+        #[arg(long)]
+        mint: Option<Pubkey>,
+        /// Id of another lock on the same funds, the funds unlock when either lock unlocks
+        #[arg(long)]
+        join: Option<u8>,
+    },
+    /// Show a locker with its balances and all locks with their evaluated status
+    Show {
+        /// Name of the locker
+        locker_name: String,
+        /// Authority of the locker, defaults to the keypair
+        #[arg(long)]
+        authority: Option<Pubkey>,
+        /// Alias of the price feed to evaluate price locks against
+        #[arg(long)]
+        price_feed: Option<String>,
+    },
+    /// Unlock all eligible locks of lockers of the keypair (all lockers when no names are given)
+    Crank {
+        /// Names of the lockers
+        locker_names: Vec<String>,
+        /// Alias of the price feed to evaluate price locks against
+        #[arg(long)]
+        price_feed: Option<String>,
+    },
+    /// Withdraw unlocked SOL (lamports) or SPL tokens (base units, with --mint) from a locker
+    Withdraw {
+        /// Name of the locker
+        locker_name: String,
+        /// Amount in lamports, or token base units with --mint
+        #[arg(required_unless_present = "max")]
+        amount: Option<u32>,
+        /// Withdraw everything that is withdrawable
+        #[arg(long, conflicts_with = "amount")]
+        max: bool,
+        /// Mint of the SPL token, SOL when not set
+        #[arg(long)]
+        mint: Option<Pubkey>,
+        /// Withdraw wrapped SOL of the locker as SOL
+        #[arg(long, conflicts_with = "mint")]
+        unwrap: bool,
+        /// Wallet on the withdrawal allowlist that receives the funds, the keypair when not set
+        #[arg(long, conflicts_with = "unwrap")]
+        to: Option<Pubkey>,
+    },
+    /// Set the cooldown between requesting and executing a withdrawal, 0 to withdraw directly
+    SetCooldown {
+        /// Name of the locker
+        locker_name: String,
+        /// Cooldown in seconds
+        cooldown_secs: u32,
+    },
+    /// Request a withdrawal from a locker with a cooldown, executable with execute-withdrawal once the cooldown passed
+    RequestWithdrawal {
+        /// Name of the locker
+        locker_name: String,
+        /// Amount in lamports, or token base units with --mint
+        #[arg(required_unless_present = "max")]
+        amount: Option<u32>,
+        /// Request everything that is withdrawable
+        #[arg(long, conflicts_with = "amount")]
+        max: bool,
+        /// Mint of the SPL token, SOL when not set
+        #[arg(long)]
+        mint: Option<Pubkey>,
+        /// Wallet on the withdrawal allowlist that receives the funds, the keypair when not set
+        #[arg(long)]
+        to: Option<Pubkey>,
+    },
+    /// Execute the withdrawal request once its cooldown passed
+    ExecuteWithdrawal {
+        /// Name of the locker
+        locker_name: String,
+    },
+    /// Cancel the withdrawal request, as the authority or as a guardian with --authority
+    CancelWithdrawal {
+        /// Name of the locker
+        locker_name: String,
+        /// Authority of the locker, when the keypair signs as a guardian
+        #[arg(long)]
+        authority: Option<Pubkey>,
+    },
+    /// Propose the wallets funds can be withdrawn to, taking effect after 48 hours, without wallets the restriction is removed
+    SetAllowlist {
+        /// Name of the locker
+        locker_name: String,
+        /// Wallets funds can be withdrawn to
+        #[arg(long = "destination")]
+        destinations: Vec<Pubkey>,
+    },
+    /// Cancel the pending change of the withdrawal allowlist
+    CancelAllowlistChange {
+        /// Name of the locker
+        locker_name: String,
+    },
+    /// Claim the funds of an unlocked lock that names the keypair as beneficiary
+    Claim {
+        /// Name of the locker
+        locker_name: String,
+        /// Id of the lock with the beneficiary
+        lock_id: u8,
+        /// Authority of the locker
+        #[arg(long)]
+        authority: Pubkey,
+        /// Amount in lamports, or token base units with --mint
+        #[arg(required_unless_present = "max")]
+        amount: Option<u32>,
+        /// Claim everything that is claimable
+        #[arg(long, conflicts_with = "amount")]
+        max: bool,
+        /// Mint of the SPL token of the lock, SOL when not set
+        #[arg(long)]
+        mint: Option<Pubkey>,
+    },
+    /// Exit a time lock early, forfeiting the penalty of its early exit to the destination of the lock
+    ExitEarly {
+        /// Name of the locker
+        locker_name: String,
+        /// Id of the time lock with an early exit
+        lock_id: u8,
+    },
+    /// Create the program config and the treasury, the keypair must be the upgrade authority of the program and becomes the admin
+    InitConfig {
+        #[command(flatten)]
+        fees: FeeArgs,
+    },
+    /// Change the protocol fees or the admin, signed by the admin
+    UpdateConfig {
+        #[command(flatten)]
+        fees: FeeArgs,
+        /// Hand the config over to a new admin
+        #[arg(long)]
+        new_admin: Option<Pubkey>,
+    },
+    /// Pause new deposits, new locks and price unlocks in an emergency, signed by the admin
+    Pause,
+    /// Resume the program after a pause, signed by the admin
+    Resume,
+    /// Halt price unlocks while a feed moved too much within a window, signed by the admin
+    SetCircuitBreaker {
+        /// Largest price move in basis points within the window, 0 disables the circuit breaker
+        #[arg(long)]
+        max_price_move_bps: u16,
+        /// Length of the window in seconds
+        #[arg(long, default_value_t = 300)]
+        window_secs: u32,
+    },
+    /// Show the program config with the protocol fees, the pause flag, the circuit breaker and the treasury
+    Config,
+    /// Withdraw collected fees of SOL (lamports) or SPL tokens (base units, with --mint) from the treasury, signed by the admin
+    WithdrawFees {
+        /// Amount in lamports, or token base units with --mint
+        #[arg(required_unless_present = "max")]
+        amount: Option<u64>,
+        /// Withdraw all collected fees of the asset
+        #[arg(long, conflicts_with = "amount")]
+        max: bool,
+        /// Mint of the SPL token, SOL when not set
+        #[arg(long)]
+        mint: Option<Pubkey>,
+    },
+    /// Set the guardians that can approve an early release of a lock, without guardians they are removed
+    SetGuardians {
+        /// Name of the locker
+        locker_name: String,
+        /// Wallets of the guardians
+        #[arg(long = "guardian")]
+        guardians: Vec<Pubkey>,
+        /// Number of guardians that must approve a release
+        #[arg(long, default_value_t = 1)]
+        threshold: u8,
+        /// Penalty of an early release in basis points of the released funds
+        #[arg(long, default_value_t = 0)]
+        penalty_bps: u16,
+        /// Wallet that receives the penalties, e.g. a treasury or a charity
+        #[arg(long, required_unless_present = "no_guardians")]
+        penalty_destination: Option<Pubkey>,
+        /// Remove the guardians
+        #[arg(long, conflicts_with_all = ["guardians", "penalty_destination"])]
+        no_guardians: bool,
+    },
+    /// Release a lock before its condition is met, signed by the guardians
+    GuardianRelease {
+        /// Name of the locker
+        locker_name: String,
+        /// Id of the lock
+        lock_id: u8,
+        /// Keypair files of the approving guardians
+        #[arg(long = "guardian-keypair", required = true)]
+        guardian_keypairs: Vec<PathBuf>,
+    },
+}
+
+
+// Conditions of a lock on a deposit, the deposit unlocks as soon as one of them is met
+// Not required as a group, an NFT can be deposited without conditions
+#[derive(Args, Debug)]
+#[group(multiple = true)]
+struct ConditionArgs {
+    /// Unix timestamp from which the deposit can be unlocked
+    #[arg(long)]
+    strike_time: Option<u32>,
+    /// Price from which the deposit can be unlocked
+    #[arg(long, requires = "strike_price_feed")]
+    strike_price: Option<u32>,
+    /// Alias of the price feed the strike price is read from
+    #[arg(long, requires = "strike_price")]
+    strike_price_feed: Option<String>,
+    /// Seconds after the deposit from which it can be unlocked, e.g. 7776000 for 90 days
+    #[arg(long)]
+    after: Option<u32>,
+    /// Seconds without activity of the authority after which the beneficiary inherits the deposit
+    #[arg(long, requires = "beneficiary")]
+    inactivity: Option<u32>,
+    /// Let the authority exit the time lock early, forfeiting this penalty (basis points) that decays to zero at the strike time
+    #[arg(long, requires_all = ["strike_time", "exit_destination"])]
+    early_exit_bps: Option<u16>,
+    /// Wallet that receives the penalty of an early exit
+    #[arg(long, requires = "early_exit_bps")]
+    exit_destination: Option<Pubkey>,
+}
+
+impl ConditionArgs {
+    fn to_conditions(&self, program_id: &Pubkey) -> Vec<LockCondition> {
+        let time = self.strike_time.map(|strike_time| match (self.early_exit_bps, self.exit_destination) {
+            (Some(max_penalty_bps), Some(penalty_destination)) => LockCondition::TimeWithEarlyExit { strike_time, max_penalty_bps, penalty_destination },
+            _ => LockCondition::Time { strike_time },
+        });
+        let price = self.strike_price.zip(self.strike_price_feed.as_deref()).map(|(strike_price, alias)| {
+            let (price_feed, _bump) = pricelocker_client::find_pricefeed_address(program_id, alias);
+            LockCondition::Price { strike_price, price_feed }
+        });
+        let after = self.after.map(|duration| LockCondition::AfterDeposit { duration });
+        let inactivity = self.inactivity.map(|period| LockCondition::Inactivity { period });
+        [time, price, after, inactivity].into_iter().flatten().collect()
+    }
+
+    // The conditions of a command that always locks, which needs at least one
+    fn to_required_conditions(&self, program_id: &Pubkey) -> Result<Vec<LockCondition>> {
+        let conditions = self.to_conditions(program_id);
+        if conditions.is_empty() {
+            return Err(anyhow!("at least one of --strike-time, --strike-price, --after or --inactivity is required"));
+        }
+        Ok(conditions)
+    }
+}
+
+
+// Protocol fees in basis points, at most 1000 each
+#[derive(Args, Debug)]
+struct FeeArgs {
+    /// Fee of every deposit
+    #[arg(long, default_value_t = 0)]
+    deposit_fee_bps: u16,
+    /// Fee of the funds released by an early exit or a guardian release
+    #[arg(long, default_value_t = 0)]
+    early_exit_fee_bps: u16,
+    /// Fee of staking rewards, recorded but not charged until staking rewards can be withdrawn
+    #[arg(long, default_value_t = 0)]
+    staking_reward_fee_bps: u16,
+}
+
+impl FeeArgs {
+    fn to_fees(&self) -> Fees {
+        Fees { deposit_bps: self.deposit_fee_bps, early_exit_bps: self.early_exit_fee_bps, staking_reward_bps: self.staking_reward_fee_bps }
+    }
+}
+
+
+// Connection and signer shared by all commands
+pub struct Session {
+    pub rpc: RpcClient,
+    pub payer: Keypair,
+    pub program_id: Pubkey,
+}
+
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+
+    let keypair_path = expand_home(&cli.keypair);
+    let payer = read_keypair_file(&keypair_path)
+        .map_err(|err| anyhow!("reading keypair {}: {err}", keypair_path.display()))?;
+
+    let session = Session {
+        rpc: RpcClient::new_with_commitment(cli.url.clone(), CommitmentConfig::confirmed()),
+        payer,
+        program_id: cli.program_id,
+    };
+
+    let report = match cli.command {
+        Command::CreateLocker { locker_name } => commands::create_locker(&session, &locker_name)?,
+        Command::MigrateLocker { locker_name, price_feed } => commands::migrate_locker(&session, &locker_name, price_feed.as_deref())?,
+        Command::Heartbeat { locker_name } => commands::heartbeat(&session, &locker_name)?,
+        Command::RevokeInheritance { locker_name, lock_id } => commands::revoke_inheritance(&session, &locker_name, lock_id)?,
+        Command::Deposit { locker_name, amount, mint, wrap, price_feed } => commands::deposit(&session, &locker_name, amount, mint, wrap, price_feed.as_deref())?,
+        Command::DepositLock { locker_name, amount, conditions, mint, price_feed, beneficiary } => commands::deposit_lock(&session, &locker_name, amount, &conditions.to_required_conditions(&session.program_id)?, mint, price_feed.as_deref(), beneficiary)?,
+        Command::LockLot { locker_name, lot_id, amount, conditions, mint, beneficiary } => commands::lock_lot(&session, &locker_name, lot_id, amount, &conditions.to_required_conditions(&session.program_id)?, mint, beneficiary)?,
+        Command::DepositNft { locker_name, mint, amount, conditions, beneficiary } => commands::deposit_nft(&session, &locker_name, &mint, amount, &conditions.to_conditions(&session.program_id), beneficiary)?,
+        Command::WithdrawNft { locker_name, mint, amount } => commands::withdraw_nft(&session, &locker_name, &mint, amount)?,
+        Command::LockTime { locker_name, amount, strike_time, mint, join } => commands::lock_time(&session, &locker_name, amount, strike_time, mint, join)?,
+        Command::LockPrice { locker_name, amount, strike_price, price_feed, mint, join } => commands::lock_price(&session, &locker_name, amount, strike_price, &price_feed, mint, join)?,
+        Command::Show { locker_name, authority, price_feed } => commands::show(&session, &locker_name, authority, price_feed.as_deref())?,
+        Command::Crank { locker_names, price_feed } => commands::crank(&session, &locker_names, price_feed.as_deref())?,
+        Command::Withdraw { locker_name, amount, max, mint, unwrap, to } => {
+            let amount = if max { pricelocker_client::WITHDRAW_MAX } else { amount.ok_or_else(|| anyhow!("missing amount"))? };
+            commands::withdraw(&session, &locker_name, amount, mint, unwrap, to)?
+        },
+        Command::SetCooldown { locker_name, cooldown_secs } => commands::set_cooldown(&session, &locker_name, cooldown_secs)?,
+        Command::RequestWithdrawal { locker_name, amount, max, mint, to } => {
+            let amount = if max { pricelocker_client::WITHDRAW_MAX } else { amount.ok_or_else(|| anyhow!("missing amount"))? };
+            commands::request_withdrawal(&session, &locker_name, amount, mint, to)?
+        },
+        Command::ExecuteWithdrawal { locker_name } => commands::execute_withdrawal(&session, &locker_name)?,
+        Command::CancelWithdrawal { locker_name, authority } => commands::cancel_withdrawal(&session, &locker_name, authority)?,
+        Command::SetAllowlist { locker_name, destinations } => commands::set_allowlist(&session, &locker_name, &destinations)?,
+        Command::CancelAllowlistChange { locker_name } => commands::cancel_allowlist_change(&session, &locker_name)?,
+        Command::Claim { locker_name, lock_id, authority, amount, max, mint } => {
+            let amount = if max { pricelocker_client::WITHDRAW_MAX } else { amount.ok_or_else(|| anyhow!("missing amount"))? };
+            commands::claim(&session, &locker_name, lock_id, &authority, amount, mint)?
+        },
+        Command::ExitEarly { locker_name, lock_id } => commands::exit_early(&session, &locker_name, lock_id)?,
+        Command::InitConfig { fees } => commands::init_config(&session, fees.to_fees())?,
+        Command::UpdateConfig { fees, new_admin } => commands::update_config(&session, fees.to_fees(), new_admin)?,
+        Command::Pause => commands::set_paused(&session, true)?,
+        Command::Resume => commands::set_paused(&session, false)?,
+        Command::SetCircuitBreaker { max_price_move_bps, window_secs } => {
+            commands::set_circuit_breaker(&session, CircuitBreaker { max_price_move_bps, window_secs })?
+        },
+        Command::Config => commands::show_config(&session)?,
+        Command::WithdrawFees { amount, max: _, mint } => commands::withdraw_fees(&session, amount, mint)?,
+        Command::SetGuardians { locker_name, guardians, threshold, penalty_bps, penalty_destination, no_guardians: _ } => {
+            // Removing the guardians sends no guardians, the destination is ignored then
+            let penalty_destination = penalty_destination.unwrap_or_default();
+            commands::set_guardians(&session, &locker_name, &guardians, threshold, penalty_bps, &penalty_destination)?
+        },
+        Command::GuardianRelease { locker_name, lock_id, guardian_keypairs } => {
+            let guardians = guardian_keypairs.iter().map(|path| {
+                let path = expand_home(path);
+                read_keypair_file(&path).map_err(|err| anyhow!("reading keypair {}: {err}", path.display()))
+            }).collect::<Result<Vec<_>>>()?;
+            commands::guardian_release(&session, &locker_name, lock_id, &guardians)?
+        },
+    };
+
+    output::print(&report, cli.output)
+}
+
+
+// Expand a leading ~ in a path to the home directory, like the solana cli does
+fn expand_home(path: &std::path::Path) -> PathBuf {
+    match (path.strip_prefix("~"), std::env::var_os("HOME")) {
+        (Ok(rest), Some(home)) => PathBuf::from(home).join(rest),
+        _ => path.to_path_buf(),
+    }
+}
@@ -0,0 +1,105 @@
+use anyhow::Result;
+
+use crate::view::{ConfigView, CrankView, LockerView, Report, TransactionView};
+use crate::OutputFormat;
+
+
+pub fn print(report: &Report, format: OutputFormat) -> Result<()> {
+    match format {
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(report)?),
+        OutputFormat::Table => match report {
+            Report::Transaction(transaction) => print_transaction(transaction),
+            Report::Locker(locker) => print_locker(locker),
+            Report::Crank(crank) => print_crank(crank),
+            Report::Config(config) => print_config(config),
+        },
+    }
+    Ok(())
+}
+
+
+fn print_transaction(transaction: &TransactionView) {
+    println!("{:<10} {}", "Action", transaction.action);
+    println!("{:<10} {}", "Locker", transaction.locker);
+    println!("{:<10} {}", "Signature", transaction.signature);
+}
+
+fn print_locker(locker: &LockerView) {
+    println!("{:<10} {}", "Locker", locker.address);
+    println!("{:<10} {}", "Name", locker.name);
+    println!("{:<10} {}", "Authority", locker.authority);
+    if let Some(request) = &locker.withdrawal_request {
+        println!("{:<10} {} of {} to {}, executable at {}", "Requested", request.amount, request.asset, request.destination, request.executable_ts);
+    }
+    println!();
+
+    println!("{:<44} {:>14} {:>14} {:>14} {:>14}", "ASSET", "TOTAL", "LOCKED", "UNLOCKABLE", "WITHDRAWABLE");
+    for asset in &locker.assets {
+        println!("{:<44} {:>14} {:>14} {:>14} {:>14}", asset.asset, asset.total, asset.locked, asset.unlockable_now, asset.withdrawable);
+    }
+    println!();
+
+    if locker.lots.is_empty() {
+        println!("No lots");
+    } else {
+        println!("{:>5} {:<44} {:>12} {:>12} {:>12} {:>12}", "LOT", "ASSET", "AMOUNT", "LOCKED", "DEPOSITED", "PRICE");
+        for lot in &locker.lots {
+            let price = lot.deposit_price.map_or("-".to_string(), |price| price.to_string());
+            println!("{:>5} {:<44} {:>12} {:>12} {:>12} {:>12}", lot.id, lot.asset, lot.amount, lot.locked, lot.deposit_ts, price);
+        }
+    }
+    println!();
+
+    if locker.locks.is_empty() {
+        println!("No locks");
+        return;
+    }
+
+    println!("{:>3} {:<6} {:<44} {:>12} {:>12} {:>5} {:>5} {:<10} {:>7} {:<44}", "ID", "KIND", "ASSET", "AMOUNT", "STRIKE", "JOIN", "LOT", "STATE", "PENALTY", "BENEFICIARY");
+    for lock in &locker.locks {
+        let join = lock.join.map_or("-".to_string(), |join| join.to_string());
+        let lot = lock.lot.map_or("-".to_string(), |lot| lot.to_string());
+        // Penalty of an early exit in percent
+        let penalty = lock.exit_penalty_bps.map_or("-".to_string(), format_bps);
+        let beneficiary = lock.beneficiary.as_deref().unwrap_or("-");
+        println!("{:>3} {:<6} {:<44} {:>12} {:>12} {:>5} {:>5} {:<10} {:>7} {:<44}", lock.id, lock.kind, lock.asset, lock.amount, lock.strike, join, lot, lock.state, penalty, beneficiary);
+    }
+}
+
+fn print_crank(crank: &CrankView) {
+    if crank.lockers.is_empty() {
+        println!("No unlockable locks");
+        return;
+    }
+
+    println!("{:<44} LOCKS", "LOCKER");
+    for locker in &crank.lockers {
+        println!("{:<44} {:?}", locker.locker, locker.lock_ids);
+    }
+    println!();
+
+    for signature in &crank.signatures {
+        println!("{:<10} {}", "Signature", signature);
+    }
+}
+
+fn print_config(config: &ConfigView) {
+    println!("{:<18} {}", "Config", config.config);
+    println!("{:<18} {}", "Admin", config.admin);
+    println!("{:<18} {}", "Deposit fee", format_bps(config.deposit_fee_bps));
+    println!("{:<18} {}", "Early exit fee", format_bps(config.early_exit_fee_bps));
+    println!("{:<18} {}", "Staking reward fee", format_bps(config.staking_reward_fee_bps));
+    println!("{:<18} {}", "Paused", config.paused);
+    let circuit_breaker = match config.max_price_move_bps {
+        0 => "disabled".to_string(),
+        max_price_move_bps => format!("{} within {}s", format_bps(max_price_move_bps), config.circuit_breaker_window_secs),
+    };
+    println!("{:<18} {}", "Circuit breaker", circuit_breaker);
+    println!("{:<18} {}", "Treasury", config.treasury);
+    println!("{:<18} {}", "Collected SOL", config.treasury_sol);
+}
+
+// Basis points in percent
+fn format_bps(bps: u16) -> String {
+    format!("{:.2}%", bps as f64 / 100.0)
+}
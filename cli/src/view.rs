@@ -0,0 +1,196 @@
+use anchor_lang::prelude::*;
+use pricelocker::{Lock, Locker, PriceReading};
+use pricelocker::accounting::lot_locked_balance;
+use pricelocker_client::{early_exit_penalty_bps, is_unlockable, withdrawable_balance};
+use serde::Serialize;
+
+
+// Serializable results of the commands, printed as a table or as JSON
+#[derive(Serialize, Debug)]
+#[serde(untagged)]
+pub enum Report {
+    Transaction(TransactionView),
+    Locker(LockerView),
+    Crank(CrankView),
+    Config(ConfigView),
+}
+
+#[derive(Serialize, Debug)]
+pub struct TransactionView {
+    pub action: String,
+    pub locker: String,
+    pub signature: String,
+}
+
+#[derive(Serialize, Debug)]
+pub struct LockerView {
+    pub address: String,
+    pub authority: String,
+    pub name: String,
+    pub assets: Vec<AssetView>,
+    pub locks: Vec<LockView>,
+    pub lots: Vec<LotView>,
+    // Withdrawal waiting for its cooldown
+    pub withdrawal_request: Option<WithdrawalRequestView>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct WithdrawalRequestView {
+    pub asset: String,
+    pub amount: u64,
+    pub destination: String,
+    pub executable_ts: u32,
+}
+
+#[derive(Serialize, Debug)]
+pub struct AssetView {
+    // "SOL" or the token mint
+    pub asset: String,
+    pub total: u64,
+    pub locked: u64,
+    pub unlockable_now: u64,
+    pub withdrawable: u64,
+}
+
+#[derive(Serialize, Debug)]
+pub struct LockView {
+    pub id: u8,
+    // "time", "price" or "inactivity"
+    pub kind: &'static str,
+    pub asset: String,
+    pub amount: u32,
+    // Strike time (unix timestamp) of a time lock, or strike price of a price lock
+    pub strike: u32,
+    pub join: Option<u8>,
+    pub lot: Option<u16>,
+    // "locked", "unlockable" or "unlocked"
+    pub state: &'static str,
+    // Wallet that claims the funds once unlocked, None when released to the authority
+    pub beneficiary: Option<String>,
+    // Current penalty in basis points of exiting the lock early, None when it can't be exited early
+    pub exit_penalty_bps: Option<u16>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct LotView {
+    pub id: u16,
+    pub asset: String,
+    pub amount: u32,
+    pub locked: u64,
+    pub deposit_ts: u32,
+    // Oracle price at the deposit, if it was recorded
+    pub deposit_price: Option<u32>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct ConfigView {
+    pub config: String,
+    pub admin: String,
+    pub deposit_fee_bps: u16,
+    pub early_exit_fee_bps: u16,
+    pub staking_reward_fee_bps: u16,
+    pub paused: bool,
+    // 0 when the circuit breaker is disabled
+    pub max_price_move_bps: u16,
+    pub circuit_breaker_window_secs: u32,
+    pub treasury: String,
+    // Collected SOL fees in lamports, without the rent-exempt minimum
+    pub treasury_sol: u64,
+}
+
+#[derive(Serialize, Debug)]
+pub struct CrankView {
+    pub lockers: Vec<CrankedLockerView>,
+    // One signature per transaction, empty when there was nothing to unlock
+    pub signatures: Vec<String>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct CrankedLockerView {
+    pub locker: String,
+    pub lock_ids: Vec<u8>,
+}
+
+
+pub fn asset_name(token_mint: &Option<Pubkey>) -> String {
+    match token_mint {
+        Some(token_mint) => token_mint.to_string(),
+        None => "SOL".to_string(),
+    }
+}
+
+
+// Evaluate all locks and balances of a locker with the same rules as the program
+// balances holds the deposited amount per asset (None for SOL)
+pub fn locker_view(address: &Pubkey, name: &str, locker: &Locker, balances: &[(Option<Pubkey>, u64)], time_now: u32, price: Option<PriceReading>) -> LockerView {
+    let locks: Vec<LockView> = locker.locks.iter().map(|lock_item| {
+        let (kind, strike) = match lock_item {
+            Lock::TimeLock { strike_time, .. } => ("time", *strike_time),
+            Lock::PriceLock { strike_price, .. } => ("price", *strike_price),
+            // The time the lock unlocks unless the authority checks in before
+            Lock::Inactivity { period, .. } => ("inactivity", locker.last_activity_ts.saturating_add(*period)),
+        };
+        let state = if !lock_item.is_locked() {
+            "unlocked"
+        } else if is_unlockable(lock_item, time_now, price, locker.last_activity_ts) {
+            "unlockable"
+        } else {
+            "locked"
+        };
+
+        LockView {
+            id: lock_item.id(),
+            kind,
+            asset: asset_name(&lock_item.token_mint()),
+            amount: lock_item.amount(),
+            strike,
+            join: lock_item.join(),
+            lot: lock_item.lot(),
+            state,
+            beneficiary: lock_item.beneficiary().map(|beneficiary| beneficiary.to_string()),
+            exit_penalty_bps: early_exit_penalty_bps(lock_item, time_now),
+        }
+    }).collect();
+
+    let assets = balances.iter().map(|(token_mint, total)| {
+        let locked = locker.get_locked_balance(token_mint);
+        // Joined locks lock the same funds, so the unlockable balance never exceeds the locked balance
+        let unlockable_now = locker.locks.iter()
+            .filter(|lock_item| lock_item.token_mint() == *token_mint && is_unlockable(lock_item, time_now, price, locker.last_activity_ts))
+            .map(|lock_item| lock_item.amount() as u64)
+            .sum::<u64>()
+            .min(locked);
+
+        AssetView {
+            asset: asset_name(token_mint),
+            total: *total,
+            locked,
+            unlockable_now,
+            withdrawable: withdrawable_balance(locker, token_mint, *total),
+        }
+    }).collect();
+
+    let lots = locker.lots.iter().map(|lot| LotView {
+        id: lot.id,
+        asset: asset_name(&lot.token_mint),
+        amount: lot.amount,
+        locked: lot_locked_balance(&locker.locks, lot.id),
+        deposit_ts: lot.deposit_ts,
+        deposit_price: lot.deposit_price,
+    }).collect();
+
+    LockerView {
+        address: address.to_string(),
+        authority: locker.authority.to_string(),
+        name: name.to_string(),
+        assets,
+        locks,
+        lots,
+        withdrawal_request: locker.withdrawal_request.map(|request| WithdrawalRequestView {
+            asset: asset_name(&request.token_mint),
+            amount: request.amount,
+            destination: request.destination.to_string(),
+            executable_ts: request.executable_ts,
+        }),
+    }
+}
@@ -0,0 +1,23 @@
+use anchor_lang::prelude::*;
+use pricelocker::{Locker, Pricefeedaccount, ProgramConfig};
+
+
+// Decode the raw data of program accounts, the discriminator is checked
+
+
+pub fn decode_locker(data: &[u8]) -> Result<Locker> {
+    Locker::try_deserialize(&mut &data[..])
+}
+
+pub fn decode_pricefeed_account(data: &[u8]) -> Result<Pricefeedaccount> {
+    Pricefeedaccount::try_deserialize(&mut &data[..])
+}
+
+pub fn decode_program_config(data: &[u8]) -> Result<ProgramConfig> {
+    ProgramConfig::try_deserialize(&mut &data[..])
+}
+
+// Decode the data returned by a view instruction (e.g. get_locker_status) in a simulated transaction
+pub fn decode_return_data<T: AnchorDeserialize>(data: &[u8]) -> Result<T> {
+    T::try_from_slice(data).map_err(Into::into)
+}
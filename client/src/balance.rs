@@ -0,0 +1,46 @@
+use anchor_lang::prelude::*;
+use pricelocker::accounting;
+use pricelocker::{Lock, Locker, PriceReading};
+use pyth_sdk_solana::state::load_price_account;
+
+
+// Maximum age of the Pyth price in seconds, the same as the program accepts on unlock
+pub const MAX_PRICE_AGE: u64 = 60;
+
+
+// Off-chain evaluation of locks and balances, with the same rules as the program
+
+
+// Whether a locked lock can be unlocked at the given time and (fresh) price
+//  a time lock unlocks when the cluster time reached the strike time
+//  a price lock unlocks when the price of the price feed it was created on reached the strike price
+//  an inactivity lock unlocks when the authority was inactive for the period, see Locker::last_activity_ts
+pub fn is_unlockable(lock_item: &Lock, time_now: u32, price: Option<PriceReading>, last_activity_ts: u32) -> bool {
+    accounting::is_unlockable(lock_item, time_now, price, last_activity_ts)
+}
+
+// Penalty in basis points the authority forfeits when it exits a locked lock at the given time,
+//  None when the lock can't be exited early
+pub fn early_exit_penalty_bps(lock_item: &Lock, time_now: u32) -> Option<u16> {
+    accounting::lock_exit_penalty_bps(lock_item, time_now)
+}
+
+// Read the price from the data of a Pyth price account the same way the program does:
+//  without confidence interval, max 60 seconds old, None when the price is stale or not trading
+pub fn pyth_price(pyth_price_account: &Pubkey, data: &[u8], time_now: i64) -> Option<u32> {
+    load_price_account(data)
+        .ok()?
+        .to_price_feed(pyth_price_account)
+        .get_price_no_older_than(time_now, MAX_PRICE_AGE)
+        .map(|price| price.price as u32)
+}
+
+// SOL deposited in a locker, without the rent-exempt minimum of the locker account
+pub fn sol_balance(lamports: u64, data_len: usize, rent: &Rent) -> u64 {
+    lamports.saturating_sub(rent.minimum_balance(data_len))
+}
+
+// Balance of an asset (None for SOL) that can be withdrawn from the locker without unlocking any lock
+pub fn withdrawable_balance(locker: &Locker, token_mint: &Option<Pubkey>, total: u64) -> u64 {
+    accounting::withdrawable_balance(total, &locker.locks, token_mint)
+}
@@ -0,0 +1,703 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::{stake, system_program, sysvar};
+use anchor_lang::InstructionData;
+use anchor_spl::associated_token;
+use pricelocker::{accounts, instruction, CircuitBreaker, Fees, LockCondition};
+
+use crate::pda::{find_config_address, find_locker_address, find_program_data_address, find_pricefeed_address, find_stake_account_address, find_treasury_address, locker_token_address, wallet_token_address, TokenAsset};
+
+
+// Builders for every instruction of the program, deriving the program derived accounts from the given seeds
+// The program id is passed explicitly, so the builders also work for a program deployed at another address (e.g. localnet)
+
+
+// Accounts of a registered price feed, needed to evaluate price locks
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PriceFeedAccounts {
+    // Price feed account of the locker program
+    pub price_feed: Pubkey,
+    // Pyth price account the price feed account points to
+    pub pyth_price_account: Pubkey,
+}
+
+// A locker to crank, with the price feed accounts when it has price locks to unlock
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CrankLocker {
+    pub locker: Pubkey,
+    pub price_feed: Option<PriceFeedAccounts>,
+}
+
+
+pub fn create_pricefeed_account(program_id: &Pubkey, authority: &Pubkey, pricefeed_alias: &str, pricefeed_id: &Pubkey) -> Instruction {
+    let accounts = accounts::CreatePricefeedAccount {
+        authority: *authority,
+        price_feed: find_pricefeed_address(program_id, pricefeed_alias).0,
+        system_program: system_program::ID,
+    };
+    let data = instruction::CreatePricefeedAccount {
+        pricefeed_alias: pricefeed_alias.to_string(),
+        pricefeed_id_string: pricefeed_id.to_string(),
+    };
+    build(program_id, accounts.to_account_metas(None), data.data())
+}
+
+pub fn create_new_locker(program_id: &Pubkey, authority: &Pubkey, locker_name: &str) -> Instruction {
+    let accounts = accounts::CreateNewLocker {
+        authority: *authority,
+        locker: find_locker_address(program_id, authority, locker_name).0,
+        system_program: system_program::ID,
+    };
+    let data = instruction::CreateNewLocker {
+        _locker_name: locker_name.to_string(),
+    };
+    build(program_id, accounts.to_account_metas(None), data.data())
+}
+
+// Migrate a locker created before lots were added, its price locks are bound to the price feed (the Pricefeedaccount address)
+pub fn migrate_locker(program_id: &Pubkey, authority: &Pubkey, locker_name: &str, price_feed: Option<&Pubkey>) -> Instruction {
+    let accounts = accounts::MigrateLocker {
+        authority: *authority,
+        locker: find_locker_address(program_id, authority, locker_name).0,
+        price_feed_optional: price_feed.copied(),
+        system_program: system_program::ID,
+    };
+    let data = instruction::MigrateLocker {
+        _locker_name: locker_name.to_string(),
+    };
+    build(program_id, accounts.to_account_metas(None), data.data())
+}
+
+// Deposit SOL (token None) or SPL tokens into the locker, recorded as a lot with the oracle price when a price feed is passed
+// Tokens are transferred from the associated token account of the authority
+pub fn deposit_funds(program_id: &Pubkey, authority: &Pubkey, locker_name: &str, amount: u32, token: Option<&TokenAsset>, price_feed: Option<&PriceFeedAccounts>) -> Instruction {
+    build_deposit_funds(program_id, authority, locker_name, amount, token, price_feed, false)
+}
+
+// Deposit SOL of the authority wrapped into the native mint token account of the locker, locked like any token
+pub fn deposit_wrapped_sol(program_id: &Pubkey, authority: &Pubkey, locker_name: &str, amount: u32, price_feed: Option<&PriceFeedAccounts>) -> Instruction {
+    build_deposit_funds(program_id, authority, locker_name, amount, Some(&TokenAsset::wrapped_sol()), price_feed, true)
+}
+
+fn build_deposit_funds(program_id: &Pubkey, authority: &Pubkey, locker_name: &str, amount: u32, token: Option<&TokenAsset>, price_feed: Option<&PriceFeedAccounts>, wrap_sol: bool) -> Instruction {
+    let locker = find_locker_address(program_id, authority, locker_name).0;
+    let treasury = find_treasury_address(program_id).0;
+    let accounts = accounts::DepositFunds {
+        authority: *authority,
+        locker,
+        system_program: system_program::ID,
+        token_mint_account_optional: token.map(|token| token.mint),
+        // Wrapped SOL is transferred from the authority itself, not from a token account
+        authority_token_account_optional: token.filter(|_| !wrap_sol).map(|token| wallet_token_address(authority, token)),
+        token_account_optional: token.map(|token| locker_token_address(&locker, token)),
+        token_program_optional: token.map(|token| token.token_program),
+        associated_token_program_optional: token.map(|_| associated_token::ID),
+        config: find_config_address(program_id).0,
+        treasury,
+        treasury_token_account_optional: token.map(|token| wallet_token_address(&treasury, token)),
+        price_feed_optional: price_feed.map(|price_feed| price_feed.price_feed),
+        pyth_price_account_optional: price_feed.map(|price_feed| price_feed.pyth_price_account),
+    };
+    let data = instruction::DepositFunds {
+        locker_name: locker_name.to_string(),
+        amount,
+        wrap_sol,
+    };
+    build(program_id, accounts.to_account_metas(None), data.data())
+}
+
+// Deposit SOL (token None) or SPL tokens and lock the deposit until any of the conditions is met
+// With a beneficiary, only the beneficiary can claim the deposit once unlocked
+#[allow(clippy::too_many_arguments)]
+pub fn deposit_and_lock(program_id: &Pubkey, authority: &Pubkey, locker_name: &str, amount: u32, token: Option<&TokenAsset>, price_feed: Option<&PriceFeedAccounts>, conditions: &[LockCondition], beneficiary: Option<Pubkey>) -> Instruction {
+    let locker = find_locker_address(program_id, authority, locker_name).0;
+    let treasury = find_treasury_address(program_id).0;
+    let accounts = accounts::DepositAndLock {
+        authority: *authority,
+        locker,
+        system_program: system_program::ID,
+        token_mint_account_optional: token.map(|token| token.mint),
+        authority_token_account_optional: token.map(|token| wallet_token_address(authority, token)),
+        token_account_optional: token.map(|token| locker_token_address(&locker, token)),
+        token_program_optional: token.map(|token| token.token_program),
+        associated_token_program_optional: token.map(|_| associated_token::ID),
+        config: find_config_address(program_id).0,
+        treasury,
+        treasury_token_account_optional: token.map(|token| wallet_token_address(&treasury, token)),
+        price_feed_optional: price_feed.map(|price_feed| price_feed.price_feed),
+        pyth_price_account_optional: price_feed.map(|price_feed| price_feed.pyth_price_account),
+    };
+    let data = instruction::DepositAndLock {
+        locker_name: locker_name.to_string(),
+        amount,
+        conditions: conditions.to_vec(),
+        beneficiary,
+    };
+    build(program_id, accounts.to_account_metas(None), data.data())
+}
+
+// Lock an amount of a lot until any of the conditions is met, token is the asset of the lot (None for SOL)
+#[allow(clippy::too_many_arguments)]
+pub fn lock_lot(program_id: &Pubkey, authority: &Pubkey, locker_name: &str, lot_id: u16, amount: u32, token: Option<&TokenAsset>, conditions: &[LockCondition], beneficiary: Option<Pubkey>) -> Instruction {
+    let locker = find_locker_address(program_id, authority, locker_name).0;
+    let accounts = accounts::LockLot {
+        authority: *authority,
+        locker,
+        system_program: system_program::ID,
+        config: find_config_address(program_id).0,
+        token_account_optional: token.map(|token| locker_token_address(&locker, token)),
+    };
+    let data = instruction::LockLot {
+        _locker_name: locker_name.to_string(),
+        lot_id,
+        amount,
+        conditions: conditions.to_vec(),
+        beneficiary,
+    };
+    build(program_id, accounts.to_account_metas(None), data.data())
+}
+
+// Deposit an NFT or an amount of a semi-fungible token, locked until any of the conditions is met (unlocked without conditions)
+pub fn deposit_nft(program_id: &Pubkey, authority: &Pubkey, locker_name: &str, nft: &TokenAsset, amount: u32, conditions: &[LockCondition], beneficiary: Option<Pubkey>) -> Instruction {
+    let locker = find_locker_address(program_id, authority, locker_name).0;
+    let accounts = accounts::DepositNft {
+        authority: *authority,
+        locker,
+        system_program: system_program::ID,
+        nft_mint: nft.mint,
+        authority_token_account: wallet_token_address(authority, nft),
+        token_account: locker_token_address(&locker, nft),
+        token_program: nft.token_program,
+        associated_token_program: associated_token::ID,
+        config: find_config_address(program_id).0,
+    };
+    let data = instruction::DepositNft {
+        _locker_name: locker_name.to_string(),
+        amount,
+        conditions: conditions.to_vec(),
+        beneficiary,
+    };
+    build(program_id, accounts.to_account_metas(None), data.data())
+}
+
+// Withdraw an NFT or an amount of a semi-fungible token to the associated token account of the authority
+pub fn withdraw_nft(program_id: &Pubkey, authority: &Pubkey, locker_name: &str, nft: &TokenAsset, amount: u32) -> Instruction {
+    let locker = find_locker_address(program_id, authority, locker_name).0;
+    let accounts = accounts::WithdrawNft {
+        authority: *authority,
+        locker,
+        system_program: system_program::ID,
+        nft_mint: nft.mint,
+        token_account: locker_token_address(&locker, nft),
+        authority_token_account: wallet_token_address(authority, nft),
+        token_program: nft.token_program,
+        associated_token_program: associated_token::ID,
+    };
+    let data = instruction::WithdrawNft {
+        locker_name: locker_name.to_string(),
+        amount,
+    };
+    build(program_id, accounts.to_account_metas(None), data.data())
+}
+
+// Stake the SOL of a locker with a validator, the authority pays for the stake account
+pub fn stake_funds(program_id: &Pubkey, authority: &Pubkey, locker_name: &str, vote_account: &Pubkey, lamports: u64) -> Instruction {
+    let accounts = accounts::CreateAndDelegateStake {
+        payer: *authority,
+        stake_account: find_stake_account_address(program_id, authority, locker_name).0,
+        locker: find_locker_address(program_id, authority, locker_name).0,
+        authority: *authority,
+        vote_account: *vote_account,
+        clock: sysvar::clock::ID,
+        stake_history: sysvar::stake_history::ID,
+        #[allow(deprecated)]
+        stake_config: stake::config::ID,
+        rent: sysvar::rent::ID,
+        system_program: system_program::ID,
+    };
+    let data = instruction::StakeFunds {
+        locker_name: locker_name.to_string(),
+        lamports,
+    };
+    build(program_id, accounts.to_account_metas(None), data.data())
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn price_lock_funds(program_id: &Pubkey, authority: &Pubkey, locker_name: &str, price_feed: &Pubkey, strike_price: u32, payout_amount: u32, token: Option<TokenAsset>, join: Option<u8>) -> Instruction {
+    let locker = find_locker_address(program_id, authority, locker_name).0;
+    let accounts = accounts::PriceLockFunds {
+        authority: *authority,
+        locker,
+        price_feed: *price_feed,
+        system_program: system_program::ID,
+        config: find_config_address(program_id).0,
+        token_account_optional: token.map(|token| locker_token_address(&locker, &token)),
+    };
+    let data = instruction::PriceLockFunds {
+        _locker_name: locker_name.to_string(),
+        strike_price,
+        payout_amount,
+        token_mint: token.map(|token| token.mint),
+        join,
+    };
+    build(program_id, accounts.to_account_metas(None), data.data())
+}
+
+pub fn time_lock_funds(program_id: &Pubkey, authority: &Pubkey, locker_name: &str, strike_time: u32, payout_amount: u32, token: Option<TokenAsset>, join: Option<u8>) -> Instruction {
+    let locker = find_locker_address(program_id, authority, locker_name).0;
+    let accounts = accounts::TimeLockFunds {
+        authority: *authority,
+        locker,
+        system_program: system_program::ID,
+        config: find_config_address(program_id).0,
+        token_account_optional: token.map(|token| locker_token_address(&locker, &token)),
+    };
+    let data = instruction::TimeLockFunds {
+        _locker_name: locker_name.to_string(),
+        strike_time,
+        payout_amount,
+        token_mint: token.map(|token| token.mint),
+        join,
+    };
+    build(program_id, accounts.to_account_metas(None), data.data())
+}
+
+// Check in as the authority, which keeps the inactivity locks of the locker locked for another period
+pub fn heartbeat(program_id: &Pubkey, authority: &Pubkey, locker_name: &str) -> Instruction {
+    let accounts = accounts::Heartbeat {
+        authority: *authority,
+        locker: find_locker_address(program_id, authority, locker_name).0,
+    };
+    let data = instruction::Heartbeat {
+        _locker_name: locker_name.to_string(),
+    };
+    build(program_id, accounts.to_account_metas(None), data.data())
+}
+
+// Revoke a locked inactivity lock, which releases its funds to the authority and checks in
+pub fn revoke_inactivity_lock(program_id: &Pubkey, authority: &Pubkey, locker_name: &str, lock_id: u8) -> Instruction {
+    let accounts = accounts::RevokeInactivityLock {
+        authority: *authority,
+        locker: find_locker_address(program_id, authority, locker_name).0,
+    };
+    let data = instruction::RevokeInactivityLock {
+        _locker_name: locker_name.to_string(),
+        lock_id,
+    };
+    build(program_id, accounts.to_account_metas(None), data.data())
+}
+
+// Unlock a time lock by its index, index 255 unlocks all unlockable locks
+// The return data decodes as UnlockResult, with the skipped locks when unlocking all
+pub fn time_unlock_funds(program_id: &Pubkey, authority: &Pubkey, locker_name: &str, lock_index: u8) -> Instruction {
+    let accounts = accounts::TimeUnlockFunds {
+        authority: *authority,
+        locker: find_locker_address(program_id, authority, locker_name).0,
+        system_program: system_program::ID,
+    };
+    let data = instruction::TimeUnlockFunds {
+        _locker_name: locker_name.to_string(),
+        lock_index,
+    };
+    build(program_id, accounts.to_account_metas(None), data.data())
+}
+
+// Unlock a price lock by its index, index 255 unlocks all unlockable locks
+// The return data decodes as UnlockResult, with the skipped locks when unlocking all
+pub fn price_unlock_funds(program_id: &Pubkey, authority: &Pubkey, locker_name: &str, price_feed: &PriceFeedAccounts, lock_index: u8) -> Instruction {
+    let accounts = accounts::PriceUnlockFunds {
+        authority: *authority,
+        price_feed: price_feed.price_feed,
+        locker: find_locker_address(program_id, authority, locker_name).0,
+        pyth_solprice_account: price_feed.pyth_price_account,
+        system_program: system_program::ID,
+        config: find_config_address(program_id).0,
+    };
+    let data = instruction::PriceUnlockFunds {
+        _locker_name: locker_name.to_string(),
+        lock_index,
+    };
+    build(program_id, accounts.to_account_metas(None), data.data())
+}
+
+// Withdraw SOL (token None) or SPL tokens from the locker, amount WITHDRAW_MAX withdraws everything that is withdrawable
+// Tokens are transferred to the associated token account of the authority, which must exist
+pub fn withdraw_unlocked_funds(program_id: &Pubkey, authority: &Pubkey, locker_name: &str, amount: u32, token: Option<&TokenAsset>) -> Instruction {
+    build_withdraw_unlocked_funds(program_id, authority, locker_name, amount, token, false, None)
+}
+
+// Withdraw SOL (token None) or SPL tokens from the locker to another wallet on the withdrawal allowlist
+// Tokens are transferred to the associated token account of the destination, which must exist
+pub fn withdraw_to_destination(program_id: &Pubkey, authority: &Pubkey, locker_name: &str, amount: u32, token: Option<&TokenAsset>, destination: &Pubkey) -> Instruction {
+    build_withdraw_unlocked_funds(program_id, authority, locker_name, amount, token, false, Some(destination))
+}
+
+// Withdraw wrapped SOL from the locker as SOL, through the native mint token account of the authority
+// The token account of the authority is closed, which also unwraps the wrapped SOL it already held
+pub fn withdraw_unwrapped_sol(program_id: &Pubkey, authority: &Pubkey, locker_name: &str, amount: u32) -> Instruction {
+    build_withdraw_unlocked_funds(program_id, authority, locker_name, amount, Some(&TokenAsset::wrapped_sol()), true, None)
+}
+
+fn build_withdraw_unlocked_funds(program_id: &Pubkey, authority: &Pubkey, locker_name: &str, amount: u32, token: Option<&TokenAsset>, unwrap_sol: bool, destination: Option<&Pubkey>) -> Instruction {
+    let locker = find_locker_address(program_id, authority, locker_name).0;
+    let recipient = destination.unwrap_or(authority);
+    let accounts = accounts::WithdrawUnlockedFunds {
+        authority: *authority,
+        locker,
+        system_program: system_program::ID,
+        token_mint_account_optional: token.map(|token| token.mint),
+        token_account_optional: token.map(|token| locker_token_address(&locker, token)),
+        authority_token_account_optional: token.map(|token| wallet_token_address(recipient, token)),
+        token_program_optional: token.map(|token| token.token_program),
+        associated_token_program_optional: unwrap_sol.then_some(associated_token::ID),
+        destination_optional: destination.copied(),
+    };
+    let data = instruction::WithdrawUnlockedFunds {
+        locker_name: locker_name.to_string(),
+        amount,
+        unwrap_sol,
+    };
+    build(program_id, accounts.to_account_metas(None), data.data())
+}
+
+// Set the cooldown between requesting and executing a withdrawal, zero to withdraw directly
+pub fn set_withdrawal_cooldown(program_id: &Pubkey, authority: &Pubkey, locker_name: &str, cooldown_secs: u32) -> Instruction {
+    let accounts = accounts::SetWithdrawalCooldown {
+        authority: *authority,
+        locker: find_locker_address(program_id, authority, locker_name).0,
+        system_program: system_program::ID,
+    };
+    let data = instruction::SetWithdrawalCooldown {
+        _locker_name: locker_name.to_string(),
+        cooldown_secs,
+    };
+    build(program_id, accounts.to_account_metas(None), data.data())
+}
+
+// Request a withdrawal of SOL (token None) or SPL tokens to the destination, executable once the cooldown passed
+pub fn request_withdrawal(program_id: &Pubkey, authority: &Pubkey, locker_name: &str, amount: u32, token: Option<&TokenAsset>, destination: &Pubkey) -> Instruction {
+    let locker = find_locker_address(program_id, authority, locker_name).0;
+    let accounts = accounts::RequestWithdrawal {
+        authority: *authority,
+        locker,
+        system_program: system_program::ID,
+        token_account_optional: token.map(|token| locker_token_address(&locker, token)),
+    };
+    let data = instruction::RequestWithdrawal {
+        _locker_name: locker_name.to_string(),
+        amount,
+        token_mint: token.map(|token| token.mint),
+        destination: *destination,
+    };
+    build(program_id, accounts.to_account_metas(None), data.data())
+}
+
+// Execute the withdrawal request, token and destination must match the request
+// Tokens are transferred to the associated token account of the destination, which is created when it doesn't exist
+pub fn execute_withdrawal(program_id: &Pubkey, authority: &Pubkey, locker_name: &str, token: Option<&TokenAsset>, destination: &Pubkey) -> Instruction {
+    let locker = find_locker_address(program_id, authority, locker_name).0;
+    let accounts = accounts::ExecuteWithdrawal {
+        authority: *authority,
+        locker,
+        system_program: system_program::ID,
+        destination: *destination,
+        token_mint_account_optional: token.map(|token| token.mint),
+        token_account_optional: token.map(|token| locker_token_address(&locker, token)),
+        destination_token_account_optional: token.map(|token| wallet_token_address(destination, token)),
+        token_program_optional: token.map(|token| token.token_program),
+        associated_token_program_optional: token.map(|_| associated_token::ID),
+    };
+    let data = instruction::ExecuteWithdrawal {
+        locker_name: locker_name.to_string(),
+    };
+    build(program_id, accounts.to_account_metas(None), data.data())
+}
+
+// Cancel the withdrawal request of the locker of authority, signed by the authority or a guardian
+pub fn cancel_withdrawal(program_id: &Pubkey, signer: &Pubkey, authority: &Pubkey, locker_name: &str) -> Instruction {
+    let accounts = accounts::CancelWithdrawal {
+        signer: *signer,
+        locker: find_locker_address(program_id, authority, locker_name).0,
+    };
+    let data = instruction::CancelWithdrawal {
+        _locker_name: locker_name.to_string(),
+    };
+    build(program_id, accounts.to_account_metas(None), data.data())
+}
+
+// Claim the funds of an unlocked lock with a beneficiary, signed by the beneficiary
+// token is the asset of the lock (None for SOL), WITHDRAW_MAX claims everything that is claimable
+pub fn claim_beneficiary_funds(program_id: &Pubkey, beneficiary: &Pubkey, authority: &Pubkey, locker_name: &str, lock_id: u8, amount: u32, token: Option<&TokenAsset>) -> Instruction {
+    let locker = find_locker_address(program_id, authority, locker_name).0;
+    let accounts = accounts::ClaimBeneficiaryFunds {
+        beneficiary: *beneficiary,
+        locker,
+        system_program: system_program::ID,
+        token_mint_account_optional: token.map(|token| token.mint),
+        token_account_optional: token.map(|token| locker_token_address(&locker, token)),
+        beneficiary_token_account_optional: token.map(|token| wallet_token_address(beneficiary, token)),
+        token_program_optional: token.map(|token| token.token_program),
+        associated_token_program_optional: token.map(|_| associated_token::ID),
+    };
+    let data = instruction::ClaimBeneficiaryFunds {
+        locker_name: locker_name.to_string(),
+        lock_id,
+        amount,
+    };
+    build(program_id, accounts.to_account_metas(None), data.data())
+}
+
+// Propose the destinations of the withdrawals of the locker, which take effect after 48 hours
+// No destinations removes the restriction, also after the delay
+pub fn set_withdrawal_allowlist(program_id: &Pubkey, authority: &Pubkey, locker_name: &str, destinations: &[Pubkey]) -> Instruction {
+    let accounts = accounts::SetWithdrawalAllowlist {
+        authority: *authority,
+        locker: find_locker_address(program_id, authority, locker_name).0,
+        system_program: system_program::ID,
+    };
+    let data = instruction::SetWithdrawalAllowlist {
+        _locker_name: locker_name.to_string(),
+        destinations: destinations.to_vec(),
+    };
+    build(program_id, accounts.to_account_metas(None), data.data())
+}
+
+// Cancel the pending change of the withdrawal allowlist before it takes effect
+pub fn cancel_withdrawal_allowlist_change(program_id: &Pubkey, authority: &Pubkey, locker_name: &str) -> Instruction {
+    let accounts = accounts::CancelWithdrawalAllowlistChange {
+        authority: *authority,
+        locker: find_locker_address(program_id, authority, locker_name).0,
+    };
+    let data = instruction::CancelWithdrawalAllowlistChange {
+        _locker_name: locker_name.to_string(),
+    };
+    build(program_id, accounts.to_account_metas(None), data.data())
+}
+
+// Set the guardians of the locker that can approve an early release, no guardians removes them
+// penalty_bps of the released funds is paid to the penalty destination on each early release
+pub fn set_guardians(program_id: &Pubkey, authority: &Pubkey, locker_name: &str, guardians: &[Pubkey], threshold: u8, penalty_bps: u16, penalty_destination: &Pubkey) -> Instruction {
+    let accounts = accounts::SetGuardians {
+        authority: *authority,
+        locker: find_locker_address(program_id, authority, locker_name).0,
+        system_program: system_program::ID,
+    };
+    let data = instruction::SetGuardians {
+        _locker_name: locker_name.to_string(),
+        guardians: guardians.to_vec(),
+        threshold,
+        penalty_bps,
+        penalty_destination: *penalty_destination,
+    };
+    build(program_id, accounts.to_account_metas(None), data.data())
+}
+
+// Release a lock early with the approval of the guardians, who must all sign the transaction
+// token is the asset of the lock (None for SOL), the penalty goes to the associated token account of the destination
+pub fn guardian_release(program_id: &Pubkey, authority: &Pubkey, locker_name: &str, lock_id: u8, guardians: &[Pubkey], penalty_destination: &Pubkey, token: Option<&TokenAsset>) -> Instruction {
+    let locker = find_locker_address(program_id, authority, locker_name).0;
+    let treasury = find_treasury_address(program_id).0;
+    let accounts = accounts::GuardianRelease {
+        authority: *authority,
+        locker,
+        system_program: system_program::ID,
+        config: find_config_address(program_id).0,
+        treasury,
+        penalty_destination: *penalty_destination,
+        token_mint_account_optional: token.map(|token| token.mint),
+        token_account_optional: token.map(|token| locker_token_address(&locker, token)),
+        penalty_token_account_optional: token.map(|token| wallet_token_address(penalty_destination, token)),
+        treasury_token_account_optional: token.map(|token| wallet_token_address(&treasury, token)),
+        token_program_optional: token.map(|token| token.token_program),
+        associated_token_program_optional: token.map(|_| associated_token::ID),
+    };
+    let mut account_metas = accounts.to_account_metas(None);
+    account_metas.extend(guardians.iter().map(|guardian| AccountMeta::new_readonly(*guardian, true)));
+    let data = instruction::GuardianRelease {
+        locker_name: locker_name.to_string(),
+        lock_id,
+    };
+    build(program_id, account_metas, data.data())
+}
+
+// Exit a time lock with an early exit before its strike time, forfeiting the decayed penalty
+// token is the asset of the lock (None for SOL), the penalty goes to the associated token account of the destination
+pub fn exit_lock_early(program_id: &Pubkey, authority: &Pubkey, locker_name: &str, lock_id: u8, penalty_destination: &Pubkey, token: Option<&TokenAsset>) -> Instruction {
+    let locker = find_locker_address(program_id, authority, locker_name).0;
+    let treasury = find_treasury_address(program_id).0;
+    let accounts = accounts::ExitLockEarly {
+        authority: *authority,
+        locker,
+        system_program: system_program::ID,
+        config: find_config_address(program_id).0,
+        treasury,
+        penalty_destination: *penalty_destination,
+        token_mint_account_optional: token.map(|token| token.mint),
+        token_account_optional: token.map(|token| locker_token_address(&locker, token)),
+        penalty_token_account_optional: token.map(|token| wallet_token_address(penalty_destination, token)),
+        treasury_token_account_optional: token.map(|token| wallet_token_address(&treasury, token)),
+        token_program_optional: token.map(|token| token.token_program),
+        associated_token_program_optional: token.map(|_| associated_token::ID),
+    };
+    let data = instruction::ExitLockEarly {
+        locker_name: locker_name.to_string(),
+        lock_id,
+    };
+    build(program_id, accounts.to_account_metas(None), data.data())
+}
+
+// Create the program config and the treasury, the admin signs and becomes the admin of the config
+// The admin must be the upgrade authority of the program
+pub fn init_config(program_id: &Pubkey, admin: &Pubkey, fees: Fees) -> Instruction {
+    let accounts = accounts::InitConfig {
+        admin: *admin,
+        config: find_config_address(program_id).0,
+        treasury: find_treasury_address(program_id).0,
+        program_data: find_program_data_address(program_id).0,
+        system_program: system_program::ID,
+    };
+    build(program_id, accounts.to_account_metas(None), instruction::InitConfig { fees }.data())
+}
+
+// Change the protocol fees, or hand the config over to new_admin (the admin itself to keep it)
+pub fn update_config(program_id: &Pubkey, admin: &Pubkey, new_admin: &Pubkey, fees: Fees) -> Instruction {
+    let accounts = accounts::UpdateConfig {
+        admin: *admin,
+        config: find_config_address(program_id).0,
+    };
+    let data = instruction::UpdateConfig {
+        admin: *new_admin,
+        fees,
+    };
+    build(program_id, accounts.to_account_metas(None), data.data())
+}
+
+// Pause or resume new deposits, new locks and price unlocks, signed by the admin
+pub fn set_paused(program_id: &Pubkey, admin: &Pubkey, paused: bool) -> Instruction {
+    let accounts = accounts::SetPaused {
+        admin: *admin,
+        config: find_config_address(program_id).0,
+    };
+    build(program_id, accounts.to_account_metas(None), instruction::SetPaused { paused }.data())
+}
+
+// Configure the circuit breaker of the price unlocks, a maximum move of zero disables it
+pub fn set_circuit_breaker(program_id: &Pubkey, admin: &Pubkey, circuit_breaker: CircuitBreaker) -> Instruction {
+    let accounts = accounts::SetCircuitBreaker {
+        admin: *admin,
+        config: find_config_address(program_id).0,
+    };
+    build(program_id, accounts.to_account_metas(None), instruction::SetCircuitBreaker { circuit_breaker }.data())
+}
+
+// Withdraw collected fees of SOL (token None) or SPL tokens from the treasury to the admin
+// Tokens are transferred to the associated token account of the admin, which is created when it doesn't exist
+pub fn withdraw_fees(program_id: &Pubkey, admin: &Pubkey, amount: u64, token: Option<&TokenAsset>) -> Instruction {
+    let treasury = find_treasury_address(program_id).0;
+    let accounts = accounts::WithdrawFees {
+        admin: *admin,
+        config: find_config_address(program_id).0,
+        treasury,
+        system_program: system_program::ID,
+        token_mint_account_optional: token.map(|token| token.mint),
+        treasury_token_account_optional: token.map(|token| wallet_token_address(&treasury, token)),
+        admin_token_account_optional: token.map(|token| wallet_token_address(admin, token)),
+        token_program_optional: token.map(|token| token.token_program),
+        associated_token_program_optional: token.map(|_| associated_token::ID),
+    };
+    build(program_id, accounts.to_account_metas(None), instruction::WithdrawFees { amount }.data())
+}
+
+// Unlock all eligible locks of many lockers in one instruction
+pub fn crank_unlocks(program_id: &Pubkey, keeper: &Pubkey, lockers: &[CrankLocker]) -> Instruction {
+    let accounts = accounts::CrankUnlocks {
+        keeper: *keeper,
+        config: find_config_address(program_id).0,
+    };
+    let mut account_metas = accounts.to_account_metas(None);
+
+    // Each locker is followed by its price feed accounts, or twice the program id when it has no price feed
+    // The price feed is writable, the crank moves the window of the circuit breaker
+    for crank_locker in lockers {
+        let (price_feed, pyth_price_account) = match crank_locker.price_feed {
+            Some(price_feed) => (AccountMeta::new(price_feed.price_feed, false), price_feed.pyth_price_account),
+            None => (AccountMeta::new_readonly(*program_id, false), *program_id),
+        };
+        account_metas.push(AccountMeta::new(crank_locker.locker, false));
+        account_metas.push(price_feed);
+        account_metas.push(AccountMeta::new_readonly(pyth_price_account, false));
+    }
+
+    build(program_id, account_metas, instruction::CrankUnlocks.data())
+}
+
+// View of the balances and locks of a locker, simulate the transaction and decode the return data as LockerStatus
+// The token accounts of the locker are derived from the given tokens
+pub fn get_locker_status(program_id: &Pubkey, locker: &Pubkey, price_feed: Option<&PriceFeedAccounts>, tokens: &[TokenAsset]) -> Instruction {
+    let accounts = accounts::GetLockerStatus {
+        locker: *locker,
+        price_feed: price_feed.map(|price_feed| price_feed.price_feed),
+        pyth_price_account: price_feed.map(|price_feed| price_feed.pyth_price_account),
+        config: Some(find_config_address(program_id).0),
+    };
+    let mut account_metas = accounts.to_account_metas(None);
+    account_metas.extend(tokens.iter().map(|token| AccountMeta::new_readonly(locker_token_address(locker, token), false)));
+
+    build(program_id, account_metas, instruction::GetLockerStatus.data())
+}
+
+// View of the NFTs held by a locker, simulate the transaction and decode the return data as Vec<NftStatus>
+// The token accounts of the locker are derived from the given NFTs
+pub fn get_locker_nfts(program_id: &Pubkey, locker: &Pubkey, nfts: &[TokenAsset]) -> Instruction {
+    let mut account_metas = accounts::GetLockerNfts { locker: *locker }.to_account_metas(None);
+    account_metas.extend(nfts.iter().map(|nft| AccountMeta::new_readonly(locker_token_address(locker, nft), false)));
+
+    build(program_id, account_metas, instruction::GetLockerNfts.data())
+}
+
+// Verify that the owner has at least min_amount of the asset (token None for SOL) locked until at least min_unlock_ts
+pub fn verify_lock(program_id: &Pubkey, locker: &Pubkey, owner: &Pubkey, token: Option<TokenAsset>, min_amount: u64, min_unlock_ts: u32) -> Instruction {
+    let data = instruction::VerifyLock {
+        owner: *owner,
+        token_mint: token.map(|token| token.mint),
+        min_amount,
+        min_unlock_ts,
+    };
+    build(program_id, verify_lock_accounts(locker, token), data.data())
+}
+
+// Same as verify_lock, but the instruction fails when the requirement is not met
+pub fn require_lock(program_id: &Pubkey, locker: &Pubkey, owner: &Pubkey, token: Option<TokenAsset>, min_amount: u64, min_unlock_ts: u32) -> Instruction {
+    let data = instruction::RequireLock {
+        owner: *owner,
+        token_mint: token.map(|token| token.mint),
+        min_amount,
+        min_unlock_ts,
+    };
+    build(program_id, verify_lock_accounts(locker, token), data.data())
+}
+
+fn verify_lock_accounts(locker: &Pubkey, token: Option<TokenAsset>) -> Vec<AccountMeta> {
+    let accounts = accounts::VerifyLock {
+        locker: *locker,
+        token_account_optional: token.map(|token| locker_token_address(locker, &token)),
+    };
+    accounts.to_account_metas(None)
+}
+
+
+// Assemble the instruction
+// Anchor passes the default program id for optional accounts that are not set, which is replaced by the given program id
+fn build(program_id: &Pubkey, mut account_metas: Vec<AccountMeta>, data: Vec<u8>) -> Instruction {
+    for account_meta in &mut account_metas {
+        if account_meta.pubkey == pricelocker::ID {
+            account_meta.pubkey = *program_id;
+        }
+    }
+
+    Instruction {
+        program_id: *program_id,
+        accounts: account_metas,
+        data,
+    }
+}
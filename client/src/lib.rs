@@ -0,0 +1,15 @@
+// Client SDK for the pricelocker program
+// Instruction builders, derivation of the program derived addresses, account decoders,
+//  and off-chain evaluation of locks and balances with the same rules as the program
+
+pub mod accounts;
+pub mod balance;
+pub mod instructions;
+pub mod pda;
+
+pub use accounts::*;
+pub use balance::*;
+pub use instructions::*;
+pub use pda::*;
+
+pub use pricelocker::{CircuitBreaker, CrankUnlocksResult, EarlyExit, Fees, GuardianConfig, Lock, LockCondition, LockVerification, Locker, LockerErrorCode, LockerStatus, Lot, LotStatus, NftStatus, PendingAllowlist, Pricefeedaccount, PriceReference, ProgramConfig, SkippedLock, UnlockResult, WithdrawalAllowlist, WithdrawalCooldown, WithdrawalRequest, ID as PROGRAM_ID, WITHDRAW_MAX};
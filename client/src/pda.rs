@@ -0,0 +1,72 @@
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::bpf_loader_upgradeable;
+use anchor_spl::associated_token::get_associated_token_address_with_program_id;
+use anchor_spl::{token, token_2022};
+
+
+// Derive the addresses of the program derived accounts, with the same seeds as the program
+
+
+// Locker of an authority, 1 authority can have multiple lockers differentiated by the locker name
+pub fn find_locker_address(program_id: &Pubkey, authority: &Pubkey, locker_name: &str) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"locker".as_ref(), authority.as_ref(), locker_name.as_ref()], program_id)
+}
+
+// Price feed account registered under an alias (e.g. "SOL/USD")
+pub fn find_pricefeed_address(program_id: &Pubkey, pricefeed_alias: &str) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"pricefeed".as_ref(), pricefeed_alias.as_ref()], program_id)
+}
+
+// Stake account created when staking the funds of a locker
+pub fn find_stake_account_address(program_id: &Pubkey, payer: &Pubkey, locker_name: &str) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"stake_account".as_ref(), payer.as_ref(), locker_name.as_ref()], program_id)
+}
+
+// Program config of the admin with the protocol fees, one per program
+pub fn find_config_address(program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"config".as_ref()], program_id)
+}
+
+// Treasury that collects the protocol fees, SOL as its lamports and SPL tokens in its associated token accounts
+pub fn find_treasury_address(program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"treasury".as_ref()], program_id)
+}
+
+// Program data account of the deployment of the program, which records its upgrade authority
+pub fn find_program_data_address(program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[program_id.as_ref()], &bpf_loader_upgradeable::ID)
+}
+
+// Associated token account of the locker, which holds the SPL tokens deposited in the locker
+pub fn locker_token_address(locker: &Pubkey, token: &TokenAsset) -> Pubkey {
+    wallet_token_address(locker, token)
+}
+
+// Associated token account of a wallet, e.g. the authority depositing and withdrawing tokens
+pub fn wallet_token_address(wallet: &Pubkey, token: &TokenAsset) -> Pubkey {
+    get_associated_token_address_with_program_id(wallet, &token.mint, &token.token_program)
+}
+
+
+// An SPL token, the associated token accounts depend on the token program that owns the mint
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TokenAsset {
+    pub mint: Pubkey,
+    // SPL Token or Token-2022 program
+    pub token_program: Pubkey,
+}
+
+impl TokenAsset {
+    pub fn spl_token(mint: Pubkey) -> Self {
+        TokenAsset { mint, token_program: token::ID }
+    }
+
+    pub fn token_2022(mint: Pubkey) -> Self {
+        TokenAsset { mint, token_program: token_2022::ID }
+    }
+
+    // SOL wrapped into tokens of the native mint of the SPL Token program
+    pub fn wrapped_sol() -> Self {
+        TokenAsset::spl_token(token::spl_token::native_mint::ID)
+    }
+}
@@ -0,0 +1,115 @@
+// Fuzz the lock accounting with random sequences of deposits, locks, unlocks and withdrawals
+// Run with: cargo fuzz run lock_accounting
+#![no_main]
+
+use anchor_lang::prelude::Pubkey;
+use arbitrary::Arbitrary;
+use libfuzzer_sys::fuzz_target;
+use pricelocker::accounting::*;
+use pricelocker::{Lock, PriceReading};
+
+
+// Maximum number of locks in a locker, so the lock ids fit in a u8
+const MAX_LOCKS: usize = 32;
+
+const TOKEN_MINT: Pubkey = Pubkey::new_from_array([7; 32]);
+
+// The price feed of the price locks, and another registered feed whose price never unlocks them
+const PRICE_FEED: Pubkey = Pubkey::new_from_array([8; 32]);
+const OTHER_PRICE_FEED: Pubkey = Pubkey::new_from_array([9; 32]);
+
+
+#[derive(Arbitrary, Debug)]
+enum Operation {
+    Deposit { token: bool, amount: u32 },
+    Lock { token: bool, price_lock: bool, strike: u16, amount: u32, join: Option<u8> },
+    Unlock { index: u8, time_now: u16, price: Option<u16>, other_feed: bool },
+    Withdraw { token: bool, amount: u64 },
+}
+
+// Balances of SOL and one SPL token, with the locks on them
+#[derive(Default)]
+struct Locker {
+    sol: u64,
+    token: u64,
+    locks: Vec<Lock>,
+}
+
+impl Locker {
+    fn total(&mut self, token: bool) -> &mut u64 {
+        if token { &mut self.token } else { &mut self.sol }
+    }
+
+    // The locked balance never exceeds the deposited balance, and the rest is withdrawable
+    fn check_invariants(&self) {
+        for (token_mint, total) in [(None, self.sol), (Some(TOKEN_MINT), self.token)] {
+            let locked = locked_balance(&self.locks, &token_mint);
+            let withdrawable = withdrawable_balance(total, &self.locks, &token_mint);
+
+            assert!(locked <= total, "locked {locked} exceeds total {total}");
+            assert_eq!(withdrawable + locked, total, "withdrawable exceeds unlocked funds");
+            assert!(committed_balance(&self.locks, &token_mint, 0) <= locked);
+        }
+    }
+}
+
+fn token_mint(token: bool) -> Option<Pubkey> {
+    token.then_some(TOKEN_MINT)
+}
+
+
+fuzz_target!(|operations: Vec<Operation>| {
+    let mut locker = Locker::default();
+
+    for operation in operations {
+        match operation {
+            Operation::Deposit { token, amount } => {
+                let total = locker.total(token);
+                *total = total.saturating_add(amount as u64);
+            },
+            Operation::Lock { token, price_lock, strike, amount, join } => {
+                if locker.locks.len() >= MAX_LOCKS {
+                    continue;
+                }
+
+                let id = locker.locks.len() as u8;
+                let token_mint = token_mint(token);
+                let new_lock = if price_lock {
+                    Lock::PriceLock { id, strike_price: strike as u32, price_feed: PRICE_FEED, amount, token_mint, locked: true, join, lot: None, beneficiary: None }
+                } else {
+                    Lock::TimeLock { id, strike_time: strike as u32, amount, token_mint, locked: true, join, lot: None, beneficiary: None, early_exit: None }
+                };
+
+                let total = *locker.total(token);
+                if check_new_lock(total, &locker.locks, &new_lock).is_ok() {
+                    locker.locks.push(new_lock);
+                }
+            },
+            Operation::Unlock { index, time_now, price, other_feed } => {
+                let Some(lock_item) = locker.locks.get(index as usize).copied() else { continue };
+                let locked_before = locked_balance(&locker.locks, &lock_item.token_mint());
+
+                // Only time and price locks are generated, the last activity of the authority doesn't matter
+                let price_feed = if other_feed { OTHER_PRICE_FEED } else { PRICE_FEED };
+                let reading = price.map(|price| PriceReading { price_feed, price: price.into() });
+                let unlockable = is_unlockable(&lock_item, time_now as u32, reading, 0);
+                let result = unlock(&mut locker.locks[index as usize], time_now as u32, reading, 0);
+
+                if lock_item.is_locked() {
+                    assert_eq!(result.is_ok(), unlockable);
+                }
+                if other_feed && matches!(lock_item, Lock::PriceLock { .. }) {
+                    assert!(result.is_err());
+                }
+                assert!(locked_balance(&locker.locks, &lock_item.token_mint()) <= locked_before);
+            },
+            Operation::Withdraw { token, amount } => {
+                // Withdrawals are limited to the withdrawable balance
+                let withdrawable = withdrawable_balance(*locker.total(token), &locker.locks, &token_mint(token));
+                *locker.total(token) -= amount.min(withdrawable);
+            },
+        }
+
+        locker.check_invariants();
+    }
+});
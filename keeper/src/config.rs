@@ -0,0 +1,52 @@
+use anchor_lang::prelude::Pubkey;
+use clap::Parser;
+use std::path::PathBuf;
+
+
+// Command line configuration of the keeper
+// Every option can also be set through an environment variable, which is convenient when running as a service
+#[derive(Parser, Debug, Clone)]
+#[command(name = "pricelocker-keeper", version, about = "Scans all lockers and unlocks the locks whose conditions are met")]
+pub struct Config {
+    /// RPC endpoint of the cluster
+    #[arg(long, short = 'u', env = "PRICELOCKER_RPC_URL", default_value = "http://127.0.0.1:8899")]
+    pub url: String,
+
+    /// Keypair of the keeper, which signs and pays for the unlock transactions
+    #[arg(long, short = 'k', env = "PRICELOCKER_KEYPAIR", default_value = "~/.config/solana/id.json")]
+    pub keypair: PathBuf,
+
+    /// Program id of the deployed locker program (e.g. when deployed to a local test validator)
+    #[arg(long, env = "PRICELOCKER_PROGRAM_ID", default_value_t = pricelocker::ID)]
+    pub program_id: Pubkey,
+
+    /// Alias of the registered price feed used to evaluate price locks, price locks are skipped when not set
+    #[arg(long, env = "PRICELOCKER_PRICE_FEED")]
+    pub price_feed: Option<String>,
+
+    /// Only log the unlocks that would be submitted, without sending transactions
+    #[arg(long)]
+    pub dry_run: bool,
+
+    /// Run a single scan and exit, instead of scanning every interval
+    #[arg(long)]
+    pub once: bool,
+
+    /// Seconds to wait between two scans
+    #[arg(long, default_value_t = 30)]
+    pub interval: u64,
+
+    /// Maximum number of lockers cranked in one transaction
+    #[arg(long, default_value_t = 5)]
+    pub batch_size: usize,
+}
+
+impl Config {
+    // Expand a leading ~ in the keypair path to the home directory, like the solana cli does
+    pub fn keypair_path(&self) -> PathBuf {
+        match (self.keypair.strip_prefix("~"), std::env::var_os("HOME")) {
+            (Ok(rest), Some(home)) => PathBuf::from(home).join(rest),
+            _ => self.keypair.clone(),
+        }
+    }
+}
@@ -0,0 +1,7 @@
+// Keeper for the pricelocker program, as a library so the scan, plan and submit steps can be tested
+//  without the daemon loop of main.rs
+
+pub mod config;
+pub mod plan;
+pub mod scan;
+pub mod submit;
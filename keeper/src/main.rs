@@ -0,0 +1,82 @@
+// Keeper daemon for the pricelocker program
+// Scans all lockers of the program, evaluates their locks against the cluster clock and a Pyth price feed,
+//  and cranks the unlocks of the locks whose conditions are met
+
+use anyhow::{anyhow, Result};
+use clap::Parser;
+use pricelocker_keeper::config::Config;
+use pricelocker_keeper::{plan, scan, submit};
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::signature::{read_keypair_file, Keypair, Signer};
+use std::thread;
+use std::time::Duration;
+
+
+fn main() -> Result<()> {
+    let config = Config::parse();
+
+    let keeper = read_keypair_file(config.keypair_path())
+        .map_err(|err| anyhow!("reading keypair {}: {err}", config.keypair_path().display()))?;
+
+    let rpc = RpcClient::new_with_commitment(config.url.clone(), CommitmentConfig::confirmed());
+
+    println!(
+        "keeper {} watching program {} on {}{}",
+        keeper.pubkey(),
+        config.program_id,
+        config.url,
+        if config.dry_run { " (dry run)" } else { "" }
+    );
+
+    loop {
+        // A failed scan (e.g. an unreachable RPC) is logged, the next scan tries again
+        if let Err(err) = run_scan(&config, &rpc, &keeper) {
+            eprintln!("scan failed: {err:#}");
+        }
+
+        if config.once {
+            return Ok(());
+        }
+
+        thread::sleep(Duration::from_secs(config.interval));
+    }
+}
+
+
+// Scan all lockers once and crank the ones with unlockable locks
+fn run_scan(config: &Config, rpc: &RpcClient, keeper: &Keypair) -> Result<()> {
+    let clock = scan::fetch_clock(rpc)?;
+    let lockers = scan::fetch_lockers(rpc, &config.program_id)?;
+
+    // Price locks can only be evaluated when a price feed is configured
+    let feed = match &config.price_feed {
+        Some(alias) => Some(scan::fetch_feed_price(rpc, &config.program_id, alias, &clock)?),
+        None => None,
+    };
+    let price = feed.and_then(|feed| feed.reading());
+
+    let planned = plan::plan_unlocks(&lockers, clock.unix_timestamp as u32, price);
+
+    println!("scanned {} lockers, {} with unlockable locks", lockers.len(), planned.len());
+
+    for batch in plan::batches(&planned, config.batch_size) {
+        for planned in batch {
+            println!("  locker {} locks {:?}", planned.locker, planned.lock_ids);
+        }
+
+        if config.dry_run {
+            continue;
+        }
+
+        let instruction = submit::crank_instruction(&config.program_id, &keeper.pubkey(), batch, feed.as_ref());
+
+        // A failed batch does not stop the other batches from being cranked
+        match submit::send_crank(rpc, keeper, instruction) {
+            Ok(signature) => println!("  cranked {} lockers: {signature}", batch.len()),
+            Err(err) => eprintln!("  crank failed: {err:#}"),
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,39 @@
+use anchor_lang::prelude::*;
+use pricelocker::{Lock, Locker, PriceReading};
+use pricelocker_client::is_unlockable;
+
+
+// A locker with at least one lock that can be unlocked now
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlannedUnlock {
+    pub locker: Pubkey,
+    // ids of the locks whose conditions are met
+    pub lock_ids: Vec<u8>,
+    // Whether one of the locks is a price lock, so the price feed must be passed to the crank
+    pub needs_price_feed: bool,
+}
+
+
+// Select the lockers that have locks to unlock, using the same conditions as the program
+// Only the price locks created on the price feed of the price are evaluated, the others stay locked
+pub fn plan_unlocks(lockers: &[(Pubkey, Locker)], time_now: u32, price: Option<PriceReading>) -> Vec<PlannedUnlock> {
+    lockers
+        .iter()
+        .filter_map(|(address, locker)| {
+            let unlockable: Vec<&Lock> = locker.locks.iter().filter(|lock_item| is_unlockable(lock_item, time_now, price, locker.last_activity_ts)).collect();
+
+            (!unlockable.is_empty()).then(|| PlannedUnlock {
+                locker: *address,
+                lock_ids: unlockable.iter().map(|lock_item| lock_item.id()).collect(),
+                needs_price_feed: unlockable.iter().any(|lock_item| matches!(lock_item, Lock::PriceLock { .. })),
+            })
+        })
+        .collect()
+}
+
+
+// Split the planned unlocks into the batches that are cranked in one transaction each, in plan order
+// A batch size of 0 is treated as 1, so every locker is still cranked
+pub fn batches(planned: &[PlannedUnlock], batch_size: usize) -> std::slice::Chunks<'_, PlannedUnlock> {
+    planned.chunks(batch_size.max(1))
+}
@@ -0,0 +1,89 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::sysvar;
+use anchor_lang::Discriminator;
+use anyhow::{anyhow, Context, Result};
+use pricelocker::{Locker, PriceReading};
+use pricelocker_client::{decode_locker, decode_pricefeed_account, find_pricefeed_address, pyth_price, PriceFeedAccounts};
+use solana_client::rpc_client::RpcClient;
+use solana_client::rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig};
+use solana_client::rpc_filter::{Memcmp, RpcFilterType};
+use solana_sdk::account::{from_account, Account};
+
+
+// A registered price feed together with its current price
+#[derive(Debug, Clone, Copy)]
+pub struct FeedPrice {
+    pub accounts: PriceFeedAccounts,
+    // Current price, None when the Pyth price is stale or not trading
+    pub price: Option<u32>,
+}
+
+impl FeedPrice {
+    // The price to evaluate the price locks with, only the locks created on this price feed can unlock at it
+    pub fn reading(&self) -> Option<PriceReading> {
+        self.price.map(|price| PriceReading { price_feed: self.accounts.price_feed, price })
+    }
+}
+
+
+// Fetch and decode every Locker account of the program
+pub fn fetch_lockers(rpc: &RpcClient, program_id: &Pubkey) -> Result<Vec<(Pubkey, Locker)>> {
+
+    // Only request accounts that start with the Locker discriminator
+    let config = RpcProgramAccountsConfig {
+        filters: Some(vec![RpcFilterType::Memcmp(Memcmp::new_base58_encoded(0, &Locker::DISCRIMINATOR))]),
+        account_config: RpcAccountInfoConfig {
+            encoding: Some(solana_account_decoder::UiAccountEncoding::Base64),
+            ..RpcAccountInfoConfig::default()
+        },
+        ..RpcProgramAccountsConfig::default()
+    };
+
+    let accounts = rpc
+        .get_program_accounts_with_config(program_id, config)
+        .context("fetching locker accounts")?;
+
+    Ok(decode_lockers(accounts))
+}
+
+// Decode the fetched locker accounts
+pub fn decode_lockers(accounts: Vec<(Pubkey, Account)>) -> Vec<(Pubkey, Locker)> {
+    let mut lockers = Vec::with_capacity(accounts.len());
+    for (address, account) in accounts {
+        // Skip accounts that can not be decoded (e.g. created by an older version of the program)
+        match decode_locker(&account.data) {
+            Ok(locker) => lockers.push((address, locker)),
+            Err(err) => eprintln!("skipping locker {address}: {err}"),
+        }
+    }
+
+    lockers
+}
+
+
+// Fetch the clock of the cluster, locks are evaluated against the cluster time and not the local time
+pub fn fetch_clock(rpc: &RpcClient) -> Result<Clock> {
+    let account = rpc.get_account(&sysvar::clock::ID).context("fetching clock sysvar")?;
+    from_account::<Clock, _>(&account).ok_or_else(|| anyhow!("invalid clock sysvar"))
+}
+
+
+// Fetch the registered price feed by its alias, and read the current price from the Pyth account it points to
+pub fn fetch_feed_price(rpc: &RpcClient, program_id: &Pubkey, alias: &str, clock: &Clock) -> Result<FeedPrice> {
+
+    let (price_feed, _bump) = find_pricefeed_address(program_id, alias);
+
+    let account = rpc.get_account(&price_feed).with_context(|| format!("fetching price feed {alias}"))?;
+    let pricefeed_account = decode_pricefeed_account(&account.data)
+        .with_context(|| format!("decoding price feed {alias}"))?;
+
+    let pyth_price_account = pricefeed_account.pricefeed_id;
+    let pyth_account = rpc.get_account(&pyth_price_account).context("fetching pyth price account")?;
+
+    let price = pyth_price(&pyth_price_account, &pyth_account.data, clock.unix_timestamp);
+
+    Ok(FeedPrice {
+        accounts: PriceFeedAccounts { price_feed, pyth_price_account },
+        price,
+    })
+}
@@ -0,0 +1,35 @@
+use anchor_lang::prelude::*;
+use anyhow::{Context, Result};
+use pricelocker_client::{crank_unlocks, CrankLocker};
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::signature::{Keypair, Signature, Signer};
+use solana_sdk::transaction::Transaction;
+
+use crate::plan::PlannedUnlock;
+use crate::scan::FeedPrice;
+
+
+// Build the crank_unlocks instruction for a batch of lockers
+// The price feed is only passed for the lockers that have a price lock to unlock
+pub fn crank_instruction(program_id: &Pubkey, keeper: &Pubkey, batch: &[PlannedUnlock], feed: Option<&FeedPrice>) -> Instruction {
+    let lockers: Vec<CrankLocker> = batch
+        .iter()
+        .map(|planned| CrankLocker {
+            locker: planned.locker,
+            price_feed: feed.filter(|_| planned.needs_price_feed).map(|feed| feed.accounts),
+        })
+        .collect();
+
+    crank_unlocks(program_id, keeper, &lockers)
+}
+
+
+// Sign and send one crank transaction, waiting for confirmation
+pub fn send_crank(rpc: &RpcClient, keeper: &Keypair, instruction: Instruction) -> Result<Signature> {
+    let blockhash = rpc.get_latest_blockhash().context("fetching latest blockhash")?;
+
+    let transaction = Transaction::new_signed_with_payer(&[instruction], Some(&keeper.pubkey()), &[keeper], blockhash);
+
+    rpc.send_and_confirm_transaction(&transaction).context("sending crank transaction")
+}
@@ -0,0 +1,135 @@
+// The keeper flow against the program in solana-program-test: the locker accounts are fetched and decoded like a scan,
+//  planned, and cranked with the instructions the keeper submits
+// Only the RPC transport of the daemon is left out, program-test has no getProgramAccounts
+// The price lock flow runs against the mock oracle, run with: cargo test -p pricelocker-keeper --features localnet
+
+#[path = "../../tests/common/mod.rs"]
+mod common;
+
+use anchor_lang::prelude::*;
+use common::*;
+use pricelocker::Locker;
+use pricelocker_client::PriceFeedAccounts;
+use pricelocker_keeper::plan::{batches, plan_unlocks, PlannedUnlock};
+use pricelocker_keeper::scan::{decode_lockers, FeedPrice};
+use pricelocker_keeper::submit::crank_instruction;
+use solana_sdk::signature::Signer;
+
+
+impl TestEnv {
+    // Fetch and decode the lockers like a scan of the keeper
+    async fn scan(&mut self, addresses: &[Pubkey]) -> Vec<(Pubkey, Locker)> {
+        let mut accounts = Vec::new();
+        for address in addresses {
+            accounts.push((*address, self.get_account(address).await.unwrap()));
+        }
+        decode_lockers(accounts)
+    }
+}
+
+fn locked(lockers: &[(Pubkey, Locker)]) -> Vec<Vec<bool>> {
+    lockers.iter().map(|(_, locker)| locker.locks.iter().map(|lock_item| lock_item.is_locked()).collect()).collect()
+}
+
+
+#[tokio::test]
+async fn keeper_cranks_the_due_locks_in_batches() {
+    let mut env = TestEnv::start().await;
+    let mut addresses = Vec::new();
+    for (locker_name, strike_times) in [("due", &[START_TS + 60, START_TS + 3_600][..]), ("also-due", &[START_TS + 30]), ("not-due", &[START_TS + 3_600])] {
+        env.create_named_locker(locker_name, 1_000_000).await;
+        for strike_time in strike_times {
+            env.time_lock(locker_name, *strike_time, 100_000).await.unwrap();
+        }
+        addresses.push(env.named_locker_address(locker_name));
+    }
+
+    // The keeper is not the authority of the lockers, anyone can crank
+    let keeper = env.fund_new_wallet(1_000_000_000).await;
+
+    env.set_time(START_TS + 60).await;
+
+    // Scan
+    let lockers = env.scan(&addresses).await;
+    assert_eq!(lockers.len(), 3);
+
+    // Plan
+    let planned = plan_unlocks(&lockers, (START_TS + 60) as u32, None);
+    assert_eq!(planned, vec![
+        PlannedUnlock { locker: addresses[0], lock_ids: vec![0], needs_price_feed: false },
+        PlannedUnlock { locker: addresses[1], lock_ids: vec![0], needs_price_feed: false },
+    ]);
+
+    // Submit, one locker per transaction
+    for batch in batches(&planned, 1) {
+        let instruction = crank_instruction(&pricelocker::ID, &keeper.pubkey(), batch, None);
+        env.process(&[instruction], &[&keeper]).await.unwrap();
+    }
+
+    let lockers = env.scan(&addresses).await;
+    assert_eq!(locked(&lockers), vec![vec![false, true], vec![false], vec![true]]);
+
+    // Nothing is left to crank until the next strike time
+    assert!(plan_unlocks(&lockers, (START_TS + 60) as u32, None).is_empty());
+}
+
+#[cfg(feature = "localnet")]
+#[tokio::test]
+async fn keeper_cranks_the_price_locks_at_the_mock_oracle_price() {
+    let mut env = TestEnv::start().await;
+    let feed = env.create_mock_price_feed(90).await;
+    env.create_named_locker("price", 1_000_000).await;
+    env.price_lock("price", 100, 300_000).await.unwrap();
+    env.price_lock("price", 200, 300_000).await.unwrap();
+    env.create_named_locker("time", 1_000_000).await;
+    env.time_lock("time", START_TS + 60, 100_000).await.unwrap();
+    let addresses = [env.named_locker_address("price"), env.named_locker_address("time")];
+    let keeper = env.fund_new_wallet(1_000_000_000).await;
+
+    // The oracle moves to the strike price of the first price lock
+    let admin = env.authority.pubkey();
+    env.set_time(START_TS + 60).await;
+    env.process(&[set_price(&admin, PRICEFEED_ALIAS, 100, START_TS + 60)], &[]).await.unwrap();
+
+    // Scan the lockers and read the price of the feed like the keeper
+    let lockers = env.scan(&addresses).await;
+    let pyth_account = env.get_account(&feed.pyth_price_account).await.unwrap();
+    let price = pricelocker_client::pyth_price(&feed.pyth_price_account, &pyth_account.data, START_TS + 60);
+    let feed_price = FeedPrice { accounts: feed, price };
+    assert_eq!(feed_price.price, Some(100));
+
+    // Plan, only the locker with a price lock to unlock needs the price feed
+    let planned = plan_unlocks(&lockers, (START_TS + 60) as u32, feed_price.reading());
+    assert_eq!(planned, vec![
+        PlannedUnlock { locker: addresses[0], lock_ids: vec![0], needs_price_feed: true },
+        PlannedUnlock { locker: addresses[1], lock_ids: vec![0], needs_price_feed: false },
+    ]);
+
+    // Submit both lockers in one transaction
+    for batch in batches(&planned, 2) {
+        let instruction = crank_instruction(&pricelocker::ID, &keeper.pubkey(), batch, Some(&feed_price));
+        env.process(&[instruction], &[&keeper]).await.unwrap();
+    }
+
+    let lockers = env.scan(&addresses).await;
+    assert_eq!(locked(&lockers), vec![vec![false, true], vec![false]]);
+    assert!(plan_unlocks(&lockers, (START_TS + 60) as u32, feed_price.reading()).is_empty());
+}
+
+#[test]
+fn price_feed_is_only_passed_for_lockers_with_a_price_lock_to_unlock() {
+    let feed = FeedPrice {
+        accounts: PriceFeedAccounts { price_feed: Pubkey::new_unique(), pyth_price_account: Pubkey::new_unique() },
+        price: Some(100),
+    };
+    let batch = [
+        PlannedUnlock { locker: Pubkey::new_unique(), lock_ids: vec![0], needs_price_feed: true },
+        PlannedUnlock { locker: Pubkey::new_unique(), lock_ids: vec![1], needs_price_feed: false },
+    ];
+
+    let instruction = crank_instruction(&pricelocker::ID, &Pubkey::new_unique(), &batch, Some(&feed));
+    let keys: Vec<Pubkey> = instruction.accounts.iter().map(|account| account.pubkey).collect();
+    assert!(keys.contains(&batch[0].locker) && keys.contains(&batch[1].locker));
+    assert_eq!(keys.iter().filter(|key| **key == feed.accounts.price_feed).count(), 1);
+    assert_eq!(keys.iter().filter(|key| **key == feed.accounts.pyth_price_account).count(), 1);
+}
@@ -0,0 +1,105 @@
+use anchor_lang::prelude::Pubkey;
+use pricelocker::{Lock, Locker, PriceReading, WithdrawalAllowlist, WithdrawalCooldown};
+use pricelocker_keeper::plan::{batches, plan_unlocks, PlannedUnlock};
+
+
+const TIME_NOW: u32 = 1_704_067_200;
+
+const PRICE_FEED: Pubkey = Pubkey::new_from_array([8; 32]);
+
+
+fn time_lock(id: u8, strike_time: u32, locked: bool) -> Lock {
+    Lock::TimeLock { id, strike_time, amount: 100, token_mint: None, locked, join: None, lot: None, beneficiary: None, early_exit: None }
+}
+
+fn price_lock(id: u8, strike_price: u32) -> Lock {
+    Lock::PriceLock { id, strike_price, price_feed: PRICE_FEED, amount: 100, token_mint: None, locked: true, join: None, lot: None, beneficiary: None }
+}
+
+fn inactivity_lock(id: u8, period: u32) -> Lock {
+    Lock::Inactivity { id, period, amount: 100, token_mint: None, locked: true, join: None, lot: None, beneficiary: Some(Pubkey::new_unique()) }
+}
+
+fn locker(locks: Vec<Lock>, last_activity_ts: u32) -> (Pubkey, Locker) {
+    let locker = Locker {
+        authority: Pubkey::new_unique(),
+        creation_ts: 0,
+        locked_balance: 0,
+        locks,
+        locked: false,
+        staked: false,
+        bump: 255,
+        lots: Vec::new(),
+        next_lot_id: 0,
+        last_activity_ts,
+        guardians: None,
+        withdrawal_allowlist: WithdrawalAllowlist::default(),
+        withdrawal_cooldown: WithdrawalCooldown::default(),
+        withdrawal_request: None,
+    };
+    (Pubkey::new_unique(), locker)
+}
+
+fn reading(price_feed: Pubkey, price: u32) -> Option<PriceReading> {
+    Some(PriceReading { price_feed, price })
+}
+
+fn planned(count: usize) -> Vec<PlannedUnlock> {
+    (0..count).map(|_| PlannedUnlock { locker: Pubkey::new_unique(), lock_ids: vec![0], needs_price_feed: false }).collect()
+}
+
+
+#[test]
+fn plans_only_the_locks_whose_conditions_are_met() {
+    let lockers = [
+        // Due, not due yet, and already unlocked
+        locker(vec![time_lock(0, TIME_NOW, true), time_lock(1, TIME_NOW + 1, true), time_lock(2, TIME_NOW - 60, false)], TIME_NOW),
+        // Nothing to unlock, the locker isn't planned
+        locker(vec![time_lock(0, TIME_NOW + 60, true)], TIME_NOW),
+        // The authority was inactive for the period of the first lock only
+        locker(vec![inactivity_lock(0, 3_600), inactivity_lock(1, 7_200)], TIME_NOW - 3_600),
+    ];
+
+    let plan = plan_unlocks(&lockers, TIME_NOW, None);
+    assert_eq!(plan, vec![
+        PlannedUnlock { locker: lockers[0].0, lock_ids: vec![0], needs_price_feed: false },
+        PlannedUnlock { locker: lockers[2].0, lock_ids: vec![0], needs_price_feed: false },
+    ]);
+}
+
+#[test]
+fn price_locks_need_a_price_at_or_above_the_strike_price() {
+    let lockers = [locker(vec![price_lock(0, 100), price_lock(1, 200), time_lock(2, TIME_NOW, true)], TIME_NOW)];
+
+    // Without a price only the time lock is planned, and the crank doesn't need the price feed
+    let plan = plan_unlocks(&lockers, TIME_NOW, None);
+    assert_eq!(plan, vec![PlannedUnlock { locker: lockers[0].0, lock_ids: vec![2], needs_price_feed: false }]);
+
+    let plan = plan_unlocks(&lockers, TIME_NOW, reading(PRICE_FEED, 99));
+    assert_eq!(plan[0].lock_ids, vec![2]);
+
+    let plan = plan_unlocks(&lockers, TIME_NOW, reading(PRICE_FEED, 100));
+    assert_eq!(plan, vec![PlannedUnlock { locker: lockers[0].0, lock_ids: vec![0, 2], needs_price_feed: true }]);
+}
+
+#[test]
+fn price_locks_are_only_planned_at_the_price_of_their_price_feed() {
+    let lockers = [locker(vec![price_lock(0, 100), time_lock(1, TIME_NOW, true)], TIME_NOW)];
+
+    let plan = plan_unlocks(&lockers, TIME_NOW, reading(Pubkey::new_unique(), 1_000));
+    assert_eq!(plan, vec![PlannedUnlock { locker: lockers[0].0, lock_ids: vec![1], needs_price_feed: false }]);
+}
+
+#[test]
+fn batches_keep_the_plan_order() {
+    let plan = planned(5);
+
+    let sizes: Vec<usize> = batches(&plan, 2).map(<[PlannedUnlock]>::len).collect();
+    assert_eq!(sizes, vec![2, 2, 1]);
+    let lockers: Vec<Pubkey> = batches(&plan, 2).flatten().map(|planned| planned.locker).collect();
+    assert_eq!(lockers, plan.iter().map(|planned| planned.locker).collect::<Vec<_>>());
+
+    // A batch size of 0 still cranks every locker, one per transaction
+    assert_eq!(batches(&plan, 0).count(), 5);
+    assert_eq!(batches(&[], 5).count(), 0);
+}
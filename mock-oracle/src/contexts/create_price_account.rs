@@ -0,0 +1,46 @@
+use crate::*;
+use anchor_lang::system_program::{create_account, CreateAccount};
+
+
+// Price accounts are differentiated by a price_alias, so 1 admin can have multiple price accounts
+// The account has no Anchor discriminator, its data is only the Pyth price layout
+#[derive(Accounts)]
+#[instruction(price_alias: String)]
+pub struct CreatePriceAccount<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    /// CHECK: created in this instruction, the address is checked by the seeds
+    #[account(mut, seeds = [b"price".as_ref(), admin.key().as_ref(), price_alias.as_ref()], bump)]
+    pub price_account: UncheckedAccount<'info>,
+    #[account(address = system_program::ID)]
+    pub system_program: Program<'info, System>,
+}
+
+impl CreatePriceAccount<'_> {
+    pub fn process(&mut self, price_alias: String, bump: u8, price: i64, conf: u64, expo: i32, publish_time: i64) -> Result<()> {
+        let Self {admin, price_account, system_program} = self;
+
+        // Create the account with the size of a Pyth price account, owned by this program
+        let space = mem::size_of::<PriceAccount>();
+        let lamports = Rent::get()?.minimum_balance(space);
+
+        let admin_key = admin.key();
+        let seeds: &[&[u8]] = &[b"price".as_ref(), admin_key.as_ref(), price_alias.as_ref(), &[bump]];
+
+        create_account(
+            CpiContext::new_with_signer(
+                system_program.to_account_info(),
+                CreateAccount {
+                    from: admin.to_account_info(),
+                    to: price_account.to_account_info(),
+                },
+                &[seeds],
+            ),
+            lamports,
+            space as u64,
+            &crate::ID,
+        )?;
+
+        write_price(&mut price_account.try_borrow_mut_data()?, price, conf, expo, publish_time)
+    }
+}
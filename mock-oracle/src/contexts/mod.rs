@@ -0,0 +1,5 @@
+pub mod create_price_account;
+pub mod set_price;
+
+pub use create_price_account::*;
+pub use set_price::*;
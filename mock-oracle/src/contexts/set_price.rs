@@ -0,0 +1,18 @@
+use crate::*;
+
+
+// Only the admin that created the price account can set its price, as the admin is part of the seeds
+#[derive(Accounts)]
+#[instruction(price_alias: String)]
+pub struct SetPrice<'info> {
+    pub admin: Signer<'info>,
+    /// CHECK: the address is checked by the seeds, the layout when writing the price
+    #[account(mut, seeds = [b"price".as_ref(), admin.key().as_ref(), price_alias.as_ref()], bump, owner = crate::ID)]
+    pub price_account: UncheckedAccount<'info>,
+}
+
+impl SetPrice<'_> {
+    pub fn process(&mut self, price: i64, conf: u64, expo: i32, publish_time: i64) -> Result<()> {
+        write_price(&mut self.price_account.try_borrow_mut_data()?, price, conf, expo, publish_time)
+    }
+}
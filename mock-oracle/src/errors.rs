@@ -0,0 +1,8 @@
+use anchor_lang::prelude::*;
+
+
+#[error_code]
+pub enum MockOracleErrorCode {
+    #[msg("The account is not a price account of the mock oracle")]
+    InvalidPriceAccount,
+}
@@ -0,0 +1,64 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::system_program;
+
+use pyth_sdk_solana::state::{AccountType, PriceAccount, PriceStatus, MAGIC, VERSION_2};
+use std::mem;
+
+pub mod contexts;
+pub mod errors;
+
+pub use contexts::*;
+pub use errors::*;
+
+
+// Mock of the Pyth oracle, to demo and test price locks on a local validator
+// The price accounts are written in the Pyth price layout, so the locker reads them like real Pyth accounts
+//  when it is built with the localnet feature
+declare_id!("C5pTmpN7WYwCDXn9pKW4SKcAimrcmMeBjmERVDX3zds5");
+
+#[program]
+pub mod mock_oracle {
+
+    use super::*;
+
+    /// Create a price account of the admin, identified by an alias (e.g. SOL/USD)
+    pub fn create_price_account(ctx: Context<CreatePriceAccount>, price_alias: String, price: i64, conf: u64, expo: i32, publish_time: i64) -> Result<()> {
+        let bump = ctx.bumps.price_account;
+        ctx.accounts.process(price_alias, bump, price, conf, expo, publish_time)
+    }
+
+    /// Overwrite the price of a price account of the admin
+    pub fn set_price(ctx: Context<SetPrice>, _price_alias: String, price: i64, conf: u64, expo: i32, publish_time: i64) -> Result<()> {
+        ctx.accounts.process(price, conf, expo, publish_time)
+    }
+}
+
+
+// Write a price into the account data, as a trading aggregate price, as previous price and as EMA price
+// The EMA price follows the price right away, a mock has no history to average
+pub(crate) fn write_price(data: &mut [u8], price: i64, conf: u64, expo: i32, publish_time: i64) -> Result<()> {
+    let price_account = data
+        .get_mut(..mem::size_of::<PriceAccount>())
+        .and_then(|data| bytemuck::try_from_bytes_mut::<PriceAccount>(data).ok())
+        .ok_or(MockOracleErrorCode::InvalidPriceAccount)?;
+
+    price_account.magic = MAGIC;
+    price_account.ver = VERSION_2;
+    price_account.atype = AccountType::Price as u32;
+    price_account.size = mem::size_of::<PriceAccount>() as u32;
+    price_account.expo = expo;
+    price_account.timestamp = publish_time;
+
+    price_account.agg.price = price;
+    price_account.agg.conf = conf;
+    price_account.agg.status = PriceStatus::Trading;
+
+    price_account.prev_price = price;
+    price_account.prev_conf = conf;
+    price_account.prev_timestamp = publish_time;
+
+    price_account.ema_price.val = price;
+    price_account.ema_conf.val = conf as i64;
+
+    Ok(())
+}
@@ -0,0 +1,156 @@
+use crate::states::{Lock, Locker, LockerErrorCode};
+use anchor_lang::prelude::Pubkey;
+use std::collections::HashMap;
+
+
+// Get the balance of an asset (None for SOL) that is still locked by the locks
+// Joined locks lock the same funds under two conditions, e.g. 100 $SOL has a time lock (01-01-2025) and a price lock ($1000)
+//  the funds are unlocked as soon as one of the joined locks is unlocked (OR), and are counted once
+// The funds of a lock with a beneficiary stay locked for the authority after the lock is unlocked, until the beneficiary claimed them
+pub fn locked_balance(locks: &[Lock], token_mint: &Option<Pubkey>) -> u64 {
+    locked_balance_where(locks, |lock_item| lock_item.token_mint() == *token_mint)
+}
+
+// Get the balance that is still locked by the locks that match the filter, e.g. the locks of one asset or one lot
+pub(crate) fn locked_balance_where(locks: &[Lock], filter: impl Fn(&Lock) -> bool) -> u64 {
+    let groups = join_groups(locks);
+
+    // Per group of joined locks the largest amount of the matching locks, or nothing when one of the locks is unlocked
+    // Joined locks have the same beneficiary (see check_new_lock), so the whole group is released or reserved for the beneficiary
+    let mut group_balances: HashMap<usize, Option<u64>> = HashMap::new();
+
+    for (lock_item, group) in locks.iter().zip(&groups) {
+        let group_balance = group_balances.entry(*group).or_insert(Some(0));
+
+        if !lock_item.is_locked() && lock_item.beneficiary().is_none() {
+            *group_balance = None;
+        } else if let Some(balance) = group_balance {
+            if filter(lock_item) {
+                *balance = (*balance).max(lock_item.amount() as u64);
+            }
+        }
+    }
+
+    group_balances.values().flatten().sum()
+}
+
+// Assign every lock (by position) to a group of locks that are joined with each other
+// A join to a lock that doesn't exist is ignored, and chains of joins form one group
+fn join_groups(locks: &[Lock]) -> Vec<usize> {
+    let mut groups: Vec<usize> = (0..locks.len()).collect();
+
+    for (position, lock_item) in locks.iter().enumerate() {
+        let joined_position = lock_item.join().and_then(|join| locks.iter().position(|other| other.id() == join));
+
+        if let Some(joined_position) = joined_position {
+            // Merge the two groups into the group with the lowest number
+            let (from, into) = (groups[position].max(groups[joined_position]), groups[position].min(groups[joined_position]));
+            for group in groups.iter_mut().filter(|group| **group == from) {
+                *group = into;
+            }
+        }
+    }
+
+    groups
+}
+
+// Balance of an asset that is not locked, and can be withdrawn or locked by a new lock
+// When the locks exceed the total balance (e.g. the total excludes funds that were never deposited) nothing is withdrawable
+pub fn withdrawable_balance(total: u64, locks: &[Lock], token_mint: &Option<Pubkey>) -> u64 {
+    total.saturating_sub(locked_balance(locks, token_mint))
+}
+
+// Check if a new lock can be added to the locks, given the total balance of its asset
+//  the locker may not hold the maximum number of locks yet, see Locker::MAX_LOCKS
+//  the lock must lock a positive amount, and a joined lock must be a locked lock on the same asset and lot, for the same beneficiary
+//  after adding the lock, the locked balance may not exceed the total balance
+pub fn check_new_lock(total: u64, locks: &[Lock], new_lock: &Lock) -> Result<(), LockerErrorCode> {
+    if locks.len() >= Locker::MAX_LOCKS {
+        return Err(LockerErrorCode::TooManyLocks);
+    }
+
+    // Check if the payout amount is more than 0, otherwise the lock is not locking any funds
+    if new_lock.amount() == 0 {
+        return Err(LockerErrorCode::PayoutAmountNotPositive);
+    }
+
+    // Joined locks lock the same funds, so the other lock must still lock funds of the same asset and lot
+    // A join can't release funds of a beneficiary to the authority, or the other way around
+    if let Some(join) = new_lock.join() {
+        let joined_lock = locks.iter().find(|lock_item| lock_item.id() == join);
+        let same_funds = |joined_lock: &Lock| joined_lock.token_mint() == new_lock.token_mint() && joined_lock.lot() == new_lock.lot()
+            && joined_lock.beneficiary() == new_lock.beneficiary();
+        if !joined_lock.is_some_and(|joined_lock| joined_lock.is_locked() && same_funds(joined_lock)) {
+            return Err(LockerErrorCode::InvalidJoin);
+        }
+    }
+
+    let mut new_locks = locks.to_vec();
+    new_locks.push(*new_lock);
+
+    if locked_balance(&new_locks, &new_lock.token_mint()) > total {
+        return Err(LockerErrorCode::PayoutAmountExceedsAvailableBalance);
+    }
+
+    Ok(())
+}
+
+// Balance of an asset that is locked by time locks of the authority which can not unlock before the given time
+// Price locks, inactivity locks, time locks with an early exit, and locks joined with another lock,
+//  can unlock at any moment and don't count
+// The funds of a lock with a beneficiary are paid out to the beneficiary, so they aren't a commitment of the authority
+pub fn committed_balance(locks: &[Lock], token_mint: &Option<Pubkey>, min_unlock_ts: u32) -> u64 {
+    let groups = join_groups(locks);
+
+    locks.iter().zip(&groups)
+        .filter(|(lock_item, _)| match lock_item {
+            Lock::TimeLock { strike_time, token_mint: lock_token_mint, locked: true, beneficiary: None, early_exit: None, .. } => lock_token_mint == token_mint && *strike_time >= min_unlock_ts,
+            _ => false,
+        })
+        .filter(|(_, group)| groups.iter().filter(|other| other == group).count() == 1)
+        .map(|(lock_item, _)| lock_item.amount() as u64)
+        .sum()
+}
+
+// Balance of a lock with a beneficiary that the beneficiary can claim, once the lock or a lock joined to it is unlocked
+// The claimable balance is the largest amount of the joined locks, claims reduce the amounts of all of them
+pub fn claimable_balance(locks: &[Lock], lock_id: u8) -> Result<u64, LockerErrorCode> {
+    let groups = join_groups(locks);
+    let position = locks.iter().position(|lock_item| lock_item.id() == lock_id).ok_or(LockerErrorCode::NoLockAtIndex)?;
+
+    if locks[position].beneficiary().is_none() {
+        return Err(LockerErrorCode::NoBeneficiary);
+    }
+
+    let joined_locks = || locks.iter().zip(&groups).filter(|(_, group)| **group == groups[position]).map(|(lock_item, _)| lock_item);
+    if joined_locks().all(Lock::is_locked) {
+        return Err(LockerErrorCode::LockNotUnlocked);
+    }
+
+    Ok(joined_locks().map(|lock_item| lock_item.amount() as u64).max().unwrap_or(0))
+}
+
+// Claim an amount of an unlocked lock with a beneficiary, which reduces the amounts of the joined locks
+pub fn claim(locks: &mut [Lock], lock_id: u8, amount: u64) -> Result<(), LockerErrorCode> {
+    if amount > claimable_balance(locks, lock_id)? {
+        return Err(LockerErrorCode::PayoutAmountExceedsAvailableBalance);
+    }
+
+    reduce_joined_locks(locks, lock_id, amount)
+}
+
+// Reduce the amounts of a lock and the locks joined to it, e.g. by a claim or the penalty of an early release
+pub fn reduce_joined_locks(locks: &mut [Lock], lock_id: u8, amount: u64) -> Result<(), LockerErrorCode> {
+    let groups = join_groups(locks);
+    let position = locks.iter().position(|lock_item| lock_item.id() == lock_id).ok_or(LockerErrorCode::NoLockAtIndex)?;
+
+    for (lock_item, group) in locks.iter_mut().zip(&groups) {
+        if *group == groups[position] {
+            // the amounts are u32, a larger amount reduces them to 0
+            let (Lock::TimeLock { amount: lock_amount, .. } | Lock::PriceLock { amount: lock_amount, .. } | Lock::Inactivity { amount: lock_amount, .. }) = lock_item;
+            *lock_amount = lock_amount.saturating_sub(u32::try_from(amount).unwrap_or(u32::MAX));
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,40 @@
+use crate::states::{CircuitBreaker, LockerErrorCode, PriceReference};
+
+use super::BPS_DENOMINATOR;
+
+
+// Circuit breaker of the price unlocks, comparing every price of a feed with the price at the start of the window,
+//  and the first price of a window with the EMA price of the oracle
+
+// Move in basis points of a price from the reference price, up or down
+pub fn price_move_bps(reference_price: u32, price: u32) -> u64 {
+    if reference_price == 0 {
+        return 0;
+    }
+    reference_price.abs_diff(price) as u64 * BPS_DENOMINATOR / reference_price as u64
+}
+
+// Check a price of a feed at time_now against its reference, and return the reference for the next price
+// The price starts a new window when there is no reference yet or the window of the reference passed,
+//  it is compared with the EMA price of the oracle then, which a single manipulated price can't move
+// Fails when the price moved more than the maximum, the reference is kept so the price unlocks stay halted
+//  until the window passed and the price is back near the EMA price
+pub fn check_price_move(circuit_breaker: &CircuitBreaker, reference: PriceReference, price: u32, ema_price: u32, time_now: u32) -> Result<PriceReference, LockerErrorCode> {
+    if !circuit_breaker.is_enabled() {
+        return Ok(reference);
+    }
+
+    let window_passed = time_now.saturating_sub(reference.ts) >= circuit_breaker.window_secs;
+    let new_window = reference.price == 0 || window_passed;
+    let reference_price = if new_window { ema_price } else { reference.price };
+
+    // Without an EMA price the first price of a window can't be checked
+    if reference_price == 0 || price_move_bps(reference_price, price) > circuit_breaker.max_price_move_bps as u64 {
+        return Err(LockerErrorCode::CircuitBreakerTripped);
+    }
+
+    if new_window {
+        return Ok(PriceReference { price, ts: time_now });
+    }
+    Ok(reference)
+}
@@ -0,0 +1,40 @@
+use crate::states::{Lock, LockerErrorCode, PriceReading};
+
+
+// Check if the condition of a lock is met at the given time and price (None when no fresh price is available)
+//  a time lock unlocks when the time reached the strike time
+//  a price lock unlocks when the price of the feed it was created on reached the strike price
+//  an inactivity lock unlocks when the period passed since the last activity of the authority
+pub fn check_unlock_condition(lock_item: &Lock, time_now: u32, price: Option<PriceReading>, last_activity_ts: u32) -> Result<(), LockerErrorCode> {
+    match *lock_item {
+        Lock::TimeLock { strike_time, .. } if time_now < strike_time => Err(LockerErrorCode::TimeLowerThanStrikeTime),
+        Lock::TimeLock { .. } => Ok(()),
+        Lock::PriceLock { strike_price, price_feed, .. } => match price {
+            None => Err(LockerErrorCode::NoPriceFeedForLock),
+            Some(reading) if reading.price_feed != price_feed => Err(LockerErrorCode::WrongPriceFeed),
+            Some(reading) if reading.price < strike_price => Err(LockerErrorCode::StrikePriceTooLow),
+            Some(_) => Ok(()),
+        },
+        Lock::Inactivity { period, .. } if time_now < last_activity_ts.saturating_add(period) => Err(LockerErrorCode::AuthorityStillActive),
+        Lock::Inactivity { .. } => Ok(()),
+    }
+}
+
+// Whether the lock is locked, and its condition is met at the given time and price
+pub fn is_unlockable(lock_item: &Lock, time_now: u32, price: Option<PriceReading>, last_activity_ts: u32) -> bool {
+    lock_item.is_locked() && check_unlock_condition(lock_item, time_now, price, last_activity_ts).is_ok()
+}
+
+// Unlock the lock when its condition is met, unlocking an unlocked lock again is allowed
+pub fn unlock(lock_item: &mut Lock, time_now: u32, price: Option<PriceReading>, last_activity_ts: u32) -> Result<(), LockerErrorCode> {
+    check_unlock_condition(lock_item, time_now, price, last_activity_ts)?;
+    release(lock_item);
+    Ok(())
+}
+
+// Unlock the lock without checking its condition, for an early release
+pub fn release(lock_item: &mut Lock) {
+    match lock_item {
+        Lock::TimeLock { locked, .. } | Lock::PriceLock { locked, .. } | Lock::Inactivity { locked, .. } => *locked = false,
+    }
+}
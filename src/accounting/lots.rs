@@ -0,0 +1,71 @@
+use crate::states::{Lock, LockerErrorCode, Lot};
+use anchor_lang::prelude::Pubkey;
+
+use super::balances::locked_balance_where;
+
+
+// Every deposit is recorded as a lot, and locks on a lot can only lock the funds of that deposit
+// The lots don't change the balances of the locker, withdrawals are still limited by the withdrawable balance
+
+// Get the balance of a lot that is still locked by the locks on the lot
+pub fn lot_locked_balance(locks: &[Lock], lot_id: u16) -> u64 {
+    locked_balance_where(locks, |lock_item| lock_item.lot() == Some(lot_id))
+}
+
+// Check if a new lock fits in its lot, a lock that is not on a lot always fits (see check_new_lock)
+//  the lot must exist and hold the asset of the lock, and after adding the lock
+//  the locked balance of the lot may not exceed the amount of the lot
+pub fn check_lot_lock(lots: &[Lot], locks: &[Lock], new_lock: &Lock) -> Result<(), LockerErrorCode> {
+    let Some(lot_id) = new_lock.lot() else {
+        return Ok(());
+    };
+
+    let lot = lots.iter()
+        .find(|lot| lot.id == lot_id && lot.token_mint == new_lock.token_mint())
+        .ok_or(LockerErrorCode::InvalidLot)?;
+
+    let mut new_locks = locks.to_vec();
+    new_locks.push(*new_lock);
+
+    if lot_locked_balance(&new_locks, lot_id) > lot.amount as u64 {
+        return Err(LockerErrorCode::PayoutAmountExceedsAvailableBalance);
+    }
+
+    Ok(())
+}
+
+// Consume a withdrawn amount of an asset from the lots, oldest lots first (FIFO)
+// Only funds of a lot that are not locked by locks on the lot are consumed, and lots that are consumed entirely are removed
+// Returns the consumed amount, which is less than the amount when the funds were not deposited as a lot
+pub fn consume_lots(lots: &mut Vec<Lot>, locks: &[Lock], token_mint: &Option<Pubkey>, amount: u64) -> u64 {
+    let mut remaining = amount;
+
+    for lot in lots.iter_mut().filter(|lot| lot.token_mint == *token_mint) {
+        let free = (lot.amount as u64).saturating_sub(lot_locked_balance(locks, lot.id));
+        let consumed = free.min(remaining);
+
+        // consumed never exceeds the amount of the lot, which is a u32
+        lot.amount -= consumed as u32;
+        remaining -= consumed;
+    }
+
+    lots.retain(|lot| lot.amount > 0);
+
+    amount - remaining
+}
+
+// Consume a claimed amount from one lot, the funds of a lock on the lot that the beneficiary claimed
+// Returns the consumed amount, which is less than the amount when the lot holds less
+pub fn consume_lot(lots: &mut Vec<Lot>, lot_id: u16, amount: u64) -> u64 {
+    let Some(lot) = lots.iter_mut().find(|lot| lot.id == lot_id) else {
+        return 0;
+    };
+
+    // consumed never exceeds the amount of the lot, which is a u32
+    let consumed = (lot.amount as u64).min(amount);
+    lot.amount -= consumed as u32;
+
+    lots.retain(|lot| lot.amount > 0);
+
+    consumed
+}
@@ -0,0 +1,14 @@
+// Evaluation of locks and accounting of the locked and withdrawable balances of a locker
+// Pure functions without the Solana runtime (no clock, accounts or sysvars), so the rules can be
+//  shared by every instruction and the client, and tested with property tests and fuzzing
+pub mod balances;
+pub mod circuit_breaker;
+pub mod evaluation;
+pub mod lots;
+pub mod penalties;
+
+pub use balances::*;
+pub use circuit_breaker::*;
+pub use evaluation::*;
+pub use lots::*;
+pub use penalties::*;
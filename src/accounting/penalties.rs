@@ -0,0 +1,36 @@
+use crate::states::{EarlyExit, Lock};
+
+
+// Penalties of releasing locked funds before the condition of the lock is met, and protocol fees
+
+// Basis points in 100%
+pub const BPS_DENOMINATOR: u64 = 10_000;
+
+
+// Share in basis points of an amount, e.g. a penalty or a fee, rounded down
+pub fn bps_amount(amount: u64, penalty_bps: u16) -> u64 {
+    // Computed in u128, so the product can't overflow
+    (amount as u128 * penalty_bps as u128 / BPS_DENOMINATOR as u128) as u64
+}
+
+// Penalty in basis points of an early exit at time_now, decaying linearly from the maximum at the start
+//  to zero at the strike time
+pub fn early_exit_penalty_bps(early_exit: &EarlyExit, strike_time: u32, time_now: u32) -> u16 {
+    if time_now >= strike_time {
+        return 0;
+    }
+    if time_now <= early_exit.start_ts {
+        return early_exit.max_penalty_bps;
+    }
+    let remaining = (strike_time - time_now) as u64;
+    let duration = (strike_time - early_exit.start_ts) as u64;
+    (early_exit.max_penalty_bps as u64 * remaining / duration) as u16
+}
+
+// Penalty in basis points of exiting a lock early at time_now, None when the lock is unlocked or has no early exit
+pub fn lock_exit_penalty_bps(lock_item: &Lock, time_now: u32) -> Option<u16> {
+    match *lock_item {
+        Lock::TimeLock { strike_time, early_exit: Some(early_exit), locked: true, .. } => Some(early_exit_penalty_bps(&early_exit, strike_time, time_now)),
+        _ => None,
+    }
+}
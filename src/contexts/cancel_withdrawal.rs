@@ -0,0 +1,39 @@
+use crate::*;
+
+
+// Cancel the withdrawal request of a locker, signed by the authority or one of the guardians
+// A guardian can stop a withdrawal requested with a compromised key of the authority
+#[derive(Accounts)]
+#[instruction(locker_name: String)]
+pub struct CancelWithdrawal<'info> {
+    pub signer: Signer<'info>,
+    #[account(mut,
+        seeds = [b"locker".as_ref(), locker.authority.as_ref(), &locker_name.as_ref()],
+        bump = locker.bump)]
+    pub locker: Account<'info, Locker>,
+}
+
+impl<'info> CancelWithdrawal<'_> {
+    pub fn process(&mut self) -> Result<()> {
+        let Self {signer, locker} = self;
+
+        let is_authority = signer.key() == locker.authority;
+        let is_guardian = locker.guardians.as_ref().is_some_and(|guardians| guardians.guardians.contains(signer.key));
+        require!(is_authority || is_guardian, LockerErrorCode::NotTheAuthorityOrAGuardian);
+
+        // Only the authority checks in, a guardian is not a sign of activity of the authority
+        if is_authority {
+            locker.record_activity()?;
+        }
+
+        let request = locker.withdrawal_request.take().ok_or(LockerErrorCode::NoWithdrawalRequest)?;
+
+        emit!(WithdrawalCancelled {
+            locker: locker.key(),
+            cancelled_by: signer.key(),
+            request,
+        });
+
+        Ok(())
+    }
+}
@@ -0,0 +1,34 @@
+use crate::*;
+
+
+// Cancel the pending change of the withdrawal allowlist before it takes effect
+#[derive(Accounts)]
+#[instruction(locker_name: String)]
+pub struct CancelWithdrawalAllowlistChange<'info> {
+    pub authority: Signer<'info>,
+    #[account(mut, seeds = [b"locker".as_ref(), authority.key().as_ref(), &locker_name.as_ref()],
+        // Ensure that the signer is the authority/owner of the locker
+        constraint = locker.authority == *authority.key,
+        bump)]
+    pub locker: Account<'info, Locker>,
+}
+
+impl<'info> CancelWithdrawalAllowlistChange<'_> {
+    pub fn process(&mut self) -> Result<()> {
+        let Self {authority, locker} = self;
+
+        locker.record_activity()?;
+
+        // A change that already took effect can't be cancelled anymore
+        let time_now = Clock::get()?.unix_timestamp as u32;
+        locker.withdrawal_allowlist.apply_due_change(time_now);
+        require!(locker.withdrawal_allowlist.pending.take().is_some(), LockerErrorCode::NoPendingAllowlistChange);
+
+        emit!(WithdrawalAllowlistChangeCancelled {
+            locker: locker.key(),
+            authority: authority.key(),
+        });
+
+        Ok(())
+    }
+}
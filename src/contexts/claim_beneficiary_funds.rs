@@ -0,0 +1,81 @@
+use crate::*;
+
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+
+// Claim the funds of an unlocked lock by its beneficiary, SOL or SPL tokens
+// The beneficiary signs instead of the authority, and the funds can only be transferred to the beneficiary
+// The withdrawal allowlist is not checked again: the beneficiary was allowed when the funds were promised,
+//  and a later allowlist of the authority can't take them back
+#[derive(Accounts)]
+#[instruction(locker_name: String)]
+pub struct ClaimBeneficiaryFunds<'info> {
+    #[account(mut)]
+    pub beneficiary: Signer<'info>,
+    #[account(mut,
+        seeds = [b"locker".as_ref(), locker.authority.as_ref(), &locker_name.as_ref()],
+        bump = locker.bump)]
+    pub locker: Account<'info, Locker>,
+    #[account(address = system_program::ID)]
+    pub system_program: Program<'info, System>,
+    /// Conditional accounts for a claim of SPL tokens, of the SPL Token or the Token-2022 program
+    #[account()]
+    pub token_mint_account_optional: Option<InterfaceAccount<'info, Mint>>,
+    #[account(mut)]
+    pub token_account_optional: Option<InterfaceAccount<'info, TokenAccount>>,
+    /// CHECK: associated token account of the beneficiary, created when it doesn't exist and checked in process
+    #[account(mut)]
+    pub beneficiary_token_account_optional: Option<UncheckedAccount<'info>>,
+    pub token_program_optional: Option<Interface<'info, TokenInterface>>,
+    pub associated_token_program_optional: Option<Program<'info, AssociatedToken>>,
+}
+
+impl<'info> ClaimBeneficiaryFunds<'_> {
+    pub fn process(&mut self, locker_name: &str, lock_id: u8, amount: u32) -> Result<()> {
+        let Self { beneficiary, locker, system_program, token_mint_account_optional, token_account_optional, beneficiary_token_account_optional, token_program_optional, associated_token_program_optional } = self;
+
+        let lock_item = *locker.locks.iter().find(|lock_item| lock_item.id() == lock_id).ok_or(LockerErrorCode::NoLockAtIndex)?;
+        require!(lock_item.beneficiary() == Some(beneficiary.key()), LockerErrorCode::NotTheBeneficiary);
+
+        // The claimed asset must be the asset of the lock
+        let token_mint = token_mint_account_optional.as_ref().map(|token_mint_account| token_mint_account.key());
+        require!(token_mint == lock_item.token_mint(), LockerErrorCode::InvalidTokenAccount);
+
+        // WITHDRAW_MAX claims everything that is claimable, any other amount must fit in the claimable balance
+        let claimable = accounting::claimable_balance(&locker.locks, lock_id)?;
+        let transfer_amount = if amount == WITHDRAW_MAX { claimable } else { amount as u64 };
+        require!((transfer_amount > 0), LockerErrorCode::PayoutAmountNotPositive);
+
+        // The reserved funds can't have been withdrawn, but the balance is checked before anything is transferred
+        let total_balance = asset_balance(locker, &token_mint, token_account_optional.as_deref())?;
+        require!((transfer_amount <= total_balance), LockerErrorCode::PayoutAmountExceedsAvailableBalance);
+
+        accounting::claim(&mut locker.locks, lock_id, transfer_amount)?;
+
+        // The funds can only be claimed to the beneficiary, who pays the rent of a new token account
+        let token_accounts = LockerTokenAccounts {
+            token_mint_account: token_mint_account_optional.as_ref(),
+            token_account: token_account_optional.as_ref().map(|token_account| token_account.as_ref()),
+            token_program: token_program_optional.as_ref(),
+            associated_token_program: associated_token_program_optional.as_ref(),
+        };
+        let payee = Payee { wallet: beneficiary.as_ref(), token_account: beneficiary_token_account_optional.as_deref() };
+        pay_from_locker(locker, locker_name, beneficiary, system_program, token_accounts, payee, transfer_amount)?;
+
+        // The claim consumes the funds of the lot the lock is on
+        locker.consume_claim(&lock_item, transfer_amount);
+
+        locker.update_locked_balance();
+
+        emit!(BeneficiaryClaimed {
+            locker: locker.key(),
+            beneficiary: beneficiary.key(),
+            lock_id,
+            amount: transfer_amount,
+            token_mint,
+        });
+
+        Ok(())
+    }
+}
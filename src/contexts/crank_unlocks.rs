@@ -0,0 +1,146 @@
+use crate::*;
+use states::PriceFeed;
+
+
+// Unlock all eligible locks over many lockers in one transaction
+// Anyone (e.g. a keeper) can crank, unlocking only flips locks whose condition is already met
+//
+// The lockers and price feeds are passed through the remaining accounts as triples:
+//  [locker, price_feed, pyth_price_account, locker, price_feed, pyth_price_account, ...]
+// When a locker has no price locks, the program id can be passed for both feed accounts (same as optional accounts in Anchor)
+// The price feed is written to move the window of the circuit breaker, so it must be passed as writable
+// Anyone can pass any registered price feed, so a price lock is only unlocked at a price of the feed it was created on
+// While the program is paused or the circuit breaker of the feed tripped, the price locks are skipped
+#[derive(Accounts)]
+pub struct CrankUnlocks<'info> {
+    pub keeper: Signer<'info>,
+    /// CHECK: program config with the pause flag and the circuit breaker, neither applies while it is not initialized
+    #[account(seeds = [b"config".as_ref()], bump)]
+    pub config: UncheckedAccount<'info>,
+}
+
+// Summary of one cranked locker, returned to the caller via return data
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct CrankUnlocksResult {
+    pub locker: Pubkey,
+    // ids of the locks that were unlocked in this crank
+    pub unlocked: Vec<u8>,
+    // number of locked locks that were not (yet) eligible to be unlocked
+    pub skipped: u16,
+}
+
+// Number of accounts passed per locker in the remaining accounts
+const ACCOUNTS_PER_LOCKER: usize = 3;
+
+impl<'info> CrankUnlocks<'info> {
+    pub fn process(&mut self, remaining_accounts: &'info [AccountInfo<'info>], program_id: &Pubkey) -> Result<Vec<CrankUnlocksResult>> {
+
+        // Remaining accounts should be complete triples of locker and feed accounts
+        require!(remaining_accounts.chunks_exact(ACCOUNTS_PER_LOCKER).remainder().is_empty(), LockerErrorCode::InvalidRemainingAccounts);
+
+        // Get the current timestamp once, it is the same for every lock in this transaction
+        let clock: Clock = Clock::get()?;
+        let time_now = clock.unix_timestamp as u32;
+
+        // Price unlocks are halted while paused, the other locks are still cranked
+        let config = ProgramConfig::load(&self.config)?;
+        let price_unlocks_paused = config.as_ref().is_some_and(|config| config.paused);
+        let circuit_breaker = config.map(|config| config.circuit_breaker).unwrap_or_default();
+
+        let mut results = Vec::with_capacity(remaining_accounts.len() / ACCOUNTS_PER_LOCKER);
+
+        for accounts in remaining_accounts.chunks(ACCOUNTS_PER_LOCKER) {
+            let locker_info = &accounts[0];
+
+            // The locker is written back at the end, so it must be passed as writable
+            require!(locker_info.is_writable, LockerErrorCode::InvalidRemainingAccounts);
+
+            // Deserializing checks the owner (this program) and the account discriminator
+            let mut locker: Account<'info, Locker> = Account::try_from(locker_info)?;
+
+            // Retrieve the current price if a price feed is passed for this locker
+            // A stale or offline price feed does not abort the crank, the price locks are skipped instead
+            let price_from_pricefeed = if price_unlocks_paused {
+                None
+            } else {
+                get_crank_price(&accounts[1], &accounts[2], program_id, &circuit_breaker, time_now)?
+            };
+
+            let mut result = CrankUnlocksResult {
+                locker: locker.key(),
+                unlocked: Vec::new(),
+                skipped: 0,
+            };
+
+            let locker_key = locker.key();
+            let authority = locker.authority;
+            let last_activity_ts = locker.last_activity_ts;
+
+            for lock_item in &mut locker.locks {
+                // Already unlocked locks are not eligible and not counted as skipped
+                if !lock_item.is_locked() {
+                    continue;
+                }
+
+                // Ineligible locks are skipped rather than aborting the crank
+                match accounting::unlock(lock_item, time_now, price_from_pricefeed, last_activity_ts) {
+                    Ok(()) => {
+                        // Only price locks are unlocked at an oracle price
+                        let price = match lock_item {
+                            Lock::PriceLock { .. } => price_from_pricefeed.map(|reading| reading.price),
+                            Lock::TimeLock { .. } | Lock::Inactivity { .. } => None,
+                        };
+                        emit!(LockUnlocked::new(locker_key, authority, lock_item, price, time_now));
+                        result.unlocked.push(lock_item.id());
+                    },
+                    Err(_) => result.skipped = result.skipped.saturating_add(1),
+                }
+            }
+
+            // Persist the updated locks and locked balance
+            locker.update_locked_balance();
+            locker.exit(program_id)?;
+
+            results.push(result);
+        }
+
+        Ok(results)
+    }
+}
+
+
+// Load the current price for a locker in the crank, None when no (fresh) price is available
+//  or the circuit breaker of the feed tripped
+fn get_crank_price<'info>(price_feed_info: &'info AccountInfo<'info>, pyth_price_info: &'info AccountInfo<'info>, program_id: &Pubkey, circuit_breaker: &CircuitBreaker, time_now: u32) -> Result<Option<PriceReading>> {
+
+    // The program id is passed in place of the feed accounts when the locker has no price locks
+    if price_feed_info.key == program_id || pyth_price_info.key == program_id {
+        return Ok(None);
+    }
+
+    require!(price_feed_info.is_writable, LockerErrorCode::InvalidRemainingAccounts);
+
+    // Lockers can share a feed, every locker reads the reference written for the previous one
+    let mut price_feed: Account<'info, Pricefeedaccount> = Account::try_from(price_feed_info)?;
+
+    // Check if the given Pyth account matches the address stated in the price feed account
+    require_keys_eq!(pyth_price_info.key(), price_feed.pricefeed_id, PythErrorCode::InvalidArgument);
+
+    // Deserializing checks that the account is owned by the Pyth oracle
+    let mut pyth_price_account: Account<'info, PriceFeed> = Account::try_from(pyth_price_info)?;
+
+    let Ok(price) = get_price_from_pricefeed(&mut pyth_price_account) else {
+        return Ok(None);
+    };
+
+    let ema_price = get_ema_price_from_pricefeed(&mut pyth_price_account).unwrap_or(0);
+
+    match accounting::check_price_move(circuit_breaker, price_feed.price_reference, price, ema_price, time_now) {
+        Ok(price_reference) => {
+            price_feed.price_reference = price_reference;
+            price_feed.exit(program_id)?;
+            Ok(Some(PriceReading { price_feed: price_feed.key(), price }))
+        },
+        Err(_) => Ok(None),
+    }
+}
@@ -27,8 +27,9 @@ impl<'info> CreateNewLocker<'_> {
         // set signer as authority
         locker.authority = authority.key();
 
-        let clock: Clock = Clock::get().unwrap();
+        let clock: Clock = Clock::get()?;
         locker.creation_ts = clock.unix_timestamp as u32;
+        locker.last_activity_ts = locker.creation_ts;
 
         // unlocked_balance starts at 0 as there is no funds deposited yet
         // locker.unlocked_balance = 0;
@@ -39,6 +40,12 @@ impl<'info> CreateNewLocker<'_> {
         
         locker.bump = bump;
 
+        emit!(LockerCreated {
+            locker: locker.key(),
+            authority: locker.authority,
+            creation_ts: locker.creation_ts,
+        });
+
         Ok(())
 
     }
@@ -22,7 +22,7 @@ pub struct CreatePricefeedAccount<'info> {
 
 impl<'info> CreatePricefeedAccount<'_> {
     pub fn process(&mut self, pricefeed_alias: String, pricefeed_id: Pubkey, bump: u8) -> Result<()> {
-        let Self {price_feed,..} = self;
+        let Self {authority, price_feed,..} = self;
 
         price_feed.pricefeed_alias = pricefeed_alias;
 
@@ -31,6 +31,13 @@ impl<'info> CreatePricefeedAccount<'_> {
 
         price_feed.bump = bump;
 
+        emit!(PriceFeedRegistered {
+            price_feed: price_feed.key(),
+            authority: authority.key(),
+            pricefeed_alias: price_feed.pricefeed_alias.clone(),
+            pricefeed_id: price_feed.pricefeed_id,
+        });
+
         Ok(())
 
     }
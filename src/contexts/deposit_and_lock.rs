@@ -0,0 +1,103 @@
+use crate::*;
+
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+
+// Deposit funds and lock that deposit (lot) in one instruction, so the funds are never unprotected in between
+#[derive(Accounts)]
+// amount is a u32 in the instruction data, matching the instruction arguments in lib.rs
+#[instruction(locker_name: String, amount: u32)]
+pub struct DepositAndLock<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(mut,
+        seeds = [b"locker".as_ref(), authority.key().as_ref(), &locker_name.as_ref()],
+        constraint = locker.authority == *authority.key,
+        bump)]
+    pub locker: Account<'info, Locker>,
+    #[account(address = system_program::ID)]
+    pub system_program: Program<'info, System>,
+    /// Conditional accounts for SPL token deposit, of the SPL Token or the Token-2022 program
+    #[account()]
+    pub token_mint_account_optional: Option<InterfaceAccount<'info, Mint>>,
+    #[account(mut)]
+    pub authority_token_account_optional: Option<InterfaceAccount<'info, TokenAccount>>,
+    /// CHECK: associated token account of the locker, created when it doesn't exist yet and validated by the associated token program
+    #[account(mut)]
+    pub token_account_optional: Option<UncheckedAccount<'info>>,
+    pub token_program_optional: Option<Interface<'info, TokenInterface>>,
+    pub associated_token_program_optional: Option<Program<'info, AssociatedToken>>,
+    /// CHECK: program config with the deposit fee and the pause flag, no fee is charged while it is not initialized
+    #[account(seeds = [b"config".as_ref()], bump)]
+    pub config: UncheckedAccount<'info>,
+    /// CHECK: treasury PDA that collects the fee
+    #[account(mut, seeds = [b"treasury".as_ref()], bump)]
+    pub treasury: UncheckedAccount<'info>,
+    /// CHECK: associated token account of the treasury, created when it doesn't exist and checked in pay_from_locker
+    #[account(mut)]
+    pub treasury_token_account_optional: Option<UncheckedAccount<'info>>,
+    /// Conditional accounts to record the oracle price at the deposit in the lot
+    pub price_feed_optional: Option<Account<'info, Pricefeedaccount>>,
+    pub pyth_price_account_optional: Option<Account<'info, PriceFeed>>,
+}
+
+
+impl<'info> DepositAndLock<'_> {
+    pub fn process(&mut self, locker_name: &str, amount: u32, conditions: Vec<LockCondition>, beneficiary: Option<Pubkey>) -> Result<()> {
+        let Self {authority, locker, system_program, token_mint_account_optional, authority_token_account_optional, token_account_optional, token_program_optional, associated_token_program_optional, config, treasury, treasury_token_account_optional, price_feed_optional, pyth_price_account_optional} = self;
+
+        ProgramConfig::check_not_paused(config)?;
+
+        locker.record_activity()?;
+
+        require!(!conditions.is_empty(), LockerErrorCode::NoLockConditions);
+
+        // Make space for the new lot and locks, the authority pays the extra rent
+        grow_locker(locker, authority, 1, conditions.len())?;
+
+        // A token transfer can charge a fee, the lot and the locks hold the amount the locker received
+        let token_accounts = DepositTokenAccounts {
+            authority_token_account: authority_token_account_optional.as_ref(),
+            token_account: token_account_optional.as_ref(),
+            token_program: token_program_optional.as_ref(),
+            associated_token_program: associated_token_program_optional.as_ref(),
+        };
+        let received = transfer_deposit(authority, locker, system_program, token_mint_account_optional.as_ref(), token_accounts, amount, false)?;
+
+        // The deposit fee is not locked
+        let token_accounts = LockerTokenAccounts {
+            token_mint_account: token_mint_account_optional.as_ref(),
+            token_account: token_account_optional.as_deref(),
+            token_program: token_program_optional.as_ref(),
+            associated_token_program: associated_token_program_optional.as_ref(),
+        };
+        let treasury = Payee { wallet: treasury, token_account: treasury_token_account_optional.as_deref() };
+        let fee = collect_deposit_fee(locker, locker_name, authority, system_program, config, token_accounts, treasury, received)?;
+        let amount = received - fee;
+
+        // Record the deposit as a lot
+        let token_mint = token_mint_account_optional.as_ref().map(|token_mint_account| token_mint_account.key());
+        let deposit_price = get_deposit_price(price_feed_optional.as_ref(), pyth_price_account_optional.as_mut())?;
+        let deposit_ts = Clock::get()?.unix_timestamp as u32;
+        let lot_id = locker.add_lot(token_mint, amount, deposit_ts, deposit_price);
+
+        emit!(Deposited {
+            locker: locker.key(),
+            authority: authority.key(),
+            amount: amount as u64,
+            token_mint,
+            lot_id,
+            deposit_price,
+            fee: fee as u64,
+        });
+
+        // Lock the whole deposit, the total balance includes the deposit
+        let token_account = match (token_account_optional.as_ref(), token_mint) {
+            (Some(token_account), Some(_)) => Some(read_token_account(token_account)?),
+            _ => None,
+        };
+        let total_balance = asset_balance(locker, &token_mint, token_account.as_ref())?;
+        lock_lot_funds(locker, authority.key(), lot_id, amount, &conditions, beneficiary, total_balance)
+    }
+}
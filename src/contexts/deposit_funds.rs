@@ -1,11 +1,15 @@
 use crate::*;
 
-use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
-use anchor_spl::associated_token;
+use anchor_spl::associated_token::{self, AssociatedToken};
+use anchor_spl::token_2022::spl_token_2022::extension::{BaseStateWithExtensions, ExtensionType, StateWithExtensions};
+use anchor_spl::token_2022::spl_token_2022::state::Mint as MintState;
+use anchor_spl::token_interface::{self, Mint, SyncNative, TokenAccount, TokenInterface, TransferChecked};
 
 
+// Deposit funds into the locker, every deposit is recorded as a lot
 #[derive(Accounts)]
-#[instruction(locker_name: String, amount: u64)]
+// amount is a u32 in the instruction data, matching the instruction arguments in lib.rs
+#[instruction(locker_name: String, amount: u32)]
 pub struct DepositFunds<'info> {
     #[account(mut)]
     pub authority: Signer<'info>,
@@ -16,79 +20,249 @@ pub struct DepositFunds<'info> {
     pub locker: Account<'info, Locker>,
     #[account(address = system_program::ID)]
     pub system_program: Program<'info, System>,
-    /// Conditional accounts for SPL token deposit
+    /// Conditional accounts for SPL token deposit, of the SPL Token or the Token-2022 program
     #[account()]
-    pub token_mint_account_optional: Option<Account<'info, Mint>>,
-    #[account(mut, constraint = token_account_optional.to_account_info().owner == &token::ID)]
-    pub token_account_optional: Option<Account<'info, TokenAccount>>,
-    #[account(address = token::ID)]
-    pub token_program_optional: Option<Program<'info, Token>>,
+    pub token_mint_account_optional: Option<InterfaceAccount<'info, Mint>>,
+    // Token account of the authority the deposit is transferred from
+    #[account(mut)]
+    pub authority_token_account_optional: Option<InterfaceAccount<'info, TokenAccount>>,
+    /// CHECK: associated token account of the locker, created when it doesn't exist yet and validated by the associated token program
+    #[account(mut)]
+    pub token_account_optional: Option<UncheckedAccount<'info>>,
+    pub token_program_optional: Option<Interface<'info, TokenInterface>>,
+    pub associated_token_program_optional: Option<Program<'info, AssociatedToken>>,
+    /// CHECK: program config with the deposit fee and the pause flag, no fee is charged while it is not initialized
+    #[account(seeds = [b"config".as_ref()], bump)]
+    pub config: UncheckedAccount<'info>,
+    /// CHECK: treasury PDA that collects the fee
+    #[account(mut, seeds = [b"treasury".as_ref()], bump)]
+    pub treasury: UncheckedAccount<'info>,
+    /// CHECK: associated token account of the treasury, created when it doesn't exist and checked in pay_from_locker
+    #[account(mut)]
+    pub treasury_token_account_optional: Option<UncheckedAccount<'info>>,
+    /// Conditional accounts to record the oracle price at the deposit in the lot
+    pub price_feed_optional: Option<Account<'info, Pricefeedaccount>>,
+    pub pyth_price_account_optional: Option<Account<'info, PriceFeed>>,
 }
 
 
 impl<'info> DepositFunds<'_> {
-    pub fn process(&mut self, amount: u64,) -> Result<()> {
-        let Self {authority, locker, system_program, token_account_optional, token_mint_account_optional, token_program_optional,..} = self;
+    pub fn process(&mut self, locker_name: &str, amount: u32, wrap_sol: bool) -> Result<()> {
+        let Self {authority, locker, system_program, token_mint_account_optional, authority_token_account_optional, token_account_optional, token_program_optional, associated_token_program_optional, config, treasury, treasury_token_account_optional, price_feed_optional, pyth_price_account_optional} = self;
 
-        // Check if the deposit is a $SOL or SPL token deposit
-        // if the token_mint is None we assume a SOL deposit, otherwise SPL token deposit
-        if let Some(token_mint_account) = token_mint_account_optional {
+        ProgramConfig::check_not_paused(config)?;
 
+        locker.record_activity()?;
 
-            // Unwrap the optional accounts which must contain addresses needed for the associated token account
-            let token_account = token_account_optional.as_ref().unwrap();
-            let token_program = token_program_optional.as_ref().unwrap();
+        // Make space for the new lot, the authority pays the extra rent
+        grow_locker(locker, authority, 1, 0)?;
 
-             
-            // Create new associated token account with the locker PDA as authority
-            // Anchor does this 'idempotent' if it already exists it doesn't waste compute
-            let cpi_accounts_create = associated_token::Create {
-                payer: authority.to_account_info(),
-                associated_token: token_account.to_account_info(),
-                authority: locker.to_account_info(),
-                mint: token_mint_account.to_account_info(),
-                system_program: system_program.to_account_info(),
-                token_program: token_program.to_account_info(),
-            };
-            associated_token::create(CpiContext::new(token_program.to_account_info(), cpi_accounts_create))?;
+        // A token transfer can charge a fee, the lot holds the amount the locker received
+        let token_accounts = DepositTokenAccounts {
+            authority_token_account: authority_token_account_optional.as_ref(),
+            token_account: token_account_optional.as_ref(),
+            token_program: token_program_optional.as_ref(),
+            associated_token_program: associated_token_program_optional.as_ref(),
+        };
+        let received = transfer_deposit(authority, locker, system_program, token_mint_account_optional.as_ref(), token_accounts, amount, wrap_sol)?;
+
+        let token_accounts = LockerTokenAccounts {
+            token_mint_account: token_mint_account_optional.as_ref(),
+            token_account: token_account_optional.as_deref(),
+            token_program: token_program_optional.as_ref(),
+            associated_token_program: associated_token_program_optional.as_ref(),
+        };
+        let treasury = Payee { wallet: treasury, token_account: treasury_token_account_optional.as_deref() };
+        let fee = collect_deposit_fee(locker, locker_name, authority, system_program, config, token_accounts, treasury, received)?;
+        let amount = received - fee;
+
+        // Record the deposit as a lot
+        let token_mint = token_mint_account_optional.as_ref().map(|token_mint_account| token_mint_account.key());
+        let deposit_price = get_deposit_price(price_feed_optional.as_ref(), pyth_price_account_optional.as_mut())?;
+        let deposit_ts = Clock::get()?.unix_timestamp as u32;
+        let lot_id = locker.add_lot(token_mint, amount, deposit_ts, deposit_price);
+
+        emit!(Deposited {
+            locker: locker.key(),
+            authority: authority.key(),
+            amount: amount as u64,
+            token_mint,
+            lot_id,
+            deposit_price,
+            fee: fee as u64,
+        });
+
+        Ok(())
+    }
+}
+
+
+// Get the current oracle price to record in a lot, when the price feed accounts are passed
+pub(crate) fn get_deposit_price<'info>(price_feed_optional: Option<&Account<'info, Pricefeedaccount>>, pyth_price_account_optional: Option<&mut Account<'info, PriceFeed>>) -> Result<Option<u32>> {
+    match (price_feed_optional, pyth_price_account_optional) {
+        (Some(price_feed), Some(pyth_price_account)) => {
+            // Check if the given account matches the address stated in the price feed account
+            require_keys_eq!(pyth_price_account.key(), price_feed.pricefeed_id, PythErrorCode::InvalidArgument);
+            Ok(Some(get_price_from_pricefeed(pyth_price_account)?))
+        },
+        (None, None) => Ok(None),
+        _ => err!(LockerErrorCode::MissingPriceFeedAccounts),
+    }
+}
+
+// Conditional token accounts of a deposit, shared with deposit_and_lock
+pub(crate) struct DepositTokenAccounts<'a, 'info> {
+    pub authority_token_account: Option<&'a InterfaceAccount<'info, TokenAccount>>,
+    pub token_account: Option<&'a UncheckedAccount<'info>>,
+    pub token_program: Option<&'a Interface<'info, TokenInterface>>,
+    pub associated_token_program: Option<&'a Program<'info, AssociatedToken>>,
+}
+
+// Transfer a deposit of SOL or SPL tokens from the authority to the locker, shared with deposit_and_lock
+// Returns the amount the locker received, which is less than the amount for a token with a transfer fee
+// With wrap_sol the SOL of the authority is wrapped into the native mint token account of the locker
+pub(crate) fn transfer_deposit<'info>(
+    authority: &Signer<'info>,
+    locker: &Account<'info, Locker>,
+    system_program: &Program<'info, System>,
+    token_mint_account_optional: Option<&InterfaceAccount<'info, Mint>>,
+    token_accounts: DepositTokenAccounts<'_, 'info>,
+    amount: u32,
+    wrap_sol: bool,
+) -> Result<u32> {
+    // Wrapped SOL is held as tokens of the native mint, so the native mint must be passed
+    require!(!wrap_sol || token_mint_account_optional.is_some(), LockerErrorCode::MissingTokenAccounts);
+
+    // Check if the deposit is a $SOL or SPL token deposit
+    // if the token_mint is None we assume a SOL deposit, otherwise SPL token deposit
+    if let Some(token_mint_account) = token_mint_account_optional {
+
+
+        // Unwrap the optional accounts which must contain addresses needed for the associated token account
+        let token_account = token_accounts.token_account.ok_or(LockerErrorCode::MissingTokenAccounts)?;
+        let token_program = token_accounts.token_program.ok_or(LockerErrorCode::MissingTokenAccounts)?;
+        let associated_token_program = token_accounts.associated_token_program.ok_or(LockerErrorCode::MissingTokenAccounts)?;
+
+        // The mint belongs to the token program the tokens are transferred with
+        require_keys_eq!(*token_mint_account.to_account_info().owner, token_program.key(), LockerErrorCode::InvalidTokenAccount);
+        check_mint_extensions(token_mint_account)?;
 
 
-            // Transfer the deposit from the authority to the token PDA
-            let cpi_accounts_transfer = Transfer {
-                from: authority.to_account_info(),
+        // Create the associated token account with the locker PDA as authority, if it doesn't exist yet
+        // The associated token program checks that the address is the associated token account of the locker
+        let cpi_accounts_create = associated_token::Create {
+            payer: authority.to_account_info(),
+            associated_token: token_account.to_account_info(),
+            authority: locker.to_account_info(),
+            mint: token_mint_account.to_account_info(),
+            system_program: system_program.to_account_info(),
+            token_program: token_program.to_account_info(),
+        };
+        associated_token::create_idempotent(CpiContext::new(associated_token_program.to_account_info(), cpi_accounts_create))?;
+        let balance_before = read_token_account(token_account)?.amount;
+
+
+        if wrap_sol {
+            // The lamports of a native mint token account above its rent-exempt minimum are its token balance
+            // Transfer the SOL to the token account of the locker, and sync the token balance with the lamports
+            require!(is_native_mint(&token_mint_account.key()), LockerErrorCode::NotNativeMint);
+            invoke(
+                &system_instruction::transfer(authority.key, token_account.key, amount.into()),
+                &[authority.to_account_info(), token_account.to_account_info()],
+            )?;
+            let cpi_accounts_sync = SyncNative {
+                account: token_account.to_account_info(),
+            };
+            token_interface::sync_native(CpiContext::new(token_program.to_account_info(), cpi_accounts_sync))?;
+        } else {
+            // Transfer the deposit from the token account of the authority to the token account of the locker
+            let authority_token_account = token_accounts.authority_token_account.ok_or(LockerErrorCode::MissingTokenAccounts)?;
+            let cpi_accounts_transfer = TransferChecked {
+                from: authority_token_account.to_account_info(),
+                mint: token_mint_account.to_account_info(),
                 to: token_account.to_account_info(),
                 authority: authority.to_account_info(),
             };
             let cpi_context = CpiContext::new(token_program.to_account_info(), cpi_accounts_transfer);
-            token::transfer(cpi_context, amount)?;
+            token_interface::transfer_checked(cpi_context, amount.into(), token_mint_account.decimals)?;
+        }
 
+        // A transfer fee is withheld in the token account of the locker, so only the balance change was received
+        let balance_after = read_token_account(token_account)?.amount;
+        Ok(balance_after.saturating_sub(balance_before) as u32)
 
-        } else {
-            
-            // Transfer funds from signer to the locker
-            invoke(
-                &system_instruction::transfer(
-                    // from authority
-                    &authority.to_account_info().key,
-                    // to locker
-                    &locker.to_account_info().key,
-                    // amount is a u32 type for simpler front-end integrations
-                    // need to convert into u64 for the transfer
-                    amount.into(),             
-                ),
-                &[
-                    // accounts for this transfer
-                    // from authority
-                    authority.to_account_info().clone(),
-                    // to locker
-                    locker.to_account_info().clone(),
-                ],
-            )?;
 
-        }
+    } else {
+        
+        // Transfer funds from signer to the locker
+        invoke(
+            &system_instruction::transfer(
+                // from authority
+                &authority.to_account_info().key,
+                // to locker
+                &locker.to_account_info().key,
+                // amount is a u32 type for simpler front-end integrations
+                // need to convert into u64 for the transfer
+                amount.into(),             
+            ),
+            &[
+                // accounts for this transfer
+                // from authority
+                authority.to_account_info().clone(),
+                // to locker
+                locker.to_account_info().clone(),
+            ],
+        )?;
 
-        Ok(())
+        Ok(amount)
     }
 }
 
+// Transfer the deposit fee of the program config from a deposit the locker received to the treasury,
+//  shared with deposit_and_lock
+// Returns the fee, the lot holds the deposit without the fee
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn collect_deposit_fee<'info>(
+    locker: &mut Account<'info, Locker>,
+    locker_name: &str,
+    authority: &Signer<'info>,
+    system_program: &Program<'info, System>,
+    config: &AccountInfo<'info>,
+    token_accounts: LockerTokenAccounts<'_, 'info>,
+    treasury: Payee<'_, 'info>,
+    received: u32,
+) -> Result<u32> {
+    let fees = ProgramConfig::load_fees(config)?;
+    let fee = accounting::bps_amount(received as u64, fees.deposit_bps);
+    pay_from_locker(locker, locker_name, authority, system_program, token_accounts, treasury, fee)?;
+    Ok(fee as u32)
+}
+
+// Reject Token-2022 mints with extensions that can take or freeze the tokens held by the locker
+// A permanent delegate can transfer the locked tokens, a transfer hook can block the withdrawal
+//  and non-transferable tokens can never be withdrawn
+pub(crate) fn check_mint_extensions(token_mint_account: &InterfaceAccount<Mint>) -> Result<()> {
+    let mint_info = token_mint_account.to_account_info();
+    if *mint_info.owner != token_interface::spl_token_2022::ID {
+        return Ok(());
+    }
+
+    let mint_data = mint_info.try_borrow_data()?;
+    let mint = StateWithExtensions::<MintState>::unpack(&mint_data)?;
+    let unsupported = mint.get_extension_types()?.into_iter().any(|extension_type| matches!(extension_type,
+        ExtensionType::PermanentDelegate | ExtensionType::TransferHook | ExtensionType::NonTransferable));
+    require!(!unsupported, LockerErrorCode::UnsupportedMintExtension);
 
+    Ok(())
+}
+
+// Native mint of the SPL Token or the Token-2022 program, the tokens of wrapped SOL
+pub(crate) fn is_native_mint(token_mint: &Pubkey) -> bool {
+    *token_mint == token_interface::spl_token_2022::native_mint::ID || *token_mint == anchor_spl::token::spl_token::native_mint::ID
+}
+
+// Deserialize a token account of either token program
+pub(crate) fn read_token_account(token_account_info: &AccountInfo) -> Result<TokenAccount> {
+    <TokenAccount as anchor_lang::CheckOwner>::check_owner(token_account_info.owner)?;
+    TokenAccount::try_deserialize(&mut &token_account_info.try_borrow_data()?[..])
+}
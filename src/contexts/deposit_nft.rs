@@ -0,0 +1,81 @@
+use crate::*;
+
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+
+// Deposit an NFT (or an amount of a semi-fungible token) and optionally lock it on one or more conditions
+// NFTs and semi-fungible tokens are mints with 0 decimals, every mint is held in its own token account of the locker
+// The locks of the deposit lock that specific mint, so the locker can hold many NFTs with their own locks
+#[derive(Accounts)]
+#[instruction(locker_name: String)]
+pub struct DepositNft<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(mut,
+        seeds = [b"locker".as_ref(), authority.key().as_ref(), &locker_name.as_ref()],
+        constraint = locker.authority == *authority.key,
+        bump)]
+    pub locker: Account<'info, Locker>,
+    #[account(address = system_program::ID)]
+    pub system_program: Program<'info, System>,
+    #[account(constraint = nft_mint.decimals == 0 @ LockerErrorCode::NotAnNft)]
+    pub nft_mint: InterfaceAccount<'info, Mint>,
+    #[account(mut)]
+    pub authority_token_account: InterfaceAccount<'info, TokenAccount>,
+    /// CHECK: associated token account of the locker for the NFT, created when it doesn't exist yet and validated by the associated token program
+    #[account(mut)]
+    pub token_account: UncheckedAccount<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    /// CHECK: program config, the instruction fails while the program is paused
+    #[account(seeds = [b"config".as_ref()], bump)]
+    pub config: UncheckedAccount<'info>,
+}
+
+
+impl<'info> DepositNft<'_> {
+    pub fn process(&mut self, amount: u32, conditions: Vec<LockCondition>, beneficiary: Option<Pubkey>) -> Result<()> {
+        let Self {authority, locker, system_program, nft_mint, authority_token_account, token_account, token_program, associated_token_program, config} = self;
+
+        ProgramConfig::check_not_paused(config)?;
+
+        locker.record_activity()?;
+
+        // Make space for the new lot and locks, the authority pays the extra rent
+        grow_locker(locker, authority, 1, conditions.len())?;
+
+        let token_accounts = DepositTokenAccounts {
+            authority_token_account: Some(authority_token_account),
+            token_account: Some(token_account),
+            token_program: Some(token_program),
+            associated_token_program: Some(associated_token_program),
+        };
+        let amount = transfer_deposit(authority, locker, system_program, Some(nft_mint), token_accounts, amount, false)?;
+
+        // Record the deposit as a lot, an NFT has no oracle price
+        let token_mint = Some(nft_mint.key());
+        let deposit_ts = Clock::get()?.unix_timestamp as u32;
+        let lot_id = locker.add_lot(token_mint, amount, deposit_ts, None);
+
+        emit!(Deposited {
+            locker: locker.key(),
+            authority: authority.key(),
+            amount: amount as u64,
+            token_mint,
+            lot_id,
+            deposit_price: None,
+            // A share in basis points of an NFT is always zero, NFTs are deposited without a fee
+            fee: 0,
+        });
+
+        // Without conditions the NFT is deposited unlocked, and can be locked later with lock_lot
+        if conditions.is_empty() {
+            return Ok(());
+        }
+
+        let token_account = read_token_account(token_account)?;
+        let total_balance = asset_balance(locker, &token_mint, Some(&token_account))?;
+        lock_lot_funds(locker, authority.key(), lot_id, amount, &conditions, beneficiary, total_balance)
+    }
+}
@@ -0,0 +1,82 @@
+use crate::*;
+
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+
+// Execute the withdrawal request of a locker once its cooldown passed
+// The funds go to the destination of the request, which must still be on the withdrawal allowlist
+#[derive(Accounts)]
+#[instruction(locker_name: String)]
+pub struct ExecuteWithdrawal<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(mut, seeds = [b"locker".as_ref(), authority.key().as_ref(), &locker_name.as_ref()],
+        // Ensure that the signer is the authority/owner of the locker
+        constraint = locker.authority == *authority.key,
+        bump)]
+    pub locker: Account<'info, Locker>,
+    #[account(address = system_program::ID)]
+    pub system_program: Program<'info, System>,
+    /// CHECK: wallet that receives the withdrawal, checked against the request in process
+    #[account(mut)]
+    pub destination: UncheckedAccount<'info>,
+    /// Conditional accounts for a withdrawal of SPL tokens, of the SPL Token or the Token-2022 program
+    #[account()]
+    pub token_mint_account_optional: Option<InterfaceAccount<'info, Mint>>,
+    #[account(mut)]
+    pub token_account_optional: Option<InterfaceAccount<'info, TokenAccount>>,
+    /// CHECK: associated token account of the destination, created when it doesn't exist and checked in pay_from_locker
+    #[account(mut)]
+    pub destination_token_account_optional: Option<UncheckedAccount<'info>>,
+    pub token_program_optional: Option<Interface<'info, TokenInterface>>,
+    pub associated_token_program_optional: Option<Program<'info, AssociatedToken>>,
+}
+
+impl<'info> ExecuteWithdrawal<'_> {
+    pub fn process(&mut self, locker_name: &str) -> Result<()> {
+        let Self {authority, locker, system_program, destination, token_mint_account_optional, token_account_optional, destination_token_account_optional, token_program_optional, associated_token_program_optional} = self;
+
+        locker.record_activity()?;
+
+        let request = locker.withdrawal_request.ok_or(LockerErrorCode::NoWithdrawalRequest)?;
+        let time_now = Clock::get()?.unix_timestamp as u32;
+        require!(time_now >= request.executable_ts, LockerErrorCode::WithdrawalCooldownNotPassed);
+
+        // The allowlist may have changed during the cooldown
+        require_keys_eq!(destination.key(), request.destination, LockerErrorCode::NotTheRequestedWithdrawal);
+        locker.check_destination(destination.key)?;
+
+        let token_mint = token_mint_account_optional.as_ref().map(|token_mint_account| token_mint_account.key());
+        require!(token_mint == request.token_mint, LockerErrorCode::NotTheRequestedWithdrawal);
+
+        // Funds may have been locked or withdrawn during the cooldown
+        let total_balance = asset_balance(locker, &token_mint, token_account_optional.as_deref())?;
+        let withdrawable = accounting::withdrawable_balance(total_balance, &locker.locks, &token_mint);
+        require!((request.amount <= withdrawable), LockerErrorCode::PayoutAmountExceedsAvailableBalance);
+
+        let token_accounts = LockerTokenAccounts {
+            token_mint_account: token_mint_account_optional.as_ref(),
+            token_account: token_account_optional.as_ref().map(|token_account| token_account.as_ref()),
+            token_program: token_program_optional.as_ref(),
+            associated_token_program: associated_token_program_optional.as_ref(),
+        };
+        let payee = Payee { wallet: destination, token_account: destination_token_account_optional.as_deref() };
+        pay_from_locker(locker, locker_name, authority, system_program, token_accounts, payee, request.amount)?;
+
+        // The withdrawal consumes the oldest deposits first
+        locker.consume_lots(&token_mint, request.amount);
+        locker.update_locked_balance();
+        locker.withdrawal_request = None;
+
+        emit!(Withdrawn {
+            locker: locker.key(),
+            authority: authority.key(),
+            amount: request.amount,
+            token_mint,
+            destination: destination.key(),
+        });
+
+        Ok(())
+    }
+}
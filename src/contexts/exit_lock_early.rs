@@ -0,0 +1,92 @@
+use crate::*;
+
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+
+// Exit a time lock with an early exit before its strike time, signed by the authority alone
+// The authority forfeits a penalty that decays toward zero as the strike time approaches,
+//  which is transferred to the penalty destination recorded in the lock
+#[derive(Accounts)]
+#[instruction(locker_name: String)]
+pub struct ExitLockEarly<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(mut, seeds = [b"locker".as_ref(), authority.key().as_ref(), &locker_name.as_ref()],
+        // Ensure that the signer is the authority/owner of the locker
+        constraint = locker.authority == *authority.key,
+        bump)]
+    pub locker: Account<'info, Locker>,
+    #[account(address = system_program::ID)]
+    pub system_program: Program<'info, System>,
+    /// CHECK: program config with the early exit fee, no fee is charged while it is not initialized
+    #[account(seeds = [b"config".as_ref()], bump)]
+    pub config: UncheckedAccount<'info>,
+    /// CHECK: treasury PDA that collects the fee
+    #[account(mut, seeds = [b"treasury".as_ref()], bump)]
+    pub treasury: UncheckedAccount<'info>,
+    /// CHECK: wallet that receives the penalty, checked against the early exit of the lock in process
+    #[account(mut)]
+    pub penalty_destination: UncheckedAccount<'info>,
+    /// Conditional accounts for a lock of SPL tokens, of the SPL Token or the Token-2022 program
+    #[account()]
+    pub token_mint_account_optional: Option<InterfaceAccount<'info, Mint>>,
+    #[account(mut)]
+    pub token_account_optional: Option<InterfaceAccount<'info, TokenAccount>>,
+    /// CHECK: associated token account of the penalty destination, created when it doesn't exist and checked in pay_from_locker
+    #[account(mut)]
+    pub penalty_token_account_optional: Option<UncheckedAccount<'info>>,
+    /// CHECK: associated token account of the treasury, created when it doesn't exist and checked in pay_from_locker
+    #[account(mut)]
+    pub treasury_token_account_optional: Option<UncheckedAccount<'info>>,
+    pub token_program_optional: Option<Interface<'info, TokenInterface>>,
+    pub associated_token_program_optional: Option<Program<'info, AssociatedToken>>,
+}
+
+impl<'info> ExitLockEarly<'_> {
+    pub fn process(&mut self, locker_name: &str, lock_id: u8) -> Result<()> {
+        let Self {authority, locker, system_program, config, treasury, penalty_destination, token_mint_account_optional, token_account_optional, penalty_token_account_optional, treasury_token_account_optional, token_program_optional, associated_token_program_optional} = self;
+
+        locker.record_activity()?;
+
+        let lock_item = *locker.locks.iter().find(|lock_item| lock_item.id() == lock_id).ok_or(LockerErrorCode::NoLockAtIndex)?;
+        let Lock::TimeLock { strike_time, early_exit: Some(early_exit), .. } = lock_item else {
+            return err!(LockerErrorCode::NoEarlyExit);
+        };
+        require_keys_eq!(penalty_destination.key(), early_exit.penalty_destination, LockerErrorCode::InvalidPenaltyDestination);
+
+        let time_now = Clock::get()?.unix_timestamp as u32;
+        let penalty_bps = accounting::early_exit_penalty_bps(&early_exit, strike_time, time_now);
+
+        // The penalty destination was allowed when the lock was created, the allowlist may have changed since
+        // Without a penalty nothing is paid to it, so a lock can always be exited at its strike time
+        if penalty_bps > 0 {
+            locker.check_destination(penalty_destination.key)?;
+        }
+
+        let fees = ProgramConfig::load_fees(config)?;
+        let token_accounts = LockerTokenAccounts {
+            token_mint_account: token_mint_account_optional.as_ref(),
+            token_account: token_account_optional.as_ref().map(|token_account| token_account.as_ref()),
+            token_program: token_program_optional.as_ref(),
+            associated_token_program: associated_token_program_optional.as_ref(),
+        };
+        let penalty_payee = Payee { wallet: penalty_destination, token_account: penalty_token_account_optional.as_deref() };
+        let fee_payee = Payee { wallet: treasury, token_account: treasury_token_account_optional.as_deref() };
+        let (released, penalty, fee) = release_with_penalty(locker, locker_name, authority, system_program, token_accounts, penalty_payee, fee_payee, lock_id, penalty_bps, fees.early_exit_bps)?;
+
+        emit!(ExitedEarly {
+            locker: locker.key(),
+            authority: authority.key(),
+            lock_id,
+            token_mint: lock_item.token_mint(),
+            released,
+            penalty,
+            penalty_bps,
+            penalty_destination: penalty_destination.key(),
+            fee,
+        });
+
+        Ok(())
+    }
+}
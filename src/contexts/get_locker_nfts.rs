@@ -0,0 +1,58 @@
+use crate::*;
+
+
+// Read-only view of the NFTs (and semi-fungible tokens) held by a locker, with their lock state, returned via return data
+// The token accounts of the locker are passed through the remaining accounts, one per NFT mint
+#[derive(Accounts)]
+pub struct GetLockerNfts<'info> {
+    pub locker: Account<'info, Locker>,
+}
+
+// An NFT held by the locker
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct NftStatus {
+    pub mint: Pubkey,
+    // 1 for an NFT, any amount for a semi-fungible token
+    pub amount: u64,
+    // Amount locked by the locks on the mint
+    pub locked: u64,
+    // Locks on the mint that are still locked
+    pub lock_ids: Vec<u8>,
+}
+
+impl<'info> GetLockerNfts<'info> {
+    pub fn process(&mut self, remaining_accounts: &'info [AccountInfo<'info>]) -> Result<Vec<NftStatus>> {
+        let Self {locker} = self;
+
+        let mut nfts = Vec::with_capacity(remaining_accounts.len());
+        for token_account_info in remaining_accounts {
+            // Token accounts are closed once the NFT is withdrawn
+            if token_account_info.data_is_empty() {
+                continue;
+            }
+
+            let token_account = read_token_account(token_account_info)?;
+            require_keys_eq!(token_account.owner, locker.key(), LockerErrorCode::InvalidRemainingAccounts);
+
+            // Semi-fungible token accounts may be left empty
+            if token_account.amount == 0 {
+                continue;
+            }
+
+            let token_mint = Some(token_account.mint);
+            let lock_ids = locker.locks.iter()
+                .filter(|lock_item| lock_item.is_locked() && lock_item.token_mint() == token_mint)
+                .map(|lock_item| lock_item.id())
+                .collect();
+
+            nfts.push(NftStatus {
+                mint: token_account.mint,
+                amount: token_account.amount,
+                locked: locker.get_locked_balance(&token_mint).min(token_account.amount),
+                lock_ids,
+            });
+        }
+
+        Ok(nfts)
+    }
+}
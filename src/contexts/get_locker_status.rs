@@ -0,0 +1,160 @@
+use crate::*;
+use states::PriceFeed;
+
+
+// Read-only view of the balances and locks of a locker, returned via return data
+// Frontends can simulate this instruction, and other programs can CPI into it
+//
+// The token accounts of the locker are passed through the remaining accounts, one per SPL token
+// The price feed accounts are optional, without them price locks are never reported as unlockable
+// The program config is optional too, without it no fees are reported
+#[derive(Accounts)]
+pub struct GetLockerStatus<'info> {
+    pub locker: Account<'info, Locker>,
+    pub price_feed: Option<Account<'info, Pricefeedaccount>>,
+    pub pyth_price_account: Option<Account<'info, PriceFeed>>,
+    /// CHECK: program config at its PDA, the fees are zero while it is not initialized
+    #[account(seeds = [b"config".as_ref()], bump)]
+    pub config: Option<UncheckedAccount<'info>>,
+}
+
+// Balances of one asset held by the locker
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct AssetStatus {
+    // None for SOL
+    pub token_mint: Option<Pubkey>,
+    // Deposited balance, for SOL this excludes the rent-exempt minimum of the locker
+    pub total: u64,
+    // Balance still locked by the locks
+    pub locked: u64,
+    // Locked balance of locks whose condition is met, and can be unlocked now
+    pub unlockable_now: u64,
+    // Balance that can be withdrawn without unlocking any lock
+    pub withdrawable: u64,
+}
+
+// State of one lock in the locker
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct LockStatus {
+    pub lock_id: u8,
+    pub token_mint: Option<Pubkey>,
+    pub amount: u32,
+    pub locked: bool,
+    // Whether the lock is locked and its condition is met at the current time and price
+    pub unlockable_now: bool,
+    pub lot: Option<u16>,
+    // The wallet that claims the funds once unlocked, None when released to the authority
+    pub beneficiary: Option<Pubkey>,
+    // Penalty of an early exit at the current time, None when the lock has no early exit
+    pub early_exit_penalty_bps: Option<u16>,
+}
+
+// A deposit that is not withdrawn yet, with the part that is locked by locks on the lot
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct LotStatus {
+    pub lot_id: u16,
+    pub token_mint: Option<Pubkey>,
+    pub amount: u32,
+    pub deposit_ts: u32,
+    pub deposit_price: Option<u32>,
+    pub locked: u64,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct LockerStatus {
+    pub locker: Pubkey,
+    pub authority: Pubkey,
+    pub assets: Vec<AssetStatus>,
+    pub locks: Vec<LockStatus>,
+    pub lots: Vec<LotStatus>,
+    // Protocol fees that apply to the deposits and early exits of the locker
+    pub fees: Fees,
+    // Withdrawal waiting for its cooldown, see request_withdrawal
+    pub withdrawal_request: Option<WithdrawalRequest>,
+}
+
+impl<'info> GetLockerStatus<'info> {
+    pub fn process(&mut self, remaining_accounts: &'info [AccountInfo<'info>]) -> Result<LockerStatus> {
+        let Self {locker, price_feed, pyth_price_account, config} = self;
+
+        let time_now = Clock::get()?.unix_timestamp as u32;
+
+        // Retrieve the current price when the price feed accounts are passed
+        // A stale price is not an error for a view, the price locks are just not unlockable
+        let price_from_pricefeed = match (price_feed, pyth_price_account) {
+            (Some(price_feed), Some(pyth_price_account)) => {
+                // Check if the given Pyth account matches the address stated in the price feed account
+                require_keys_eq!(pyth_price_account.key(), price_feed.pricefeed_id, PythErrorCode::InvalidArgument);
+                get_price_from_pricefeed(pyth_price_account).ok().map(|price| PriceReading { price_feed: price_feed.key(), price })
+            },
+            _ => None,
+        };
+
+        // Evaluate every lock with the same checks used to unlock it
+        let locks: Vec<LockStatus> = locker.locks.iter().map(|lock_item| LockStatus {
+            lock_id: lock_item.id(),
+            token_mint: lock_item.token_mint(),
+            amount: lock_item.amount(),
+            locked: lock_item.is_locked(),
+            unlockable_now: accounting::is_unlockable(lock_item, time_now, price_from_pricefeed, locker.last_activity_ts),
+            lot: lock_item.lot(),
+            beneficiary: lock_item.beneficiary(),
+            early_exit_penalty_bps: accounting::lock_exit_penalty_bps(lock_item, time_now),
+        }).collect();
+
+        let lots: Vec<LotStatus> = locker.lots.iter().map(|lot| LotStatus {
+            lot_id: lot.id,
+            token_mint: lot.token_mint,
+            amount: lot.amount,
+            deposit_ts: lot.deposit_ts,
+            deposit_price: lot.deposit_price,
+            locked: accounting::lot_locked_balance(&locker.locks, lot.id),
+        }).collect();
+
+        // SOL balance of the locker, without the rent-exempt minimum which can never be withdrawn
+        let rent_exempt_minimum = Rent::get()?.minimum_balance(locker.to_account_info().data_len());
+        let mut balances: Vec<(Option<Pubkey>, u64)> = vec![(None, locker.get_lamports().saturating_sub(rent_exempt_minimum))];
+
+        // SPL token balances from the token accounts owned by the locker
+        for token_account_info in remaining_accounts {
+            let token_account = read_token_account(token_account_info)?;
+            require_keys_eq!(token_account.owner, locker.key(), LockerErrorCode::InvalidRemainingAccounts);
+            balances.push((Some(token_account.mint), token_account.amount));
+        }
+
+        // Tokens that are locked but of which no token account is passed, are reported with a balance of 0
+        for lock_item in &locker.locks {
+            if !balances.iter().any(|(token_mint, _)| *token_mint == lock_item.token_mint()) {
+                balances.push((lock_item.token_mint(), 0));
+            }
+        }
+
+        let assets = balances.into_iter().map(|(token_mint, total)| {
+            let locked = locker.get_locked_balance(&token_mint);
+            // Joined locks lock the same funds, so the unlockable balance never exceeds the locked balance
+            let unlockable_now = locks.iter()
+                .filter(|lock_status| lock_status.unlockable_now && lock_status.token_mint == token_mint)
+                .map(|lock_status| lock_status.amount as u64)
+                .sum::<u64>()
+                .min(locked);
+
+            AssetStatus {
+                token_mint,
+                total,
+                locked,
+                unlockable_now,
+                withdrawable: accounting::withdrawable_balance(total, &locker.locks, &token_mint),
+            }
+        }).collect();
+
+        Ok(LockerStatus {
+            locker: locker.key(),
+            authority: locker.authority,
+            assets,
+            locks,
+            lots,
+            fees: config.as_ref().map_or(Ok(Fees::default()), |config| ProgramConfig::load_fees(config))?,
+            withdrawal_request: locker.withdrawal_request,
+        })
+    }
+}
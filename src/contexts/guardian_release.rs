@@ -0,0 +1,237 @@
+use crate::*;
+
+use anchor_spl::associated_token::{self, AssociatedToken};
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked};
+
+
+// Release a lock before its condition is met, approved by the threshold of guardians of the locker
+// The guardians sign the same transaction and are passed through the remaining accounts
+// A penalty of the released funds is transferred to the penalty destination of the guardians
+#[derive(Accounts)]
+#[instruction(locker_name: String)]
+pub struct GuardianRelease<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(mut, seeds = [b"locker".as_ref(), authority.key().as_ref(), &locker_name.as_ref()],
+        // Ensure that the signer is the authority/owner of the locker
+        constraint = locker.authority == *authority.key,
+        bump)]
+    pub locker: Account<'info, Locker>,
+    #[account(address = system_program::ID)]
+    pub system_program: Program<'info, System>,
+    /// CHECK: program config with the early exit fee, no fee is charged while it is not initialized
+    #[account(seeds = [b"config".as_ref()], bump)]
+    pub config: UncheckedAccount<'info>,
+    /// CHECK: treasury PDA that collects the fee
+    #[account(mut, seeds = [b"treasury".as_ref()], bump)]
+    pub treasury: UncheckedAccount<'info>,
+    /// CHECK: wallet that receives the penalty, checked against the guardian config in process
+    #[account(mut)]
+    pub penalty_destination: UncheckedAccount<'info>,
+    /// Conditional accounts for a lock of SPL tokens, of the SPL Token or the Token-2022 program
+    #[account()]
+    pub token_mint_account_optional: Option<InterfaceAccount<'info, Mint>>,
+    #[account(mut)]
+    pub token_account_optional: Option<InterfaceAccount<'info, TokenAccount>>,
+    /// CHECK: associated token account of the penalty destination, created when it doesn't exist and checked in pay_from_locker
+    #[account(mut)]
+    pub penalty_token_account_optional: Option<UncheckedAccount<'info>>,
+    /// CHECK: associated token account of the treasury, created when it doesn't exist and checked in pay_from_locker
+    #[account(mut)]
+    pub treasury_token_account_optional: Option<UncheckedAccount<'info>>,
+    pub token_program_optional: Option<Interface<'info, TokenInterface>>,
+    pub associated_token_program_optional: Option<Program<'info, AssociatedToken>>,
+}
+
+impl<'info> GuardianRelease<'info> {
+    pub fn process(&mut self, locker_name: &str, lock_id: u8, remaining_accounts: &[AccountInfo<'info>]) -> Result<()> {
+        let Self {authority, locker, system_program, config, treasury, penalty_destination, token_mint_account_optional, token_account_optional, penalty_token_account_optional, treasury_token_account_optional, token_program_optional, associated_token_program_optional} = self;
+
+        locker.record_activity()?;
+
+        let guardians = locker.guardians.clone().ok_or(LockerErrorCode::NoGuardians)?;
+        require_keys_eq!(penalty_destination.key(), guardians.penalty_destination, LockerErrorCode::InvalidPenaltyDestination);
+
+        // Only guardians that signed this transaction approve the release
+        let signers: Vec<Pubkey> = remaining_accounts.iter().filter(|account| account.is_signer).map(|account| account.key()).collect();
+        let approvals = guardians.approvals(&signers);
+        require!(approvals >= guardians.threshold as usize, LockerErrorCode::NotEnoughGuardianApprovals);
+
+        // The funds of a beneficiary lock are promised to the beneficiary, a penalty can't divert them to another wallet
+        let lock_item = *locker.locks.iter().find(|lock_item| lock_item.id() == lock_id).ok_or(LockerErrorCode::NoLockAtIndex)?;
+        if let Some(beneficiary) = lock_item.beneficiary() {
+            require!(guardians.penalty_bps == 0 || guardians.penalty_destination == beneficiary, LockerErrorCode::PenaltyNotToTheBeneficiary);
+        }
+
+        // The penalty destination was allowed when the guardians were set, the allowlist may have changed since
+        if guardians.penalty_bps > 0 {
+            locker.check_destination(penalty_destination.key)?;
+        }
+
+        let fees = ProgramConfig::load_fees(config)?;
+        let token_accounts = LockerTokenAccounts {
+            token_mint_account: token_mint_account_optional.as_ref(),
+            token_account: token_account_optional.as_ref().map(|token_account| token_account.as_ref()),
+            token_program: token_program_optional.as_ref(),
+            associated_token_program: associated_token_program_optional.as_ref(),
+        };
+        let penalty_payee = Payee { wallet: penalty_destination, token_account: penalty_token_account_optional.as_deref() };
+        let fee_payee = Payee { wallet: treasury, token_account: treasury_token_account_optional.as_deref() };
+        let token_mint = token_accounts.token_mint_account.map(|token_mint_account| token_mint_account.key());
+        let (released, penalty, fee) = release_with_penalty(locker, locker_name, authority, system_program, token_accounts, penalty_payee, fee_payee, lock_id, guardians.penalty_bps, fees.early_exit_bps)?;
+
+        emit!(EarlyReleased {
+            locker: locker.key(),
+            authority: authority.key(),
+            lock_id,
+            token_mint,
+            released,
+            penalty,
+            penalty_destination: penalty_destination.key(),
+            fee,
+            approvals: guardians.guardians.iter().filter(|guardian| signers.contains(guardian)).copied().collect(),
+        });
+
+        Ok(())
+    }
+}
+
+
+// Release a locked lock before its condition is met, paying the penalty in basis points of the released funds
+//  to the destination and the early exit fee of the program config to the treasury
+// Returns the released funds, including the penalty and the fee, the penalty and the fee
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn release_with_penalty<'info>(
+    locker: &mut Account<'info, Locker>,
+    locker_name: &str,
+    authority: &Signer<'info>,
+    system_program: &Program<'info, System>,
+    token_accounts: LockerTokenAccounts<'_, 'info>,
+    penalty_payee: Payee<'_, 'info>,
+    fee_payee: Payee<'_, 'info>,
+    lock_id: u8,
+    penalty_bps: u16,
+    fee_bps: u16,
+) -> Result<(u64, u64, u64)> {
+    let position = locker.locks.iter().position(|lock_item| lock_item.id() == lock_id).ok_or(LockerErrorCode::NoLockAtIndex)?;
+    let lock_item = locker.locks[position];
+    require!(lock_item.is_locked(), LockerErrorCode::LockAlreadyUnlocked);
+
+    let token_mint = token_accounts.token_mint_account.map(|token_mint_account| token_mint_account.key());
+    require!(token_mint == lock_item.token_mint(), LockerErrorCode::InvalidTokenAccount);
+
+    // The released funds are the funds that are no longer locked, or that the beneficiary can claim now
+    let locked_before = locker.get_locked_balance(&token_mint);
+    accounting::release(&mut locker.locks[position]);
+    let released = match lock_item.beneficiary() {
+        Some(_) => accounting::claimable_balance(&locker.locks, lock_id)?,
+        None => locked_before.saturating_sub(locker.get_locked_balance(&token_mint)),
+    };
+
+    let penalty = accounting::bps_amount(released, penalty_bps);
+    let fee = accounting::bps_amount(released, fee_bps);
+    pay_from_locker(locker, locker_name, authority, system_program, token_accounts, penalty_payee, penalty)?;
+    pay_from_locker(locker, locker_name, authority, system_program, token_accounts, fee_payee, fee)?;
+
+    let forfeited = penalty + fee;
+    if forfeited > 0 {
+        // The beneficiary claims what is left after the penalty and the fee
+        if lock_item.beneficiary().is_some() {
+            accounting::reduce_joined_locks(&mut locker.locks, lock_id, forfeited)?;
+        }
+        locker.consume_claim(&lock_item, forfeited);
+    }
+
+    locker.update_locked_balance();
+    Ok((released, penalty, fee))
+}
+
+// Conditional accounts of the SPL tokens held by the locker (or the treasury), of the SPL Token or the Token-2022 program
+#[derive(Clone, Copy)]
+pub(crate) struct LockerTokenAccounts<'a, 'info> {
+    pub token_mint_account: Option<&'a InterfaceAccount<'info, Mint>>,
+    // Token account of the locker, or of the treasury for the fees
+    pub token_account: Option<&'a AccountInfo<'info>>,
+    pub token_program: Option<&'a Interface<'info, TokenInterface>>,
+    pub associated_token_program: Option<&'a Program<'info, AssociatedToken>>,
+}
+
+// Wallet that receives a payment from the locker, with its associated token account for SPL tokens
+pub(crate) struct Payee<'a, 'info> {
+    pub wallet: &'a AccountInfo<'info>,
+    pub token_account: Option<&'a AccountInfo<'info>>,
+}
+
+// Transfer SOL or SPL tokens from the locker to a payee, e.g. a withdrawal, a claim, a penalty or a fee
+pub(crate) fn pay_from_locker<'info>(
+    locker: &Account<'info, Locker>,
+    locker_name: &str,
+    payer: &Signer<'info>,
+    system_program: &Program<'info, System>,
+    token_accounts: LockerTokenAccounts<'_, 'info>,
+    payee: Payee<'_, 'info>,
+    amount: u64,
+) -> Result<()> {
+    // The locker PDA owns its token accounts, so the program signs with the seeds of the locker
+    let authority_key = locker.authority;
+    let signer_seeds: &[&[&[u8]]] = &[&[b"locker".as_ref(), authority_key.as_ref(), locker_name.as_ref(), &[locker.bump]]];
+    pay_from_pda(&locker.to_account_info(), signer_seeds, payer, system_program, token_accounts, payee, amount)
+}
+
+// Transfer SOL or SPL tokens from a PDA of the program (a locker or the treasury) to a payee
+// Tokens go to the token account of the payee, which the payer creates as its associated token account
+//  when the associated token program is passed and it doesn't exist yet
+pub(crate) fn pay_from_pda<'info>(
+    source: &AccountInfo<'info>,
+    signer_seeds: &[&[&[u8]]],
+    payer: &Signer<'info>,
+    system_program: &Program<'info, System>,
+    token_accounts: LockerTokenAccounts<'_, 'info>,
+    payee: Payee<'_, 'info>,
+    amount: u64,
+) -> Result<()> {
+    if amount == 0 {
+        return Ok(());
+    }
+
+    let Some(token_mint_account) = token_accounts.token_mint_account else {
+        // The program owns the PDA, so it moves the lamports directly
+        source.sub_lamports(amount)?;
+        payee.wallet.add_lamports(amount)?;
+        return Ok(());
+    };
+
+    // Unwrap the optional accounts which must contain addresses needed for the token transfer
+    let token_account = token_accounts.token_account.ok_or(LockerErrorCode::MissingTokenAccounts)?;
+    let payee_token_account = payee.token_account.ok_or(LockerErrorCode::MissingTokenAccounts)?;
+    let token_program = token_accounts.token_program.ok_or(LockerErrorCode::MissingTokenAccounts)?;
+    require_keys_eq!(read_token_account(token_account)?.owner, source.key(), LockerErrorCode::InvalidTokenAccount);
+
+    // The payee may never have held the token
+    if let Some(associated_token_program) = token_accounts.associated_token_program {
+        let cpi_accounts_create = associated_token::Create {
+            payer: payer.to_account_info(),
+            associated_token: payee_token_account.clone(),
+            authority: payee.wallet.clone(),
+            mint: token_mint_account.to_account_info(),
+            system_program: system_program.to_account_info(),
+            token_program: token_program.to_account_info(),
+        };
+        associated_token::create_idempotent(CpiContext::new(associated_token_program.to_account_info(), cpi_accounts_create))?;
+    }
+
+    // The tokens can only be transferred to a token account of the payee
+    let payee_account = read_token_account(payee_token_account)?;
+    require_keys_eq!(payee_account.owner, payee.wallet.key(), LockerErrorCode::InvalidTokenAccount);
+    require_keys_eq!(payee_account.mint, token_mint_account.key(), LockerErrorCode::InvalidTokenAccount);
+
+    // A transfer fee is withheld from the amount the payee receives
+    let cpi_accounts = TransferChecked {
+        from: token_account.clone(),
+        mint: token_mint_account.to_account_info(),
+        to: payee_token_account.clone(),
+        authority: source.clone(),
+    };
+    let cpi_context = CpiContext::new_with_signer(token_program.to_account_info(), cpi_accounts, signer_seeds);
+    token_interface::transfer_checked(cpi_context, amount, token_mint_account.decimals)
+}
@@ -0,0 +1,31 @@
+use crate::*;
+
+
+// Check in as the authority of a locker, which restarts the period of its inactivity locks
+// Any other instruction signed by the authority checks in as well
+#[derive(Accounts)]
+#[instruction(locker_name: String)]
+pub struct Heartbeat<'info> {
+    pub authority: Signer<'info>,
+    #[account(mut, seeds = [b"locker".as_ref(), authority.key().as_ref(), &locker_name.as_ref()],
+        // Ensure that the signer is the authority/owner of the locker
+        constraint = locker.authority == *authority.key,
+        bump)]
+    pub locker: Account<'info, Locker>,
+}
+
+impl<'info> Heartbeat<'_> {
+    pub fn process(&mut self) -> Result<()> {
+        let Self {authority, locker} = self;
+
+        locker.record_activity()?;
+
+        emit!(CheckedIn {
+            locker: locker.key(),
+            authority: authority.key(),
+            last_activity_ts: locker.last_activity_ts,
+        });
+
+        Ok(())
+    }
+}
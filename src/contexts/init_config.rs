@@ -0,0 +1,56 @@
+use crate::*;
+
+use anchor_lang::solana_program::bpf_loader_upgradeable;
+
+
+// Create the program config and the treasury, the signer becomes the admin
+// There is one config per program, only the upgrade authority of the program can initialize it,
+//  otherwise anyone could take the admin role between the deployment and the initialization
+#[derive(Accounts)]
+pub struct InitConfig<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    #[account(init,
+        payer = admin,
+        // Room for settings that are added later
+        space = 8 + mem::size_of::<ProgramConfig>() + 64,
+        seeds = [b"config".as_ref()],
+        bump)]
+    pub config: Account<'info, ProgramConfig>,
+    #[account(init,
+        payer = admin,
+        space = 8 + mem::size_of::<Treasury>(),
+        seeds = [b"treasury".as_ref()],
+        bump)]
+    pub treasury: Account<'info, Treasury>,
+    // The program data account of the deployment, which records the upgrade authority
+    #[account(
+        seeds = [crate::ID.as_ref()],
+        bump,
+        seeds::program = bpf_loader_upgradeable::ID,
+        constraint = program_data.upgrade_authority_address == Some(admin.key()) @ LockerErrorCode::NotTheUpgradeAuthority)]
+    pub program_data: Account<'info, ProgramData>,
+    #[account(address = system_program::ID)]
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> InitConfig<'_> {
+    pub fn process(&mut self, fees: Fees, bump: u8, treasury_bump: u8) -> Result<()> {
+        let Self {admin, config, treasury, ..} = self;
+
+        fees.validate()?;
+
+        config.admin = admin.key();
+        config.fees = fees;
+        config.bump = bump;
+        config.treasury_bump = treasury_bump;
+        treasury.bump = treasury_bump;
+
+        emit!(ConfigUpdated {
+            admin: config.admin,
+            fees,
+        });
+
+        Ok(())
+    }
+}
@@ -0,0 +1,121 @@
+use crate::*;
+
+use anchor_spl::token_interface::TokenAccount;
+
+
+// Lock the funds of a lot (an earlier deposit) on one or more conditions
+// A condition can be relative to the deposit, e.g. unlock 90 days after the deposit
+#[derive(Accounts)]
+#[instruction(locker_name: String, lot_id: u16, amount: u32, conditions: Vec<LockCondition>)]
+pub struct LockLot<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(mut, seeds = [b"locker".as_ref(), authority.key().as_ref(), &locker_name.as_ref()],
+        // Ensure that the signer is the authority/owner of the locker
+        constraint = locker.authority == *authority.key,
+        bump)]
+    pub locker: Account<'info, Locker>,
+    #[account(address = system_program::ID)]
+    pub system_program: Program<'info, System>,
+    /// CHECK: program config, the instruction fails while the program is paused
+    #[account(seeds = [b"config".as_ref()], bump)]
+    pub config: UncheckedAccount<'info>,
+    /// Conditional account for a lot of SPL tokens, to read the token balance of the locker
+    pub token_account_optional: Option<InterfaceAccount<'info, TokenAccount>>,
+}
+
+impl<'info> LockLot<'_> {
+    pub fn process(&mut self, lot_id: u16, amount: u32, conditions: Vec<LockCondition>, beneficiary: Option<Pubkey>) -> Result<()> {
+        let Self {authority, locker, config, token_account_optional,..} = self;
+
+        ProgramConfig::check_not_paused(config)?;
+
+        locker.record_activity()?;
+
+        // Make space for the new locks before the balance is read, the extra rent is not part of the balance
+        grow_locker(locker, authority, 0, conditions.len())?;
+
+        let token_mint = locker.get_lot(lot_id).ok_or(LockerErrorCode::InvalidLot)?.token_mint;
+        let total_balance = asset_balance(locker, &token_mint, token_account_optional.as_deref())?;
+
+        lock_lot_funds(locker, authority.key(), lot_id, amount, &conditions, beneficiary, total_balance)
+    }
+}
+
+
+// Add a lock on an amount of a lot for every condition, shared with deposit_and_lock
+// The first lock locks the funds, the other locks are joined to it and lock the same funds,
+//  so the funds unlock as soon as one of the conditions is met
+// With a beneficiary, only the beneficiary can claim the funds once unlocked, the authority can't withdraw them
+pub(crate) fn lock_lot_funds(locker: &mut Account<Locker>, authority: Pubkey, lot_id: u16, amount: u32, conditions: &[LockCondition], beneficiary: Option<Pubkey>, total_balance: u64) -> Result<()> {
+    require!(!conditions.is_empty(), LockerErrorCode::NoLockConditions);
+
+    // Funds of an inactivity lock are inherited, there must be an heir
+    let has_inactivity_condition = conditions.iter().any(|condition| matches!(condition, LockCondition::Inactivity { .. }));
+    require!(!has_inactivity_condition || beneficiary.is_some(), LockerErrorCode::InactivityLockWithoutHeir);
+
+    // The penalty of an early exit can't forfeit more than the funds, and can't return to the authority
+    // Funds promised to a beneficiary can't be exited early by the authority
+    let valid_early_exits = conditions.iter().all(|condition| match condition {
+        LockCondition::TimeWithEarlyExit { max_penalty_bps, penalty_destination, .. } =>
+            *max_penalty_bps as u64 <= accounting::BPS_DENOMINATOR && *penalty_destination != authority && beneficiary.is_none(),
+        _ => true,
+    });
+    require!(valid_early_exits, LockerErrorCode::InvalidEarlyExit);
+
+    // The beneficiary and the penalty destination receive funds of the locker, like a withdrawal
+    // With a cooldown they would get the funds without waiting for it, so they can only be added without a cooldown
+    //  (a shorter cooldown takes effect after the cooldown in effect, which delays them as long as a withdrawal)
+    let time_now = Clock::get()?.unix_timestamp as u32;
+    let pays_out = beneficiary.is_some() || conditions.iter().any(|condition| matches!(condition, LockCondition::TimeWithEarlyExit { .. }));
+    require!(!pays_out || locker.withdrawal_cooldown.active_cooldown_secs(time_now) == 0, LockerErrorCode::WithdrawalCooldownActive);
+    if let Some(beneficiary) = beneficiary {
+        locker.check_destination(&beneficiary)?;
+    }
+    for condition in conditions {
+        if let LockCondition::TimeWithEarlyExit { penalty_destination, .. } = condition {
+            locker.check_destination(penalty_destination)?;
+        }
+    }
+
+    let lot = *locker.get_lot(lot_id).ok_or(LockerErrorCode::InvalidLot)?;
+    let first_lock_id = locker.locks.len() as u8;
+
+    for (index, condition) in conditions.iter().enumerate() {
+        // The lock id is equivalent to the position in the vector
+        let lock_id = locker.locks.len() as u8;
+        let join = if index == 0 { None } else { Some(first_lock_id) };
+        let new_lock = condition.to_lock(lock_id, amount, &lot, join, beneficiary, time_now);
+
+        // Check if the amount is positive and doesn't exceed the balance that is not locked yet,
+        //  of the asset and of the lot
+        accounting::check_new_lock(total_balance, &locker.locks, &new_lock)?;
+        accounting::check_lot_lock(&locker.lots, &locker.locks, &new_lock)?;
+        locker.locks.push(new_lock);
+
+        emit!(LockCreated::new(locker.key(), authority, &new_lock));
+    }
+
+    // Update the locked balance
+    locker.update_locked_balance();
+
+    Ok(())
+}
+
+// Get the total balance of an asset of the locker, the token account is required for SPL tokens
+// The rent-exempt minimum of the locker can never be withdrawn, so it doesn't count
+pub(crate) fn asset_balance(locker: &Account<Locker>, token_mint: &Option<Pubkey>, token_account_optional: Option<&TokenAccount>) -> Result<u64> {
+    match token_mint {
+        Some(token_mint) => {
+            // The balance is read from the token account, so it must be the token account of the locker
+            let token_account = token_account_optional.ok_or(LockerErrorCode::MissingTokenAccounts)?;
+            require_keys_eq!(token_account.owner, locker.key(), LockerErrorCode::InvalidTokenAccount);
+            require_keys_eq!(token_account.mint, *token_mint, LockerErrorCode::InvalidTokenAccount);
+            Ok(token_account.amount)
+        },
+        None => {
+            let rent_exempt_minimum = Rent::get()?.minimum_balance(locker.to_account_info().data_len());
+            Ok(locker.get_lamports().saturating_sub(rent_exempt_minimum))
+        },
+    }
+}
@@ -0,0 +1,65 @@
+use crate::*;
+use anchor_lang::Discriminator;
+
+
+// Rewrite a locker created before lots, beneficiaries and guardians were added in the current layout
+// Legacy lockers can't be deserialized as Locker anymore, so every other instruction fails on them until they are migrated
+// The account is resized to the current layout, the authority pays the extra rent and the deposited lamports stay untouched
+// The legacy price locks are bound to the passed price feed, which is required when the locker has price locks
+#[derive(Accounts)]
+#[instruction(locker_name: String)]
+pub struct MigrateLocker<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    /// CHECK: legacy locker of the authority, decoded as LegacyLocker in process
+    #[account(mut,
+        seeds = [b"locker".as_ref(), authority.key().as_ref(), locker_name.as_ref()],
+        bump,
+        owner = crate::ID)]
+    pub locker: UncheckedAccount<'info>,
+    pub price_feed_optional: Option<Account<'info, Pricefeedaccount>>,
+    #[account(address = system_program::ID)]
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> MigrateLocker<'_> {
+    pub fn process(&mut self) -> Result<()> {
+        let Self {authority, locker, price_feed_optional, ..} = self;
+        let locker_info = locker.to_account_info();
+
+        // Legacy lockers were never resized, a locker of any other size is in the current layout
+        require!(locker_info.data_len() == LegacyLocker::SPACE, LockerErrorCode::NotALegacyLocker);
+        let legacy_locker = {
+            let data = locker_info.try_borrow_data()?;
+            require!(data[..8] == Locker::DISCRIMINATOR, LockerErrorCode::NotALegacyLocker);
+            LegacyLocker::deserialize(&mut &data[8..]).map_err(|_| LockerErrorCode::NotALegacyLocker)?
+        };
+        require_keys_eq!(legacy_locker.authority, authority.key(), LockerErrorCode::NotALegacyLocker);
+
+        let time_now = Clock::get()?.unix_timestamp as u32;
+        let price_feed = price_feed_optional.as_ref().map(|price_feed| price_feed.key());
+        let migrated_locker = legacy_locker.migrate(price_feed, time_now)?;
+
+        // Resize the account to the current layout, with at least the space of a new locker
+        //  so a migrated locker never has the size of a legacy locker again
+        let rent = Rent::get()?;
+        let current_space = locker_info.data_len();
+        let required_space = migrated_locker.required_space(0, 0).max(8 + mem::size_of::<Locker>() + 100);
+        let extra_rent = rent.minimum_balance(required_space).saturating_sub(rent.minimum_balance(current_space));
+        invoke(
+            &system_instruction::transfer(authority.key, locker_info.key, extra_rent),
+            &[authority.to_account_info(), locker_info.clone()],
+        )?;
+        locker_info.realloc(required_space, true)?;
+
+        let mut data = locker_info.try_borrow_mut_data()?;
+        migrated_locker.try_serialize(&mut &mut data[..])?;
+
+        emit!(LockerMigrated {
+            locker: locker_info.key(),
+            authority: authority.key(),
+        });
+
+        Ok(())
+    }
+}
@@ -1,19 +1,71 @@
 pub mod create_new_locker;
+pub mod migrate_locker;
 pub mod deposit_funds;
+pub mod deposit_and_lock;
+pub mod lock_lot;
+pub mod deposit_nft;
+pub mod withdraw_nft;
 pub mod price_lock_funds;
 pub mod time_lock_funds;
 pub mod time_unlock_funds;
 pub mod price_unlock_funds;
 pub mod withdraw_unlocked_funds;
+pub mod set_withdrawal_cooldown;
+pub mod request_withdrawal;
+pub mod execute_withdrawal;
+pub mod cancel_withdrawal;
+pub mod claim_beneficiary_funds;
+pub mod heartbeat;
+pub mod revoke_inactivity_lock;
+pub mod set_withdrawal_allowlist;
+pub mod cancel_withdrawal_allowlist_change;
+pub mod set_guardians;
+pub mod guardian_release;
+pub mod exit_lock_early;
+pub mod init_config;
+pub mod update_config;
+pub mod set_paused;
+pub mod set_circuit_breaker;
+pub mod withdraw_fees;
 pub mod create_pricefeed_account;
 pub mod stake_funds;
+pub mod crank_unlocks;
+pub mod get_locker_status;
+pub mod verify_lock;
+pub mod get_locker_nfts;
 
 pub use deposit_funds::*;
+pub use deposit_and_lock::*;
+pub use lock_lot::*;
+pub use deposit_nft::*;
+pub use withdraw_nft::*;
 pub use create_new_locker::*;
+pub use migrate_locker::*;
 pub use price_lock_funds::*;
 pub use time_lock_funds::*;
 pub use time_unlock_funds::*;
 pub use price_unlock_funds::*;
 pub use withdraw_unlocked_funds::*;
+pub use set_withdrawal_cooldown::*;
+pub use request_withdrawal::*;
+pub use execute_withdrawal::*;
+pub use cancel_withdrawal::*;
+pub use claim_beneficiary_funds::*;
+pub use heartbeat::*;
+pub use revoke_inactivity_lock::*;
+pub use set_withdrawal_allowlist::*;
+pub use cancel_withdrawal_allowlist_change::*;
+pub use set_guardians::*;
+pub use guardian_release::*;
+pub use exit_lock_early::*;
+pub use init_config::*;
+pub use update_config::*;
+pub use set_paused::*;
+pub use set_circuit_breaker::*;
+pub use withdraw_fees::*;
 pub use create_pricefeed_account::*;
-pub use stake_funds::*;
\ No newline at end of file
+pub use stake_funds::*;
+pub use crank_unlocks::*;
+pub use get_locker_status::*;
+pub use verify_lock::*;
+pub use get_locker_nfts::*;
\ No newline at end of file
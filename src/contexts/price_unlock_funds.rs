@@ -11,6 +11,8 @@ use anchor_lang::solana_program::pubkey::Pubkey;
 pub struct PriceUnlockFunds<'info> {
     #[account(mut)]
     pub authority: Signer<'info>,
+    // Writable to move the window of the circuit breaker
+    #[account(mut)]
     pub price_feed: Account<'info, Pricefeedaccount>,
     #[account(mut, seeds = [b"locker".as_ref(), authority.key().as_ref(), &locker_name.as_ref()],
         // Ensure that the signer is the authority/owner of the locker
@@ -23,24 +25,61 @@ pub struct PriceUnlockFunds<'info> {
     pub pyth_solprice_account: Account<'info, PriceFeed>,
     #[account(address = system_program::ID)]
     pub system_program: Program<'info, System>,
+    /// CHECK: program config with the pause flag and the circuit breaker, neither applies while it is not initialized
+    #[account(seeds = [b"config".as_ref()], bump)]
+    pub config: UncheckedAccount<'info>,
 }
 
 impl<'info> PriceUnlockFunds<'_> {
-    pub fn process(&mut self, lock_index: u8) -> Result<()> {
-        let Self {ref mut locker, pyth_solprice_account,..} = self;
+    pub fn process(&mut self, lock_index: u8) -> Result<UnlockResult> {
+        let Self {ref mut locker, price_feed, pyth_solprice_account, config,..} = self;
+
+        ProgramConfig::check_not_paused(config)?;
+
+        locker.record_activity()?;
+
+        // Retrieve the current price from Pyth once, currently this is the SOL price
+        let price_from_pricefeed = get_price_from_pricefeed(pyth_solprice_account)?;
+
+        let locker_key = locker.key();
+        let authority = locker.authority;
+        let time_now = Clock::get()?.unix_timestamp as u32;
+
+        // Fails while the price moved too much within the window of the circuit breaker
+        let circuit_breaker = ProgramConfig::load_circuit_breaker(config)?;
+        // Without an EMA price the first price of a window can't be checked, which trips an enabled circuit breaker
+        let ema_price = get_ema_price_from_pricefeed(pyth_solprice_account).unwrap_or(0);
+        price_feed.price_reference = accounting::check_price_move(&circuit_breaker, price_feed.price_reference, price_from_pricefeed, ema_price, time_now)?;
+
+        // Only the price locks created on this price feed are unlocked at its price
+        let reading = PriceReading { price_feed: price_feed.key(), price: price_from_pricefeed };
+
+        let mut result = UnlockResult::default();
 
         // Users can choose whether they want to unlock all unlockable funds,or just a specific one
         // Lock_index is the index used to retrieve the lock object in the locks vector
         match lock_index {
             // index 255 is code for: unlock all unlockable-locks
+            // Locks that can't be unlocked (yet) are skipped and reported, instead of failing the transaction
             255 => {
 
                 // Loops through all available locks
                 for lock_item in &mut locker.locks {
 
+                    // Already unlocked locks are not eligible and not reported
+                    if !lock_item.is_locked() {
+                        continue;
+                    }
+
                     // Check if price lock can be openend (asset price exceeds strike price)
                     // Unlock locker if true
-                    process_price_lock(lock_item, pyth_solprice_account).unwrap();
+                    match process_price_lock(lock_item, reading) {
+                        Ok(()) => {
+                            emit!(LockUnlocked::new(locker_key, authority, lock_item, Some(price_from_pricefeed), time_now));
+                            result.unlocked.push(lock_item.id());
+                        },
+                        Err(reason) => result.skipped.push(SkippedLock { lock_id: lock_item.id(), reason }),
+                    }
                 }
             },
             // any other index than 255 leads to trying to retrieve the lock and unlock the funds
@@ -49,24 +88,32 @@ impl<'info> PriceUnlockFunds<'_> {
                 // Retrieves price lock from locks vector by the index
                 let lock_item = locker.locks
                     .get_mut(index as usize)
-                    .ok_or(LockerErrorCode::NoLockAtIndex)
-                    .unwrap();
+                    .ok_or(LockerErrorCode::NoLockAtIndex)?;
+
+                let was_locked = lock_item.is_locked();
 
                 // Check if price lock can be openend (asset price exceeds strike price)
                 // Unlock locker if true
-                process_price_lock(lock_item, pyth_solprice_account).unwrap();
+                process_price_lock(lock_item, reading)?;
+
+                if was_locked {
+                    emit!(LockUnlocked::new(locker_key, authority, lock_item, Some(price_from_pricefeed), time_now));
+                    result.unlocked.push(lock_item.id());
+                }
 
             }
         }
 
-        Ok(())
+        locker.update_locked_balance();
+
+        Ok(result)
 
     }
 }
 
 
 // Retrieve price from Pyth pricefeed for comparison with strike price
-fn get_price_from_pricefeed<'info>(pricefeed_account: &mut Account<'info, PriceFeed>) -> Result<u32> {
+pub(crate) fn get_price_from_pricefeed<'info>(pricefeed_account: &mut Account<'info, PriceFeed>) -> Result<u32> {
 
     // Get the current timestamp
     let current_timestamp = Clock::get()?.unix_timestamp;
@@ -85,25 +132,27 @@ fn get_price_from_pricefeed<'info>(pricefeed_account: &mut Account<'info, PriceF
 
 }
 
-// Open up locks of which the current price is larger than the strike_price stated in the lock (as earlier defined by the user)
-fn process_price_lock<'info>(lock_item: &mut Lock, pyth_solprice_account: &mut Account<'info, PriceFeed>) -> Result<()> {
-
-    // Retrieve the current price from Pyth, currently this is the SOL price
-    let price_from_pricefeed = get_price_from_pricefeed(pyth_solprice_account).unwrap();
-
-    // Check if lock is a price lock, and if so access the values 
-    if let Lock::PriceLock { id, strike_price, amount, token_mint, locked, join } = lock_item {
-        // Check if the price of the asset exceeds the strike_price defined in the locker
-        if price_from_pricefeed >= *strike_price {
-            // asset price exceeds strike_price so unlock the lock
-            *locked = false;
-            Ok(())
-        } else {
-            Err(LockerErrorCode::StrikePriceTooLow.into())
-        }
-    } else {
-        Err(LockerErrorCode::NotAPriceLock.into())
-    }
+// Retrieve the EMA price from Pyth pricefeed, the independent reference of the circuit breaker
+pub(crate) fn get_ema_price_from_pricefeed<'info>(pricefeed_account: &mut Account<'info, PriceFeed>) -> Result<u32> {
+    let current_timestamp = Clock::get()?.unix_timestamp;
+
+    // The EMA price is published with the price, and held to the same maximum age of 60 seconds
+    let ema_price = pricefeed_account
+        .get_ema_price_no_older_than(current_timestamp, 60)
+        .ok_or(PythErrorCode::PythOffline)?;
+
+    Ok(ema_price.price as u32)
 }
 
+// Open up locks of which the current price is larger than the strike_price stated in the lock (as earlier defined by the user)
+pub(crate) fn process_price_lock(lock_item: &mut Lock, reading: PriceReading) -> std::result::Result<(), LockerErrorCode> {
+
+    // Check if lock is a price lock, only price locks are unlocked by price
+    if !matches!(lock_item, Lock::PriceLock { .. }) {
+        return Err(LockerErrorCode::NotAPriceLock);
+    }
 
+    // Unlock the lock when the price of the asset exceeds the strike_price defined in the locker
+    // The time and the last activity are not used for price locks
+    accounting::unlock(lock_item, 0, Some(reading), 0)
+}
@@ -0,0 +1,62 @@
+use crate::*;
+
+use anchor_spl::token_interface::TokenAccount;
+
+
+// Request a withdrawal from a locker with a withdrawal cooldown, executable once the cooldown passed
+// The request replaces an earlier request that was not executed yet
+#[derive(Accounts)]
+#[instruction(locker_name: String)]
+pub struct RequestWithdrawal<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(mut, seeds = [b"locker".as_ref(), authority.key().as_ref(), &locker_name.as_ref()],
+        // Ensure that the signer is the authority/owner of the locker
+        constraint = locker.authority == *authority.key,
+        bump)]
+    pub locker: Account<'info, Locker>,
+    #[account(address = system_program::ID)]
+    pub system_program: Program<'info, System>,
+    /// Conditional account for a withdrawal of SPL tokens, to read the token balance of the locker
+    pub token_account_optional: Option<InterfaceAccount<'info, TokenAccount>>,
+}
+
+impl<'info> RequestWithdrawal<'_> {
+    pub fn process(&mut self, amount: u32, token_mint: Option<Pubkey>, destination: Pubkey) -> Result<()> {
+        let Self {authority, locker, token_account_optional, ..} = self;
+
+        locker.record_activity()?;
+
+        let time_now = Clock::get()?.unix_timestamp as u32;
+        let cooldown_secs = locker.withdrawal_cooldown.active_cooldown_secs(time_now);
+        require!(cooldown_secs > 0, LockerErrorCode::NoWithdrawalCooldown);
+
+        locker.check_destination(&destination)?;
+
+        // The withdrawable balance is checked again when the request is executed
+        let total_balance = asset_balance(locker, &token_mint, token_account_optional.as_deref())?;
+        let withdrawable = accounting::withdrawable_balance(total_balance, &locker.locks, &token_mint);
+        let amount = if amount == WITHDRAW_MAX { withdrawable } else { amount as u64 };
+        require!((amount > 0), LockerErrorCode::PayoutAmountNotPositive);
+        require!((amount <= withdrawable), LockerErrorCode::PayoutAmountExceedsAvailableBalance);
+
+        let request = WithdrawalRequest {
+            amount,
+            token_mint,
+            destination,
+            executable_ts: time_now.saturating_add(cooldown_secs),
+        };
+        locker.withdrawal_request = Some(request);
+
+        // Make space for the request, the authority pays the extra rent
+        grow_locker(locker, authority, 0, 0)?;
+
+        emit!(WithdrawalRequested {
+            locker: locker.key(),
+            authority: authority.key(),
+            request,
+        });
+
+        Ok(())
+    }
+}
@@ -0,0 +1,50 @@
+use crate::*;
+
+
+// Revoke a locked inactivity lock as the authority, which releases its funds back to the authority
+// The heir only inherits when the authority stops checking in, so while it is active the authority can change its mind
+// Signing checks in as well, which restarts the period of the other inactivity locks
+#[derive(Accounts)]
+#[instruction(locker_name: String)]
+pub struct RevokeInactivityLock<'info> {
+    pub authority: Signer<'info>,
+    #[account(mut, seeds = [b"locker".as_ref(), authority.key().as_ref(), &locker_name.as_ref()],
+        // Ensure that the signer is the authority/owner of the locker
+        constraint = locker.authority == *authority.key,
+        bump)]
+    pub locker: Account<'info, Locker>,
+}
+
+impl<'info> RevokeInactivityLock<'_> {
+    pub fn process(&mut self, lock_id: u8) -> Result<()> {
+        let Self {authority, locker} = self;
+
+        locker.record_activity()?;
+
+        let lock_item = *locker.locks.iter().find(|lock_item| lock_item.id() == lock_id).ok_or(LockerErrorCode::NoLockAtIndex)?;
+        require!(matches!(lock_item, Lock::Inactivity { .. }), LockerErrorCode::NotAnInactivityLock);
+
+        // Once unlocked the funds belong to the heir
+        require!(lock_item.is_locked(), LockerErrorCode::LockAlreadyUnlocked);
+
+        // Joined locks hold the same funds for the heir under another condition, e.g. a time lock, which stays promised
+        let joined = lock_item.join().is_some() || locker.locks.iter().any(|other| other.join() == Some(lock_id));
+        require!(!joined, LockerErrorCode::InactivityLockJoined);
+
+        // Without the heir the unlocked funds are the authority's again
+        if let Some(Lock::Inactivity { locked, beneficiary, .. }) = locker.locks.iter_mut().find(|lock_item| lock_item.id() == lock_id) {
+            *locked = false;
+            *beneficiary = None;
+        }
+        locker.update_locked_balance();
+
+        emit!(InactivityLockRevoked {
+            locker: locker.key(),
+            authority: authority.key(),
+            lock_id,
+            heir: lock_item.beneficiary(),
+        });
+
+        Ok(())
+    }
+}
@@ -0,0 +1,30 @@
+use crate::*;
+
+
+// Configure the circuit breaker that halts the price unlocks of a feed when its price moves too much, signed by the admin
+#[derive(Accounts)]
+pub struct SetCircuitBreaker<'info> {
+    pub admin: Signer<'info>,
+    #[account(mut,
+        seeds = [b"config".as_ref()],
+        bump = config.bump,
+        has_one = admin @ LockerErrorCode::NotTheAdmin)]
+    pub config: Account<'info, ProgramConfig>,
+}
+
+impl<'info> SetCircuitBreaker<'_> {
+    pub fn process(&mut self, circuit_breaker: CircuitBreaker) -> Result<()> {
+        let Self {admin, config} = self;
+
+        circuit_breaker.validate()?;
+
+        config.circuit_breaker = circuit_breaker;
+
+        emit!(CircuitBreakerSet {
+            admin: admin.key(),
+            circuit_breaker,
+        });
+
+        Ok(())
+    }
+}
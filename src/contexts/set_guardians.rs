@@ -0,0 +1,56 @@
+use crate::*;
+
+
+// Set the M-of-N guardians of a locker that can approve an early release, or remove them with no guardians
+// Changing the guardians while funds are locked would let the authority approve its own early release,
+//  so the guardians can only be changed when the locker has no locked locks, and has no withdrawal cooldown
+#[derive(Accounts)]
+#[instruction(locker_name: String)]
+pub struct SetGuardians<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(mut, seeds = [b"locker".as_ref(), authority.key().as_ref(), &locker_name.as_ref()],
+        // Ensure that the signer is the authority/owner of the locker
+        constraint = locker.authority == *authority.key,
+        bump)]
+    pub locker: Account<'info, Locker>,
+    #[account(address = system_program::ID)]
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> SetGuardians<'_> {
+    pub fn process(&mut self, guardians: Vec<Pubkey>, threshold: u8, penalty_bps: u16, penalty_destination: Pubkey) -> Result<()> {
+        let Self {authority, locker, ..} = self;
+
+        locker.record_activity()?;
+
+        require!(locker.locks.iter().all(|lock_item| !lock_item.is_locked()), LockerErrorCode::GuardiansLocked);
+
+        // Guardians release funds to the penalty destination, and cancel withdrawal requests,
+        //  so with a cooldown they can only be changed once the cooldown is lifted
+        let time_now = Clock::get()?.unix_timestamp as u32;
+        require!(locker.withdrawal_cooldown.active_cooldown_secs(time_now) == 0, LockerErrorCode::WithdrawalCooldownActive);
+
+        let config = if guardians.is_empty() {
+            None
+        } else {
+            let config = GuardianConfig { guardians, threshold, penalty_bps, penalty_destination };
+            config.validate(authority.key)?;
+            // The penalties of early releases are paid out of the locker
+            locker.check_destination(&penalty_destination)?;
+            Some(config)
+        };
+        locker.guardians = config.clone();
+
+        // Make space for the guardians, the authority pays the extra rent
+        grow_locker(locker, authority, 0, 0)?;
+
+        emit!(GuardiansSet {
+            locker: locker.key(),
+            authority: authority.key(),
+            guardians: config,
+        });
+
+        Ok(())
+    }
+}
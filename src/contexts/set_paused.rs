@@ -0,0 +1,29 @@
+use crate::*;
+
+
+// Pause or resume the program in an emergency, signed by the admin
+// While paused, new deposits, new locks and price unlocks fail, withdrawals of unlocked funds are always allowed
+#[derive(Accounts)]
+pub struct SetPaused<'info> {
+    pub admin: Signer<'info>,
+    #[account(mut,
+        seeds = [b"config".as_ref()],
+        bump = config.bump,
+        has_one = admin @ LockerErrorCode::NotTheAdmin)]
+    pub config: Account<'info, ProgramConfig>,
+}
+
+impl<'info> SetPaused<'_> {
+    pub fn process(&mut self, paused: bool) -> Result<()> {
+        let Self {admin, config} = self;
+
+        config.paused = paused;
+
+        emit!(PauseSet {
+            admin: admin.key(),
+            paused,
+        });
+
+        Ok(())
+    }
+}
@@ -0,0 +1,47 @@
+use crate::*;
+
+
+// Propose the destinations the funds of the locker can be withdrawn to, no destinations removes the restriction
+// The proposal replaces the destinations after a delay of 48 hours, and replaces an earlier proposal that is still pending
+#[derive(Accounts)]
+#[instruction(locker_name: String)]
+pub struct SetWithdrawalAllowlist<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(mut, seeds = [b"locker".as_ref(), authority.key().as_ref(), &locker_name.as_ref()],
+        // Ensure that the signer is the authority/owner of the locker
+        constraint = locker.authority == *authority.key,
+        bump)]
+    pub locker: Account<'info, Locker>,
+    #[account(address = system_program::ID)]
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> SetWithdrawalAllowlist<'_> {
+    pub fn process(&mut self, destinations: Vec<Pubkey>) -> Result<()> {
+        let Self {authority, locker, ..} = self;
+
+        locker.record_activity()?;
+
+        WithdrawalAllowlist::validate_destinations(&destinations)?;
+
+        let time_now = Clock::get()?.unix_timestamp as u32;
+        let active_ts = time_now.saturating_add(WithdrawalAllowlist::CHANGE_DELAY_SECS);
+
+        // A pending change that already took effect is kept, the new proposal replaces it later
+        locker.withdrawal_allowlist.apply_due_change(time_now);
+        locker.withdrawal_allowlist.pending = Some(PendingAllowlist { destinations: destinations.clone(), active_ts });
+
+        // Make space for the proposed destinations, the authority pays the extra rent
+        grow_locker(locker, authority, 0, 0)?;
+
+        emit!(WithdrawalAllowlistProposed {
+            locker: locker.key(),
+            authority: authority.key(),
+            destinations,
+            active_ts,
+        });
+
+        Ok(())
+    }
+}
@@ -0,0 +1,43 @@
+use crate::*;
+
+
+// Set the cooldown between requesting and executing a withdrawal, zero to withdraw directly again
+// A longer cooldown takes effect right away, a shorter one only after the cooldown in effect
+#[derive(Accounts)]
+#[instruction(locker_name: String)]
+pub struct SetWithdrawalCooldown<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(mut, seeds = [b"locker".as_ref(), authority.key().as_ref(), &locker_name.as_ref()],
+        // Ensure that the signer is the authority/owner of the locker
+        constraint = locker.authority == *authority.key,
+        bump)]
+    pub locker: Account<'info, Locker>,
+    #[account(address = system_program::ID)]
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> SetWithdrawalCooldown<'_> {
+    pub fn process(&mut self, cooldown_secs: u32) -> Result<()> {
+        let Self {authority, locker, ..} = self;
+
+        locker.record_activity()?;
+
+        require!(cooldown_secs <= WithdrawalCooldown::MAX_COOLDOWN_SECS, LockerErrorCode::InvalidWithdrawalCooldown);
+
+        let time_now = Clock::get()?.unix_timestamp as u32;
+        let active_ts = locker.withdrawal_cooldown.set(cooldown_secs, time_now);
+
+        // Make space for a pending cooldown, the authority pays the extra rent
+        grow_locker(locker, authority, 0, 0)?;
+
+        emit!(WithdrawalCooldownSet {
+            locker: locker.key(),
+            authority: authority.key(),
+            cooldown_secs,
+            active_ts,
+        });
+
+        Ok(())
+    }
+}
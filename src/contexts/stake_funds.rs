@@ -39,6 +39,8 @@ pub struct CreateAndDelegateStake<'info> {
 
 impl<'info> CreateAndDelegateStake<'info> {
     pub fn process(&mut self, locker_name: String, lamports: u64) -> Result<()> {
+        self.locker.record_activity()?;
+
         // Check if the stake account is rent-exempt
         let rent = &self.rent;
         if !rent.is_exempt(self.stake_account.lamports(), self.stake_account.data_len()) {
@@ -83,6 +85,14 @@ impl<'info> CreateAndDelegateStake<'info> {
             ],
         )?;
 
+        emit!(Staked {
+            locker: self.locker.key(),
+            authority: self.authority.key(),
+            stake_account: self.stake_account.key(),
+            vote_account: self.vote_account.key(),
+            lamports,
+        });
+
         Ok(())
     }
 }
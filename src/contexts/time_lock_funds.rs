@@ -1,5 +1,7 @@
 use crate::*;
 
+use anchor_spl::token_interface::TokenAccount;
+
 // Add a price lock to the locker
 // This will ensure the user can not access the funds before the given date (timestamp)
 #[derive(Accounts)]
@@ -14,32 +16,20 @@ pub struct TimeLockFunds<'info> {
     pub locker: Account<'info, Locker>,
     #[account(address = system_program::ID)]
     pub system_program: Program<'info, System>,
+    /// CHECK: program config, the instruction fails while the program is paused
+    #[account(seeds = [b"config".as_ref()], bump)]
+    pub config: UncheckedAccount<'info>,
+    /// Conditional account for a lock on SPL tokens (including wrapped SOL), to read the token balance of the locker
+    pub token_account_optional: Option<InterfaceAccount<'info, TokenAccount>>,
 }
 
 impl<'info> TimeLockFunds<'_> {
     pub fn process(&mut self, strike_time: u32, amount: u32, token_mint: Option<Pubkey>, join: Option<u8>) -> Result<()> {
-        let Self {locker,..} = self;
-
-        // Check if the payout amount is more than 0, otherwise the lock is not locking any funds
-        require!((amount > 0), LockerErrorCode::PayoutAmountNotPositive);
-
-
-        // Get total balance of the locker
-        let mut available_balance = locker.get_lamports();
+        let Self {authority, locker, config, token_account_optional,..} = self;
 
-        // Iterator through locks to subtract the locked amounts from the available balance
-        // When user has 0 locks yet, the available balance will equal the total balance
-        // With every lock, funds are locked and not available for a new lock
-        for lock_item in &locker.locks {  
-            // Do checks and retrieve amount of funds locked
-            let lock_item_balance = get_time_locked_balance(lock_item, &token_mint);
-            // Subtract the funds locked from the available balance
-            available_balance -= lock_item_balance;
-        }
-
-        // Check if the amount the user wants to lock is equal or lower than the available balance
-        require!(((amount as u64) <= available_balance), LockerErrorCode::PayoutAmountExceedsAvailableBalance);
+        ProgramConfig::check_not_paused(config)?;
 
+        locker.record_activity()?;
 
         // The lock id is equivalent to the position in the vector 
         let lock_id: u8 = locker.locks.len() as u8;
@@ -52,44 +42,30 @@ impl<'info> TimeLockFunds<'_> {
             token_mint: token_mint,
             locked: true,
             join: join,
+            // Not attached to a lot, the lock locks any funds of the asset
+            lot: None,
+            // Released to the authority, a beneficiary can only be named on a lock of a lot
+            beneficiary: None,
+            // A soft commitment can only be made on a lock of a lot
+            early_exit: None,
         };
 
+        // Make space for the new lock, the authority pays the extra rent
+        grow_locker(locker, authority, 0, 1)?;
+
+        // Check if the amount is positive and doesn't exceed the balance of the asset that is not locked yet
+        let total_balance = asset_balance(locker, &token_mint, token_account_optional.as_deref())?;
+        accounting::check_new_lock(total_balance, &locker.locks, &new_time_lock)?;
+
         // Add price lock to locker vector
         locker.locks.push(new_time_lock);
         
 
-        // Update the locked and unlocked balance
-        // locker.unlocked_balance -= payout_amount;
-        locker.locked_balance += amount;
+        // Update the locked balance
+        locker.update_locked_balance();
 
+        emit!(LockCreated::new(locker.key(), authority.key(), &new_time_lock));
 
         Ok(())
     }
 }
-
-
-// Perform checks and get the locked balance from a lock
-fn get_time_locked_balance<'info>(lock_item: &Lock, token_mint_user: &Option<Pubkey>) -> u64 {
-
-    // Check if lock is a price lock, and if so access the values 
-    if let Lock::TimeLock { id, strike_time, amount, token_mint, locked, join } = lock_item {
-        
-        // Check if the lock is locking up tokens that we are looking for
-        if &token_mint_user.unwrap() == &token_mint.unwrap() &&
-            // Check if the lock is locked
-            *locked == true
-            {
-                // Lock is locked, retrieve the locked balance
-                let locked_balance = *amount as u64;
-                return locked_balance
-
-        } else {
-            // the price lock is not for the token mint we are looking for - or lock is unlocked
-            return 0;
-        }
-    } else {
-        // Lock is not a Price lock
-        // Adding >2 price locks on the same balance is not allowed, but combining a price lock and a time lock is allowed
-        return 0;
-    }
-}
\ No newline at end of file
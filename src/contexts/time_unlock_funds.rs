@@ -17,70 +17,87 @@ pub struct TimeUnlockFunds<'info> {
 }
 
 impl<'info> TimeUnlockFunds<'_> {
-    pub fn process(&mut self, lock_index: u8) -> Result<()> {
+    pub fn process(&mut self, lock_index: u8) -> Result<UnlockResult> {
         let Self {ref mut locker,..} = self;
 
+        let locker_key = locker.key();
+        let authority = locker.authority;
+
+        let clock: Clock = Clock::get()?;
+        let time_now = clock.unix_timestamp as u32;
+
+        // The authority signs, so it is active and its inactivity locks don't unlock
+        locker.record_activity()?;
+        let last_activity_ts = locker.last_activity_ts;
+
+        let mut result = UnlockResult::default();
+
         // Users can choose whether they want to unlock all unlockable funds,or just a specific one
         // Lock_index is the index used to retrieve the lock object in the locks vector
         match lock_index {
             // index 255 is code for: unlock all unlockable-locks
+            // Locks that can't be unlocked (yet) are skipped and reported, instead of failing the transaction
             255 => {
 
                 // Loops through all available locks
                 for lock_item in &mut locker.locks {
 
-                    let clock: Clock = Clock::get().unwrap();
-                    let mut time_now = clock.unix_timestamp as u32;
+                    // Already unlocked locks are not eligible and not reported
+                    if !lock_item.is_locked() {
+                        continue;
+                    }
 
-                    // Check if price lock can be openend (asset price exceeds strike price)
+                    // Check if time lock can be openend (current time exceeds strike time)
                     // Unlock locker if true
-                    process_time_lock(lock_item, time_now).unwrap();
+                    match process_time_lock(lock_item, time_now, last_activity_ts) {
+                        Ok(()) => {
+                            emit!(LockUnlocked::new(locker_key, authority, lock_item, None, time_now));
+                            result.unlocked.push(lock_item.id());
+                        },
+                        Err(reason) => result.skipped.push(SkippedLock { lock_id: lock_item.id(), reason }),
+                    }
                 }
             },
 
             // any other index than 255 leads to trying to retrieve the lock and unlock the funds
             index => {
 
-                let clock: Clock = Clock::get().unwrap();
-                let mut time_now = clock.unix_timestamp as u32;
-
-                // Retrieves price lock from locks vector by the index
+                // Retrieves time lock from locks vector by the index
                 let lock_item = locker.locks
                     .get_mut(index as usize)
-                    .ok_or(LockerErrorCode::NoLockAtIndex)
-                    .unwrap();
+                    .ok_or(LockerErrorCode::NoLockAtIndex)?;
+
+                let was_locked = lock_item.is_locked();
 
-                // Check if price lock can be openend (asset price exceeds strike price)
+                // Check if time lock can be openend (current time exceeds strike time)
                 // Unlock locker if true
-                process_time_lock(lock_item, time_now).unwrap();
+                process_time_lock(lock_item, time_now, last_activity_ts)?;
+
+                if was_locked {
+                    emit!(LockUnlocked::new(locker_key, authority, lock_item, None, time_now));
+                    result.unlocked.push(lock_item.id());
+                }
 
             }
         }
 
-        Ok(())
+        locker.update_locked_balance();
+
+        Ok(result)
 
     }
 }            
 
 
 // Open up locks of which the current price is larger than the strike_price stated in the lock (as earlier defined by the user)
-fn process_time_lock<'info>(lock_item: &mut Lock, time_now: u32) -> Result<()> {
-
-    // Check if lock is a price lock, and if so access the values 
-    if let Lock::TimeLock { id, strike_time, amount, token_mint, locked, join } = lock_item {
-        // Check if the current time exceeds the strike_time defined in the locker
-        if time_now >= *strike_time {
-
-            // Unlock the lock
-            *locked = false;
+pub(crate) fn process_time_lock(lock_item: &mut Lock, time_now: u32, last_activity_ts: u32) -> std::result::Result<(), LockerErrorCode> {
 
-            Ok(())
-            
-        } else {
-            Err(LockerErrorCode::TimeLowerThanStrikeTime.into())
-        }
-    } else {
-        Err(LockerErrorCode::NotATimeLock.into())
+    // Check if lock is a time lock, only time locks and inactivity locks are unlocked by time
+    if !matches!(lock_item, Lock::TimeLock { .. } | Lock::Inactivity { .. }) {
+        return Err(LockerErrorCode::NotATimeLock);
     }
-}
 
+    // Unlock the lock when the current time exceeds the strike_time defined in the locker,
+    //  or the authority was inactive for the period of an inactivity lock
+    accounting::unlock(lock_item, time_now, None, last_activity_ts)
+}
@@ -0,0 +1,31 @@
+use crate::*;
+
+
+// Change the fees of the program config, or hand the config over to a new admin
+#[derive(Accounts)]
+pub struct UpdateConfig<'info> {
+    pub admin: Signer<'info>,
+    #[account(mut,
+        seeds = [b"config".as_ref()],
+        bump = config.bump,
+        has_one = admin @ LockerErrorCode::NotTheAdmin)]
+    pub config: Account<'info, ProgramConfig>,
+}
+
+impl<'info> UpdateConfig<'_> {
+    pub fn process(&mut self, new_admin: Pubkey, fees: Fees) -> Result<()> {
+        let Self {config, ..} = self;
+
+        fees.validate()?;
+
+        config.admin = new_admin;
+        config.fees = fees;
+
+        emit!(ConfigUpdated {
+            admin: config.admin,
+            fees,
+        });
+
+        Ok(())
+    }
+}
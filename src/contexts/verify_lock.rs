@@ -0,0 +1,64 @@
+use crate::*;
+
+use anchor_spl::token_interface::TokenAccount;
+
+
+// Verify that a wallet has at least an amount of an asset locked until at least a given time
+// Designed for other programs (e.g. DAO voting or allowlists) to use a locker as proof-of-commitment via CPI
+//
+// Only time locks without an early exit count, as a price lock (or a lock joined with a price lock) can unlock at any moment,
+//  and the authority can exit a time lock with an early exit at any moment
+// Time locks with a beneficiary don't count either, their funds go to the beneficiary and not back to the authority
+// Nothing counts in a locker with guardians, as the guardians can release any lock early
+// The locked amount is capped by the balance actually held by the locker, for SPL tokens the token account must be passed
+#[derive(Accounts)]
+pub struct VerifyLock<'info> {
+    pub locker: Account<'info, Locker>,
+    #[account(constraint = token_account_optional.owner == locker.key() @ LockerErrorCode::InvalidTokenAccount)]
+    pub token_account_optional: Option<InterfaceAccount<'info, TokenAccount>>,
+}
+
+// Result of a lock verification, returned to the caller via return data
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct LockVerification {
+    pub locker: Pubkey,
+    pub owner: Pubkey,
+    // None for SOL
+    pub token_mint: Option<Pubkey>,
+    // Amount locked by time locks that can not unlock before the requested time
+    pub locked_amount: u64,
+    // Whether the owner is the authority of the locker, and the locked amount is at least the requested amount
+    pub satisfied: bool,
+}
+
+impl VerifyLock<'_> {
+    pub fn process(&mut self, owner: Pubkey, token_mint: Option<Pubkey>, min_amount: u64, min_unlock_ts: u32) -> Result<LockVerification> {
+        let Self {locker, token_account_optional} = self;
+
+        // Sum the time locks of the asset that are locked until at least the requested time
+        // Joined locks can be unlocked by the other lock, so they don't prove a commitment
+        // The guardians can release every lock through guardian_release, so none of them proves a commitment
+        let locked_amount = if locker.guardians.is_some() { 0 } else { accounting::committed_balance(&locker.locks, &token_mint, min_unlock_ts) };
+
+        // Locks can't lock more than the locker actually holds of the asset
+        let balance = match token_mint {
+            None => {
+                let rent_exempt_minimum = Rent::get()?.minimum_balance(locker.to_account_info().data_len());
+                locker.get_lamports().saturating_sub(rent_exempt_minimum)
+            },
+            Some(token_mint) => match token_account_optional {
+                Some(token_account) if token_account.mint == token_mint => token_account.amount,
+                _ => 0,
+            },
+        };
+        let locked_amount = locked_amount.min(balance);
+
+        Ok(LockVerification {
+            locker: locker.key(),
+            owner,
+            token_mint,
+            locked_amount,
+            satisfied: locker.authority == owner && locked_amount >= min_amount,
+        })
+    }
+}
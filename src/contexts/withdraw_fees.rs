@@ -0,0 +1,76 @@
+use crate::*;
+
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+
+// Withdraw collected fees of SOL or an SPL token from the treasury to the admin
+#[derive(Accounts)]
+pub struct WithdrawFees<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    #[account(
+        seeds = [b"config".as_ref()],
+        bump = config.bump,
+        has_one = admin @ LockerErrorCode::NotTheAdmin)]
+    pub config: Account<'info, ProgramConfig>,
+    #[account(mut, seeds = [b"treasury".as_ref()], bump = treasury.bump)]
+    pub treasury: Account<'info, Treasury>,
+    #[account(address = system_program::ID)]
+    pub system_program: Program<'info, System>,
+    /// Conditional accounts to withdraw SPL tokens, of the SPL Token or the Token-2022 program
+    #[account()]
+    pub token_mint_account_optional: Option<InterfaceAccount<'info, Mint>>,
+    #[account(mut)]
+    pub treasury_token_account_optional: Option<InterfaceAccount<'info, TokenAccount>>,
+    /// CHECK: associated token account of the admin, created when it doesn't exist and validated by the associated token program
+    #[account(mut)]
+    pub admin_token_account_optional: Option<UncheckedAccount<'info>>,
+    pub token_program_optional: Option<Interface<'info, TokenInterface>>,
+    pub associated_token_program_optional: Option<Program<'info, AssociatedToken>>,
+}
+
+impl<'info> WithdrawFees<'_> {
+    pub fn process(&mut self, amount: u64) -> Result<()> {
+        let Self {admin, treasury, system_program, token_mint_account_optional, treasury_token_account_optional, admin_token_account_optional, token_program_optional, associated_token_program_optional, ..} = self;
+
+        require!(amount > 0, LockerErrorCode::PayoutAmountNotPositive);
+
+        // The mint and the token account of the treasury are passed together for tokens, and both left out for SOL,
+        //  so the balance is checked on the asset that is paid out
+        let token_mint = token_mint_account_optional.as_ref().map(|token_mint_account| token_mint_account.key());
+        match (token_mint, treasury_token_account_optional.as_ref()) {
+            (Some(token_mint), Some(treasury_token_account)) => {
+                require_keys_eq!(treasury_token_account.owner, treasury.key(), LockerErrorCode::InvalidTokenAccount);
+                require_keys_eq!(treasury_token_account.mint, token_mint, LockerErrorCode::InvalidTokenAccount);
+                require!(amount <= treasury_token_account.amount, LockerErrorCode::PayoutAmountExceedsAvailableBalance);
+            },
+            (None, None) => {
+                // The rent-exempt minimum keeps the treasury alive
+                let rent_exempt_minimum = Rent::get()?.minimum_balance(treasury.to_account_info().data_len());
+                let collected = treasury.get_lamports().saturating_sub(rent_exempt_minimum);
+                require!(amount <= collected, LockerErrorCode::PayoutAmountExceedsAvailableBalance);
+            },
+            _ => return err!(LockerErrorCode::MissingTokenAccounts),
+        }
+
+        // The treasury PDA owns its token accounts, so the program signs with the seeds of the treasury
+        let signer_seeds: &[&[&[u8]]] = &[&[b"treasury".as_ref(), &[treasury.bump]]];
+        let token_accounts = LockerTokenAccounts {
+            token_mint_account: token_mint_account_optional.as_ref(),
+            token_account: treasury_token_account_optional.as_ref().map(|treasury_token_account| treasury_token_account.as_ref()),
+            token_program: token_program_optional.as_ref(),
+            associated_token_program: associated_token_program_optional.as_ref(),
+        };
+        let payee = Payee { wallet: admin.as_ref(), token_account: admin_token_account_optional.as_deref() };
+        pay_from_pda(&treasury.to_account_info(), signer_seeds, admin, system_program, token_accounts, payee, amount)?;
+
+        emit!(FeesWithdrawn {
+            admin: admin.key(),
+            token_mint,
+            amount,
+        });
+
+        Ok(())
+    }
+}
@@ -0,0 +1,109 @@
+use crate::*;
+
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_2022::spl_token_2022::extension::transfer_fee::TransferFeeAmount;
+use anchor_spl::token_2022::spl_token_2022::extension::{BaseStateWithExtensions, StateWithExtensions};
+use anchor_spl::token_2022::spl_token_2022::state::Account as TokenAccountState;
+use anchor_spl::token_interface::{self, CloseAccount, Mint, TokenAccount, TokenInterface};
+
+
+// Withdraw an NFT (or an amount of a semi-fungible token) that is not locked from the locker
+// The token account of the locker is closed once it is empty, so the rent of every NFT returns to the authority
+#[derive(Accounts)]
+#[instruction(locker_name: String)]
+pub struct WithdrawNft<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(mut,
+        seeds = [b"locker".as_ref(), authority.key().as_ref(), &locker_name.as_ref()],
+        constraint = locker.authority == *authority.key,
+        bump)]
+    pub locker: Account<'info, Locker>,
+    #[account(address = system_program::ID)]
+    pub system_program: Program<'info, System>,
+    pub nft_mint: InterfaceAccount<'info, Mint>,
+    #[account(mut)]
+    pub token_account: InterfaceAccount<'info, TokenAccount>,
+    /// CHECK: associated token account of the authority for the NFT, created when it doesn't exist yet and validated by the associated token program
+    #[account(mut)]
+    pub authority_token_account: UncheckedAccount<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+impl<'info> WithdrawNft<'_> {
+    pub fn process(&mut self, locker_name: &str, amount: u32) -> Result<()> {
+        let Self {authority, locker, system_program, nft_mint, token_account, authority_token_account, token_program, associated_token_program} = self;
+
+        locker.record_activity()?;
+
+        // NFTs are always withdrawn to the authority
+        locker.check_destination(authority.key)?;
+
+        // With a cooldown, NFTs are withdrawn through request_withdrawal and execute_withdrawal like other tokens
+        let time_now = Clock::get()?.unix_timestamp as u32;
+        require!(locker.withdrawal_cooldown.active_cooldown_secs(time_now) == 0, LockerErrorCode::WithdrawalRequestRequired);
+
+        require!((amount > 0), LockerErrorCode::PayoutAmountNotPositive);
+
+        // Only the balance of the mint that is not locked can be withdrawn
+        let token_mint = Some(nft_mint.key());
+        let total_balance = asset_balance(locker, &token_mint, Some(token_account))?;
+        let withdrawable = accounting::withdrawable_balance(total_balance, &locker.locks, &token_mint);
+
+        let transfer_amount = if amount == WITHDRAW_MAX { withdrawable } else { amount as u64 };
+        require!((transfer_amount > 0), LockerErrorCode::PayoutAmountNotPositive);
+        require!((transfer_amount <= withdrawable), LockerErrorCode::PayoutAmountExceedsAvailableBalance);
+
+        // The authority may not hold a token account for the NFT (anymore), it is created by the payout
+        let token_accounts = LockerTokenAccounts {
+            token_mint_account: Some(nft_mint),
+            token_account: Some(token_account.as_ref()),
+            token_program: Some(token_program),
+            associated_token_program: Some(associated_token_program),
+        };
+        let payee = Payee { wallet: authority.as_ref(), token_account: Some(authority_token_account.as_ref()) };
+        pay_from_locker(locker, locker_name, authority, system_program, token_accounts, payee, transfer_amount)?;
+
+        // Close the empty token account of the locker, the rent goes to the authority
+        // A token account with withheld transfer fees can't be closed, it stays open
+        token_account.reload()?;
+        if token_account.amount == 0 && !has_withheld_fees(&token_account.to_account_info())? {
+            let authority_key = authority.key();
+            let signer_seeds: &[&[&[u8]]] = &[&[b"locker".as_ref(), authority_key.as_ref(), locker_name.as_ref(), &[locker.bump]]];
+            let cpi_accounts_close = CloseAccount {
+                account: token_account.to_account_info(),
+                destination: authority.to_account_info(),
+                authority: locker.to_account_info(),
+            };
+            token_interface::close_account(CpiContext::new_with_signer(token_program.to_account_info(), cpi_accounts_close, signer_seeds))?;
+        }
+
+        // The withdrawal consumes the oldest deposits of the mint first
+        locker.consume_lots(&token_mint, transfer_amount);
+        locker.update_locked_balance();
+
+        emit!(Withdrawn {
+            locker: locker.key(),
+            authority: authority.key(),
+            amount: transfer_amount,
+            token_mint,
+            destination: authority.key(),
+        });
+
+        Ok(())
+    }
+}
+
+
+// Whether transfer fees are withheld in a Token-2022 token account, which prevents closing it
+fn has_withheld_fees(token_account_info: &AccountInfo) -> Result<bool> {
+    if *token_account_info.owner != token_interface::spl_token_2022::ID {
+        return Ok(false);
+    }
+
+    let token_account_data = token_account_info.try_borrow_data()?;
+    let token_account = StateWithExtensions::<TokenAccountState>::unpack(&token_account_data)?;
+    let withheld_amount = token_account.get_extension::<TransferFeeAmount>().map_or(0, |transfer_fee_amount| u64::from(transfer_fee_amount.withheld_amount));
+    Ok(withheld_amount > 0)
+}
@@ -1,12 +1,14 @@
 use crate::*;
 
-use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
-use anchor_spl::associated_token;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_interface::{self, CloseAccount, Mint, TokenAccount, TokenInterface};
 
 
-// Withdraw funds from the locker, for now only SOL - later SPL tokens are added
+// Withdraw funds from the locker that are not locked, SOL or SPL tokens
+// The funds go to the destination, or the authority without a destination, which must be on the withdrawal allowlist
 #[derive(Accounts)]
-#[instruction(locker_name: String, amount: u64)]
+// amount is a u32 in the instruction data, matching the instruction arguments in lib.rs
+#[instruction(locker_name: String, amount: u32)]
 pub struct WithdrawUnlockedFunds<'info> {
     #[account(mut)]
     pub authority: Signer<'info>,
@@ -17,112 +19,100 @@ pub struct WithdrawUnlockedFunds<'info> {
     pub locker: Account<'info, Locker>,
     #[account(address = system_program::ID)]
     pub system_program: Program<'info, System>,
-    /// Conditional accounts for SPL token withdrawal
+    /// Conditional accounts for SPL token withdrawal, of the SPL Token or the Token-2022 program
     #[account()]
-    pub token_mint_account_optional: Option<Account<'info, Mint>>,
-    #[account(mut, constraint = token_account_optional.to_account_info().owner == &token::ID)]
-    pub token_account_optional: Option<Account<'info, TokenAccount>>,
-    #[account(address = token::ID)]
-    pub token_program_optional: Option<Program<'info, Token>>,
+    pub token_mint_account_optional: Option<InterfaceAccount<'info, Mint>>,
+    #[account(mut)]
+    pub token_account_optional: Option<InterfaceAccount<'info, TokenAccount>>,
+    /// CHECK: token account of the destination the withdrawal is transferred to, checked in process
+    /// When unwrapping SOL it is the associated token account of the authority, created when it doesn't exist
+    #[account(mut)]
+    pub authority_token_account_optional: Option<UncheckedAccount<'info>>,
+    pub token_program_optional: Option<Interface<'info, TokenInterface>>,
+    // Only needed to unwrap SOL
+    pub associated_token_program_optional: Option<Program<'info, AssociatedToken>>,
+    /// CHECK: wallet that receives the withdrawal instead of the authority, checked against the withdrawal allowlist
+    #[account(mut)]
+    pub destination_optional: Option<UncheckedAccount<'info>>,
 }
 
 impl<'info> WithdrawUnlockedFunds<'_> {
-    pub fn process(&mut self, locker_name: String, amount: u64) -> Result<()> {
-        let Self { authority, locker, system_program, token_account_optional, token_mint_account_optional, token_program_optional, .. } = self;
+    pub fn process(&mut self, locker_name: &str, amount: u32, unwrap_sol: bool) -> Result<()> {
+        let Self { authority, locker, system_program, token_account_optional, token_mint_account_optional, authority_token_account_optional, token_program_optional, associated_token_program_optional, destination_optional } = self;
 
-        // assert!(amount <= locker.unlocked_balance, "Insufficient unlocked balance");
-        assert!(authority.key() == locker.authority, "Signer not authorized");
+        locker.record_activity()?;
 
-        // Check if the payout amount is more than 0, otherwise the lock is not locking any funds
-        require!((amount > 0), LockerErrorCode::PayoutAmountNotPositive);
+        require_keys_eq!(authority.key(), locker.authority, LockerErrorCode::Unauthorized);
 
+        // With a cooldown, withdrawals go through request_withdrawal and execute_withdrawal
+        let time_now = Clock::get()?.unix_timestamp as u32;
+        require!(locker.withdrawal_cooldown.active_cooldown_secs(time_now) == 0, LockerErrorCode::WithdrawalRequestRequired);
 
-        /// TODO: Only implemented available balance for $SOL, not yet for SPL tokens
-         
-        
-        // Get total balance of the locker
-        let mut available_balance = locker.get_lamports();
-
-
-        // Initiate locked_balance which we will fill when we find locked locks
-        let mut locked_balance = 0;
-
-        // Joined locks is a HashSet to find locks that are join together (join = id of another lock)
-        let mut joined_locks = HashSet::new();
-
-
-        // Iterator through locks to subtract the locked amounts from the available balance
-        // When user has 0 locks yet, the available balance will equal the total balance
-        // With every lock, funds are locked and not available for a new lock which will be reflected in the locked_balance variable
-        for lock_item in &locker.locks { 
-
-            match lock_item {
-                // Access values of Lock items
-                Lock::PriceLock { id, amount, locked, join, .. } | Lock::TimeLock { id, amount, locked, join, .. } => {
-                    // Check if the lock is locked and has no dependency on other locks
-                    if *locked == true && join.is_none() {
-                        locked_balance += amount;
-                    // Lock is locked and has a join with another lock, e.g. 100 $SOL has two locks: time lock (01-01-2025) and a price lock ($1000)
-                    // We need to check if both locks are locked or if one is unlocked, in that case the funds are unlocked
-                    // A user in this example basically says: unlock after 01-01-2025 OR if the $SOL price hits $1000
-                    } else if *locked == true {
-                        // Check if the id is already in the joined_locks
-                        if !joined_locks.contains(id) {
-                            // Add the id to joined_locks
-                            joined_locks.insert(*id);
-                        } else {
-                            // The lock id is already in joined_locks
-                            // This means both the locks are locked and the locked amount should be added to the locked_balance
-                            locked_balance += amount;
-                        }
-                    }
-                }
-            } 
-        }
+        let destination = destination_optional.as_ref().map_or_else(|| authority.to_account_info(), |destination| destination.to_account_info());
+        locker.check_destination(destination.key)?;
 
-        // Cast from u32 to u64
-        let balance_locked = locked_balance as u64;
+        // Check if the payout amount is more than 0, otherwise there is nothing to withdraw
+        require!((amount > 0), LockerErrorCode::PayoutAmountNotPositive);
 
-        // Subtract the locked amounts from the available balance
-        let transfer_amount = available_balance - balance_locked;
+        // Get the total balance of the asset that is withdrawn
+        // if the token_mint is None we assume a SOL withdrawal, otherwise SPL token withdrawal
+        // The rent-exempt minimum stays in the locker, otherwise the account would be removed
+        let token_mint = token_mint_account_optional.as_ref().map(|token_mint_account| token_mint_account.key());
+        let total_balance = asset_balance(locker, &token_mint, token_account_optional.as_deref())?;
+
+        // Get the balance that is not locked for the asset that is withdrawn
+        let withdrawable = accounting::withdrawable_balance(total_balance, &locker.locks, &token_mint);
+
+        // WITHDRAW_MAX withdraws everything that is withdrawable, any other amount must fit in the withdrawable balance
+        let transfer_amount = if amount == WITHDRAW_MAX { withdrawable } else { amount as u64 };
+        require!((transfer_amount > 0), LockerErrorCode::PayoutAmountNotPositive);
+        require!((transfer_amount <= withdrawable), LockerErrorCode::PayoutAmountExceedsAvailableBalance);
+
+
+        // Only wrapped SOL can be unwrapped
+        require!(!unwrap_sol || token_mint.as_ref().is_some_and(is_native_mint), LockerErrorCode::NotNativeMint);
+
+        // The token account is closed to unwrap, which only its owner can sign
+        require!(!unwrap_sol || destination.key == authority.key, LockerErrorCode::UnwrapToOtherDestination);
+
+        // Wrapped SOL is unwrapped through the native mint token account of the authority, which may not exist yet
+        require!(!unwrap_sol || associated_token_program_optional.is_some(), LockerErrorCode::MissingTokenAccounts);
+
+        let token_accounts = LockerTokenAccounts {
+            token_mint_account: token_mint_account_optional.as_ref(),
+            token_account: token_account_optional.as_ref().map(|token_account| token_account.as_ref()),
+            token_program: token_program_optional.as_ref(),
+            associated_token_program: associated_token_program_optional.as_ref(),
+        };
+        let payee = Payee { wallet: &destination, token_account: authority_token_account_optional.as_deref() };
+        pay_from_locker(locker, locker_name, authority, system_program, token_accounts, payee, transfer_amount)?;
+
+        // Closing the native mint token account moves all its lamports to the authority as SOL,
+        //  including wrapped SOL the authority already held in it
+        if unwrap_sol {
+            let authority_token_account = authority_token_account_optional.as_ref().ok_or(LockerErrorCode::MissingTokenAccounts)?;
+            let token_program = token_program_optional.as_ref().ok_or(LockerErrorCode::MissingTokenAccounts)?;
+            let cpi_accounts_close = CloseAccount {
+                account: authority_token_account.to_account_info(),
+                destination: authority.to_account_info(),
+                authority: authority.to_account_info(),
+            };
+            token_interface::close_account(CpiContext::new(token_program.to_account_info(), cpi_accounts_close))?;
+        }
 
-        // Check if the amount the user wants withdraw is within the available balance
-        require!((transfer_amount <= available_balance), LockerErrorCode::PayoutAmountExceedsAvailableBalance);
+        // The withdrawal consumes the oldest deposits first
+        locker.consume_lots(&token_mint, transfer_amount);
 
+        locker.update_locked_balance();
 
-        // Check if the deposit is a $SOL or SPL token deposit
-        // if the token_mint is None we assume a SOL deposit, otherwise SPL token deposit
-        if let Some(_token_mint_account) = token_mint_account_optional {
-            
-            // Unwrap the optional accounts which must contain addresses needed for the associated token account
-            let token_account = token_account_optional.as_ref().unwrap();
-            let token_program = token_program_optional.as_ref().unwrap();
+        emit!(Withdrawn {
+            locker: locker.key(),
+            authority: authority.key(),
+            amount: transfer_amount,
+            token_mint,
+            destination: destination.key(),
+        });
 
-            // Transfer the withdrawal amount from the token account to the authority
-            let cpi_accounts = Transfer {
-                from: token_account.to_account_info(),
-                to: authority.to_account_info(),
-                authority: locker.to_account_info(),
-            };
-            let cpi_context = CpiContext::new(token_program.to_account_info(), cpi_accounts);
-            token::transfer(cpi_context, amount)?;
-        } else {
-            // Transfer SOL from the locker to the authority
-            invoke_signed(
-                &system_instruction::transfer(
-                    &locker.to_account_info().key,
-                    &authority.to_account_info().key,
-                    amount,
-                ),
-                &[
-                    locker.to_account_info().clone(),
-                    authority.to_account_info().clone(),
-                    system_program.to_account_info().clone(),
-                ],
-                &[&[b"locker".as_ref(), authority.key().as_ref(), &locker_name.as_ref(), &[locker.bump]]],
-            )?;
-        }
-        
         Ok(())
     }
-}  
+}
@@ -1,13 +1,13 @@
 use anchor_lang::prelude::*;
 use anchor_lang::solana_program::system_instruction;
 use anchor_lang::solana_program::system_program;
-use anchor_lang::solana_program::program::{invoke, invoke_signed};
+use anchor_lang::solana_program::program::invoke;
 
 
 use std::mem;
 use std::str::FromStr;
-use std::collections::HashSet;
 
+pub mod accounting;
 pub mod contexts;
 pub mod states;
 
@@ -17,6 +17,9 @@ pub use states::*;
 
 declare_id!("5GUctGGG8KFkoS5LbQSdfGuDGeFd3jkkcVZVfHzBB6hF");
 
+// Withdrawal amount that withdraws the whole withdrawable balance of an asset
+pub const WITHDRAW_MAX: u32 = u32::MAX;
+
 #[program]
 pub mod pricelocker {
 
@@ -25,7 +28,7 @@ pub mod pricelocker {
     /// Create new price feed account to track price of a currency
     pub fn create_pricefeed_account(ctx: Context<CreatePricefeedAccount>, pricefeed_alias: String, pricefeed_id_string: String) -> Result<()> {
         let alias = pricefeed_alias.clone();
-        let pricefeed_id = Pubkey::from_str(&pricefeed_id_string).unwrap();
+        let pricefeed_id = Pubkey::from_str(&pricefeed_id_string).map_err(|_| LockerErrorCode::InvalidPricefeedId)?;
         let bump = ctx.bumps.price_feed;
         ctx.accounts.process(alias, pricefeed_id, bump)
     }
@@ -36,19 +39,51 @@ pub mod pricelocker {
         ctx.accounts.process(bump)
     }
 
+    /// Migrate a locker created before lots, beneficiaries and guardians were added to the current layout, signed by its authority
+    /// The legacy price locks are bound to the passed price feed, which is required when the locker has price locks
+    pub fn migrate_locker(ctx: Context<MigrateLocker>, _locker_name: String) -> Result<()> {
+        ctx.accounts.process()
+    }
+
 
-    /// Deposit to price locker
-    pub fn deposit_funds(ctx: Context<DepositFunds>, _locker_name: String, amount: u32) -> Result<()> {
-        let transfer_amount: u64 = amount as u64;
-        ctx.accounts.process(transfer_amount)
+    /// Deposit to price locker, the deposit is recorded as a lot with the oracle price if a price feed is passed
+    /// With `wrap_sol` the SOL is wrapped into a native mint token account of the locker, and locked like any token
+    pub fn deposit_funds(ctx: Context<DepositFunds>, locker_name: String, amount: u32, wrap_sol: bool) -> Result<()> {
+        ctx.accounts.process(&locker_name, amount, wrap_sol)
     }
     
+    /// Deposit to price locker and lock exactly that deposit in the same instruction
+    /// Every condition becomes a lock on the deposit, joined to the first one, so the deposit unlocks when any condition is met
+    /// With a beneficiary, the deposit is claimed by the beneficiary once unlocked, see claim_beneficiary_funds
+    pub fn deposit_and_lock(ctx: Context<DepositAndLock>, locker_name: String, amount: u32, conditions: Vec<LockCondition>, beneficiary: Option<Pubkey>) -> Result<()> {
+        ctx.accounts.process(&locker_name, amount, conditions, beneficiary)
+    }
+
+
+    /// Deposit an NFT or an amount of a semi-fungible token (a mint with 0 decimals), locked on the conditions if any
+    /// The locks lock that specific mint, like locks on a lot
+    pub fn deposit_nft(ctx: Context<DepositNft>, _locker_name: String, amount: u32, conditions: Vec<LockCondition>, beneficiary: Option<Pubkey>) -> Result<()> {
+        ctx.accounts.process(amount, conditions, beneficiary)
+    }
+
+    /// Withdraw an NFT or an amount of a semi-fungible token that is not locked, closing the empty token account of the locker
+    pub fn withdraw_nft(ctx: Context<WithdrawNft>, locker_name: String, amount: u32) -> Result<()> {
+        ctx.accounts.process(&locker_name, amount)
+    }
+
+    /// Lock an amount of a lot on one or more conditions, joined like the conditions of deposit_and_lock
+    /// With a beneficiary, the amount is claimed by the beneficiary once unlocked
+    pub fn lock_lot(ctx: Context<LockLot>, _locker_name: String, lot_id: u16, amount: u32, conditions: Vec<LockCondition>, beneficiary: Option<Pubkey>) -> Result<()> {
+        ctx.accounts.process(lot_id, amount, conditions, beneficiary)
+    }
+
 
     pub fn stake_funds(ctx: Context<CreateAndDelegateStake>, locker_name: String, lamports: u64) -> Result<()> {
         ctx.accounts.process(locker_name, lamports)
     }
 
 
+    /// Lock funds until the price of the passed price feed reaches the strike price, a price of another feed never unlocks it
     pub fn price_lock_funds(ctx: Context<PriceLockFunds>, _locker_name: String, strike_price: u32, payout_amount: u32, token_mint: Option<Pubkey>, join: Option<u8>) -> Result<()> {
 
         ctx.accounts.process(strike_price, payout_amount, token_mint, join)
@@ -61,23 +96,163 @@ pub mod pricelocker {
 
 
 
-    // /// Withdraw from price locker
-    pub fn time_unlock_funds(ctx: Context<TimeUnlockFunds>, _locker_name: String, lock_index: u8) -> Result<()> {
+    /// Unlock the time lock at `lock_index`, or all eligible time locks with index 255
+    /// Returns the unlocked locks, and when unlocking all the skipped locks with the reason
+    pub fn time_unlock_funds(ctx: Context<TimeUnlockFunds>, _locker_name: String, lock_index: u8) -> Result<UnlockResult> {
         ctx.accounts.process(lock_index)
     }
 
-    pub fn price_unlock_funds(ctx: Context<PriceUnlockFunds>, _locker_name: String, lock_index: u8) -> Result<()> {
+    /// Unlock the price lock at `lock_index`, or all eligible price locks with index 255
+    /// Only the price locks created on the passed price feed can be unlocked, see price_lock_funds
+    /// Returns the unlocked locks, and when unlocking all the skipped locks with the reason
+    pub fn price_unlock_funds(ctx: Context<PriceUnlockFunds>, _locker_name: String, lock_index: u8) -> Result<UnlockResult> {
         ctx.accounts.process(lock_index)
     }
 
+    /// Unlock all eligible locks over many lockers, passed as remaining accounts
+    /// Returns a summary per locker of the unlocked and skipped locks
+    pub fn crank_unlocks<'info>(ctx: Context<'_, '_, 'info, 'info, CrankUnlocks<'info>>) -> Result<Vec<CrankUnlocksResult>> {
+        ctx.accounts.process(ctx.remaining_accounts, ctx.program_id)
+    }
+
+    /// Check in as the authority, which keeps the inactivity locks locked for another period
+    /// Every instruction signed by the authority checks in as well
+    pub fn heartbeat(ctx: Context<Heartbeat>, _locker_name: String) -> Result<()> {
+        ctx.accounts.process()
+    }
+
+    /// Revoke a locked inactivity lock, its funds are no longer inherited and can be withdrawn by the authority
+    /// An inactivity lock joined with other locks can't be revoked
+    pub fn revoke_inactivity_lock(ctx: Context<RevokeInactivityLock>, _locker_name: String, lock_id: u8) -> Result<()> {
+        ctx.accounts.process(lock_id)
+    }
+
+    /// Propose the wallets the funds of the locker can be withdrawn and paid out to, no wallets removes the restriction
+    /// The proposal takes effect after 48 hours, until then it can be cancelled with cancel_withdrawal_allowlist_change
+    /// Penalties are checked against the allowlist in effect when paid, beneficiaries keep the funds promised to them
+    pub fn set_withdrawal_allowlist(ctx: Context<SetWithdrawalAllowlist>, _locker_name: String, destinations: Vec<Pubkey>) -> Result<()> {
+        ctx.accounts.process(destinations)
+    }
+
+    /// Cancel the pending change of the withdrawal allowlist before it takes effect
+    pub fn cancel_withdrawal_allowlist_change(ctx: Context<CancelWithdrawalAllowlistChange>, _locker_name: String) -> Result<()> {
+        ctx.accounts.process()
+    }
+
+    /// Set the M-of-N guardians that can approve an early release and its penalty, no guardians removes them
+    /// Only allowed while the locker has no locked locks and no withdrawal cooldown
+    pub fn set_guardians(ctx: Context<SetGuardians>, _locker_name: String, guardians: Vec<Pubkey>, threshold: u8, penalty_bps: u16, penalty_destination: Pubkey) -> Result<()> {
+        ctx.accounts.process(guardians, threshold, penalty_bps, penalty_destination)
+    }
+
+    /// Release a lock before its condition is met, signed by the threshold of guardians passed as remaining accounts
+    /// The penalty of the guardian config is transferred from the released funds to the penalty destination
+    pub fn guardian_release<'info>(ctx: Context<'_, '_, 'info, 'info, GuardianRelease<'info>>, locker_name: String, lock_id: u8) -> Result<()> {
+        ctx.accounts.process(&locker_name, lock_id, ctx.remaining_accounts)
+    }
+
+    /// Exit a time lock with an early exit before its strike time, signed by the authority alone
+    /// The penalty decays linearly toward zero at the strike time and is transferred to the destination recorded in the lock
+    pub fn exit_lock_early(ctx: Context<ExitLockEarly>, locker_name: String, lock_id: u8) -> Result<()> {
+        ctx.accounts.process(&locker_name, lock_id)
+    }
+
+    /// Create the program config with the protocol fees and the treasury that collects them, the signer becomes the admin
+    /// Only the upgrade authority of the program can initialize it
+    pub fn init_config(ctx: Context<InitConfig>, fees: Fees) -> Result<()> {
+        let (bump, treasury_bump) = (ctx.bumps.config, ctx.bumps.treasury);
+        ctx.accounts.process(fees, bump, treasury_bump)
+    }
+
+    /// Change the protocol fees or the admin, signed by the admin
+    /// The staking reward fee is validated and recorded for when staking rewards can be withdrawn, no instruction charges it yet
+    pub fn update_config(ctx: Context<UpdateConfig>, admin: Pubkey, fees: Fees) -> Result<()> {
+        ctx.accounts.process(admin, fees)
+    }
+
+    /// Pause or resume new deposits, new locks and price unlocks in an emergency, signed by the admin
+    /// Withdrawals of unlocked funds, claims and early releases stay allowed while paused
+    pub fn set_paused(ctx: Context<SetPaused>, paused: bool) -> Result<()> {
+        ctx.accounts.process(paused)
+    }
+
+    /// Halt the price unlocks of a feed while its price moved more than the maximum within the window, signed by the admin
+    /// The first price of a window is compared with the EMA price of the oracle, a maximum move of zero disables the circuit breaker
+    pub fn set_circuit_breaker(ctx: Context<SetCircuitBreaker>, circuit_breaker: CircuitBreaker) -> Result<()> {
+        ctx.accounts.process(circuit_breaker)
+    }
+
+    /// Withdraw collected fees of SOL (no mint) or an SPL token from the treasury to the admin
+    /// For a token both the mint and the token account of the treasury are passed, for SOL neither
+    pub fn withdraw_fees(ctx: Context<WithdrawFees>, amount: u64) -> Result<()> {
+        ctx.accounts.process(amount)
+    }
+
+    /// Read-only view of the balances per asset and the state of every lock in a locker
+    /// The token accounts of the locker are passed as remaining accounts
+    pub fn get_locker_status<'info>(ctx: Context<'_, '_, 'info, 'info, GetLockerStatus<'info>>) -> Result<LockerStatus> {
+        ctx.accounts.process(ctx.remaining_accounts)
+    }
+
+    /// Read-only view of the NFTs held by a locker and their lock state
+    /// The token accounts of the locker are passed as remaining accounts, one per NFT
+    pub fn get_locker_nfts<'info>(ctx: Context<'_, '_, 'info, 'info, GetLockerNfts<'info>>) -> Result<Vec<NftStatus>> {
+        ctx.accounts.process(ctx.remaining_accounts)
+    }
+
+    /// Verify that `owner` has at least `min_amount` of `token_mint` (None for SOL) locked until at least `min_unlock_ts`
+    /// Returns the verification result, intended for other programs calling via CPI (`cpi` feature)
+    pub fn verify_lock(ctx: Context<VerifyLock>, owner: Pubkey, token_mint: Option<Pubkey>, min_amount: u64, min_unlock_ts: u32) -> Result<LockVerification> {
+        ctx.accounts.process(owner, token_mint, min_amount, min_unlock_ts)
+    }
+
+    /// Same check as `verify_lock`, but fails when the requirement is not met
+    /// Other programs can gate an instruction on a lock with a single CPI
+    pub fn require_lock(ctx: Context<VerifyLock>, owner: Pubkey, token_mint: Option<Pubkey>, min_amount: u64, min_unlock_ts: u32) -> Result<LockVerification> {
+        let verification = ctx.accounts.process(owner, token_mint, min_amount, min_unlock_ts)?;
+        require!(verification.satisfied, LockerErrorCode::LockRequirementNotMet);
+        Ok(verification)
+    }
+
     // // TODO: decide on using the term stake or delegate
     // pub fn unstake_funds(ctx: Context<UnlockFunds>, amount: u128) -> Result<()> {
     //     ctx.accounts.process(amount)
     // }
 
-    pub fn withdraw_unlocked_funds(ctx: Context<WithdrawUnlockedFunds>, locker_name: String, amount: u32) -> Result<()> {
-        let transfer_amount: u64 = amount as u64;
-        ctx.accounts.process(locker_name, transfer_amount)
+    /// Claim funds of an unlocked lock that names the signer as beneficiary, or everything that is claimable with amount WITHDRAW_MAX
+    /// The funds are transferred to the beneficiary, the authority of the locker doesn't sign
+    pub fn claim_beneficiary_funds(ctx: Context<ClaimBeneficiaryFunds>, locker_name: String, lock_id: u8, amount: u32) -> Result<()> {
+        ctx.accounts.process(&locker_name, lock_id, amount)
+    }
+
+    /// Withdraw funds that are not locked, or everything that is withdrawable with amount WITHDRAW_MAX
+    /// With `unwrap_sol` wrapped SOL is withdrawn as SOL, which closes the native mint token account of the authority
+    /// The funds go to the destination account if passed, otherwise to the authority, restricted by the withdrawal allowlist
+    pub fn withdraw_unlocked_funds(ctx: Context<WithdrawUnlockedFunds>, locker_name: String, amount: u32, unwrap_sol: bool) -> Result<()> {
+        ctx.accounts.process(&locker_name, amount, unwrap_sol)
+    }
+
+    /// Set the cooldown between requesting and executing a withdrawal, zero withdraws directly with withdraw_unlocked_funds
+    /// A shorter cooldown only takes effect after the cooldown in effect, so it can't be used to skip it
+    /// While a cooldown is in effect, no beneficiaries, early exit penalty destinations or guardians can be set
+    pub fn set_withdrawal_cooldown(ctx: Context<SetWithdrawalCooldown>, _locker_name: String, cooldown_secs: u32) -> Result<()> {
+        ctx.accounts.process(cooldown_secs)
+    }
+
+    /// Request a withdrawal from a locker with a withdrawal cooldown, or everything that is withdrawable with amount WITHDRAW_MAX
+    /// The request is stored in the locker until it is executed or cancelled
+    pub fn request_withdrawal(ctx: Context<RequestWithdrawal>, _locker_name: String, amount: u32, token_mint: Option<Pubkey>, destination: Pubkey) -> Result<()> {
+        ctx.accounts.process(amount, token_mint, destination)
+    }
+
+    /// Execute the withdrawal request once its cooldown passed, transferring the funds to its destination
+    pub fn execute_withdrawal(ctx: Context<ExecuteWithdrawal>, locker_name: String) -> Result<()> {
+        ctx.accounts.process(&locker_name)
+    }
+
+    /// Cancel the withdrawal request, signed by the authority or one of the guardians of the locker
+    pub fn cancel_withdrawal(ctx: Context<CancelWithdrawal>, _locker_name: String) -> Result<()> {
+        ctx.accounts.process()
     }
 
 
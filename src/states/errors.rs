@@ -2,6 +2,8 @@ use anchor_lang::prelude::*;
 
 
 #[error_code]
+// Serializable to report why a lock was not unlocked, see UnlockResult
+#[derive(AnchorSerialize, AnchorDeserialize, PartialEq, Eq)]
 pub enum LockerErrorCode {
     #[msg("Payout amount should be more than 0")]
     PayoutAmountNotPositive,
@@ -17,6 +19,108 @@ pub enum LockerErrorCode {
     NotATimeLock,
     #[msg("The payout amount exceeds the available balance")]
     PayoutAmountExceedsAvailableBalance,
+    #[msg("The remaining accounts do not match the expected layout")]
+    InvalidRemainingAccounts,
+    #[msg("No price feed was provided to evaluate the price lock")]
+    NoPriceFeedForLock,
+    #[msg("The token account is not owned by the locker")]
+    InvalidTokenAccount,
+    #[msg("The locker does not lock the required amount until the required time")]
+    LockRequirementNotMet,
+    #[msg("The joined lock does not exist, is unlocked, locks another asset or has another beneficiary")]
+    InvalidJoin,
+    #[msg("The price feed id is not a valid public key")]
+    InvalidPricefeedId,
+    #[msg("The signer is not the authority of the locker")]
+    Unauthorized,
+    #[msg("The token account and token program are required for SPL tokens")]
+    MissingTokenAccounts,
+    #[msg("At least one lock condition is required")]
+    NoLockConditions,
+    #[msg("The lot does not exist or holds another asset")]
+    InvalidLot,
+    #[msg("The price feed and the Pyth price account are required together")]
+    MissingPriceFeedAccounts,
+    #[msg("The token mint has an extension that can take or freeze the locked tokens")]
+    UnsupportedMintExtension,
+    #[msg("Only SOL can be wrapped or unwrapped, the token mint must be the native mint")]
+    NotNativeMint,
+    #[msg("The mint is not an NFT or semi-fungible token, it must have 0 decimals")]
+    NotAnNft,
+    #[msg("The lock has no beneficiary, its funds are released to the authority")]
+    NoBeneficiary,
+    #[msg("The signer is not the beneficiary of the lock")]
+    NotTheBeneficiary,
+    #[msg("The lock has not been unlocked yet")]
+    LockNotUnlocked,
+    #[msg("The authority was active within the inactivity period of the lock")]
+    AuthorityStillActive,
+    #[msg("An inactivity lock needs a beneficiary, the heir that claims the funds")]
+    InactivityLockWithoutHeir,
+    #[msg("The guardians must be distinct, at most 10, other than the authority, with a threshold between 1 and their number and a penalty of at most 10000 bps to another wallet than the authority")]
+    InvalidGuardianConfig,
+    #[msg("The guardians can't be changed while the locker has locked locks")]
+    GuardiansLocked,
+    #[msg("The locker has no guardians")]
+    NoGuardians,
+    #[msg("Not enough guardians signed to approve the early release")]
+    NotEnoughGuardianApprovals,
+    #[msg("The lock is already unlocked")]
+    LockAlreadyUnlocked,
+    #[msg("The penalty destination does not match the destination of the guardians or of the lock")]
+    InvalidPenaltyDestination,
+    #[msg("The lock has no early exit")]
+    NoEarlyExit,
+    #[msg("The penalty of an early exit can't exceed 10000 basis points or go to the authority, and a lock with a beneficiary can't be exited early")]
+    InvalidEarlyExit,
+    #[msg("A fee can't exceed 1000 basis points")]
+    InvalidFee,
+    #[msg("Only the admin of the program config can do this")]
+    NotTheAdmin,
+    #[msg("The program is paused by the admin, only withdrawals are allowed")]
+    ProgramPaused,
+    #[msg("The circuit breaker needs a window when it is enabled")]
+    InvalidCircuitBreaker,
+    #[msg("The price moved too much within the window of the circuit breaker, price unlocks are halted")]
+    CircuitBreakerTripped,
+    #[msg("The withdrawal allowlist must have distinct destinations, at most 10")]
+    InvalidWithdrawalAllowlist,
+    #[msg("The destination is not on the withdrawal allowlist of the locker")]
+    DestinationNotAllowed,
+    #[msg("The withdrawal allowlist has no pending change to cancel")]
+    NoPendingAllowlistChange,
+    #[msg("Wrapped SOL can only be unwrapped to the authority")]
+    UnwrapToOtherDestination,
+    #[msg("The withdrawal cooldown can't exceed 30 days")]
+    InvalidWithdrawalCooldown,
+    #[msg("The locker has a withdrawal cooldown, withdrawals must be requested first")]
+    WithdrawalRequestRequired,
+    #[msg("The locker has no withdrawal cooldown, withdraw directly instead")]
+    NoWithdrawalCooldown,
+    #[msg("The locker has no withdrawal request")]
+    NoWithdrawalRequest,
+    #[msg("The cooldown of the withdrawal request has not passed yet")]
+    WithdrawalCooldownNotPassed,
+    #[msg("The destination or the asset does not match the withdrawal request")]
+    NotTheRequestedWithdrawal,
+    #[msg("Only the authority or a guardian of the locker can cancel the withdrawal request")]
+    NotTheAuthorityOrAGuardian,
+    #[msg("The lock is not an inactivity lock")]
+    NotAnInactivityLock,
+    #[msg("An inactivity lock joined with other locks can't be revoked, the heir keeps the funds under the other conditions")]
+    InactivityLockJoined,
+    #[msg("The penalty of a guardian release of a beneficiary lock can only go to the beneficiary")]
+    PenaltyNotToTheBeneficiary,
+    #[msg("Beneficiaries, early exit penalty destinations and guardians can't be set while a withdrawal cooldown is in effect")]
+    WithdrawalCooldownActive,
+    #[msg("Only the upgrade authority of the program can initialize the config")]
+    NotTheUpgradeAuthority,
+    #[msg("The price is not read from the price feed the price lock was created on")]
+    WrongPriceFeed,
+    #[msg("The locker holds the maximum number of locks")]
+    TooManyLocks,
+    #[msg("The locker is not in the legacy layout, only legacy lockers can be migrated")]
+    NotALegacyLocker,
 }
 
 
@@ -0,0 +1,263 @@
+use anchor_lang::prelude::*;
+
+use crate::{CircuitBreaker, EarlyExit, Fees, GuardianConfig, Lock, WithdrawalRequest};
+
+
+// Events emitted on every state change of a locker, so indexers don't have to diff account data
+
+#[event]
+pub struct PriceFeedRegistered {
+    pub price_feed: Pubkey,
+    pub authority: Pubkey,
+    pub pricefeed_alias: String,
+    pub pricefeed_id: Pubkey,
+}
+
+#[event]
+pub struct LockerCreated {
+    pub locker: Pubkey,
+    pub authority: Pubkey,
+    pub creation_ts: u32,
+}
+
+#[event]
+pub struct Deposited {
+    pub locker: Pubkey,
+    pub authority: Pubkey,
+    pub amount: u64,
+    // None for a SOL deposit
+    pub token_mint: Option<Pubkey>,
+    // The lot that records the deposit
+    pub lot_id: u16,
+    pub deposit_price: Option<u32>,
+    // Deposit fee transferred to the treasury, the lot holds the amount without the fee
+    pub fee: u64,
+}
+
+#[event]
+pub struct Staked {
+    pub locker: Pubkey,
+    pub authority: Pubkey,
+    pub stake_account: Pubkey,
+    pub vote_account: Pubkey,
+    pub lamports: u64,
+}
+
+#[event]
+pub struct LockCreated {
+    pub locker: Pubkey,
+    pub authority: Pubkey,
+    pub lock_id: u8,
+    pub amount: u32,
+    pub token_mint: Option<Pubkey>,
+    // Only one of the strike values is set, depending on the type of lock
+    pub strike_time: Option<u32>,
+    pub strike_price: Option<u32>,
+    pub inactivity_period: Option<u32>,
+    pub join: Option<u8>,
+    pub lot: Option<u16>,
+    // The wallet that claims the funds once unlocked, None when released to the authority
+    pub beneficiary: Option<Pubkey>,
+    // Terms under which the authority may exit the lock early, None when it can't
+    pub early_exit: Option<EarlyExit>,
+    // Price feed of a price lock
+    pub price_feed: Option<Pubkey>,
+}
+
+impl LockCreated {
+    pub fn new(locker: Pubkey, authority: Pubkey, lock: &Lock) -> Self {
+        let (strike_time, strike_price, price_feed, inactivity_period) = match *lock {
+            Lock::TimeLock { strike_time, .. } => (Some(strike_time), None, None, None),
+            Lock::PriceLock { strike_price, price_feed, .. } => (None, Some(strike_price), Some(price_feed), None),
+            Lock::Inactivity { period, .. } => (None, None, None, Some(period)),
+        };
+        LockCreated {
+            locker,
+            authority,
+            lock_id: lock.id(),
+            amount: lock.amount(),
+            token_mint: lock.token_mint(),
+            strike_time,
+            strike_price,
+            inactivity_period,
+            join: lock.join(),
+            lot: lock.lot(),
+            beneficiary: lock.beneficiary(),
+            early_exit: lock.early_exit(),
+            price_feed,
+        }
+    }
+}
+
+#[event]
+pub struct LockUnlocked {
+    pub locker: Pubkey,
+    pub authority: Pubkey,
+    pub lock_id: u8,
+    pub amount: u32,
+    pub token_mint: Option<Pubkey>,
+    // The oracle price the lock was unlocked at, None for a time lock
+    pub price: Option<u32>,
+    pub unlock_ts: u32,
+}
+
+impl LockUnlocked {
+    pub fn new(locker: Pubkey, authority: Pubkey, lock: &Lock, price: Option<u32>, unlock_ts: u32) -> Self {
+        LockUnlocked {
+            locker,
+            authority,
+            lock_id: lock.id(),
+            amount: lock.amount(),
+            token_mint: lock.token_mint(),
+            price,
+            unlock_ts,
+        }
+    }
+}
+
+#[event]
+pub struct CheckedIn {
+    pub locker: Pubkey,
+    pub authority: Pubkey,
+    pub last_activity_ts: u32,
+}
+
+#[event]
+pub struct InactivityLockRevoked {
+    pub locker: Pubkey,
+    pub authority: Pubkey,
+    pub lock_id: u8,
+    pub heir: Option<Pubkey>,
+}
+
+#[event]
+pub struct Withdrawn {
+    pub locker: Pubkey,
+    pub authority: Pubkey,
+    pub amount: u64,
+    // None for a SOL withdrawal
+    pub token_mint: Option<Pubkey>,
+    // Wallet that received the withdrawal
+    pub destination: Pubkey,
+}
+
+#[event]
+pub struct BeneficiaryClaimed {
+    pub locker: Pubkey,
+    pub beneficiary: Pubkey,
+    pub lock_id: u8,
+    pub amount: u64,
+    // None for a SOL claim
+    pub token_mint: Option<Pubkey>,
+}
+
+#[event]
+pub struct WithdrawalAllowlistProposed {
+    pub locker: Pubkey,
+    pub authority: Pubkey,
+    // Empty when the restriction is removed
+    pub destinations: Vec<Pubkey>,
+    pub active_ts: u32,
+}
+
+#[event]
+pub struct WithdrawalAllowlistChangeCancelled {
+    pub locker: Pubkey,
+    pub authority: Pubkey,
+}
+
+#[event]
+pub struct WithdrawalCooldownSet {
+    pub locker: Pubkey,
+    pub authority: Pubkey,
+    pub cooldown_secs: u32,
+    // When the cooldown takes effect, later than now for a shorter cooldown
+    pub active_ts: u32,
+}
+
+#[event]
+pub struct WithdrawalRequested {
+    pub locker: Pubkey,
+    pub authority: Pubkey,
+    pub request: WithdrawalRequest,
+}
+
+#[event]
+pub struct WithdrawalCancelled {
+    pub locker: Pubkey,
+    // The authority or a guardian
+    pub cancelled_by: Pubkey,
+    pub request: WithdrawalRequest,
+}
+
+#[event]
+pub struct GuardiansSet {
+    pub locker: Pubkey,
+    pub authority: Pubkey,
+    // None when the guardians were removed
+    pub guardians: Option<GuardianConfig>,
+}
+
+#[event]
+pub struct EarlyReleased {
+    pub locker: Pubkey,
+    pub authority: Pubkey,
+    pub lock_id: u8,
+    pub token_mint: Option<Pubkey>,
+    // Funds the release unlocked, including the penalty and the fee
+    pub released: u64,
+    pub penalty: u64,
+    pub penalty_destination: Pubkey,
+    // Early exit fee transferred to the treasury
+    pub fee: u64,
+    // Guardians that signed the release
+    pub approvals: Vec<Pubkey>,
+}
+
+#[event]
+pub struct ExitedEarly {
+    pub locker: Pubkey,
+    pub authority: Pubkey,
+    pub lock_id: u8,
+    pub token_mint: Option<Pubkey>,
+    // Funds the exit unlocked, including the penalty and the fee
+    pub released: u64,
+    pub penalty: u64,
+    // Penalty at the time of the exit, decayed from the maximum of the lock
+    pub penalty_bps: u16,
+    pub penalty_destination: Pubkey,
+    // Early exit fee transferred to the treasury
+    pub fee: u64,
+}
+
+#[event]
+pub struct ConfigUpdated {
+    pub admin: Pubkey,
+    pub fees: Fees,
+}
+
+#[event]
+pub struct PauseSet {
+    pub admin: Pubkey,
+    pub paused: bool,
+}
+
+#[event]
+pub struct CircuitBreakerSet {
+    pub admin: Pubkey,
+    pub circuit_breaker: CircuitBreaker,
+}
+
+#[event]
+pub struct FeesWithdrawn {
+    pub admin: Pubkey,
+    // None for SOL
+    pub token_mint: Option<Pubkey>,
+    pub amount: u64,
+}
+
+#[event]
+pub struct LockerMigrated {
+    pub locker: Pubkey,
+    pub authority: Pubkey,
+}
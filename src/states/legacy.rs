@@ -0,0 +1,82 @@
+use anchor_lang::prelude::*;
+use std::mem;
+
+use crate::states::{Lock, Locker, LockerErrorCode, WithdrawalAllowlist, WithdrawalCooldown};
+
+
+// Layout of the lockers created before lots, beneficiaries and guardians were added, see migrate_locker
+// The accounts have the same discriminator as the current lockers, and were never resized
+#[derive(Debug, AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
+pub struct LegacyLocker {
+    pub authority: Pubkey,
+    pub creation_ts: u32,
+    pub locked_balance: u32,
+    pub locks: Vec<LegacyLock>,
+    pub locked: bool,
+    pub staked: bool,
+    pub bump: u8,
+}
+
+#[derive(Debug, AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum LegacyLock {
+    TimeLock {
+        id: u8,
+        strike_time: u32,
+        amount: u32,
+        token_mint: Option<Pubkey>,
+        locked: bool,
+        join: Option<u8>,
+    },
+    PriceLock {
+        id: u8,
+        strike_price: u32,
+        amount: u32,
+        token_mint: Option<Pubkey>,
+        locked: bool,
+        join: Option<u8>,
+    },
+}
+
+impl LegacyLocker {
+    // Account space of a legacy locker, as it was allocated by create_new_locker
+    // The current lockers are larger from their creation, so only legacy lockers have this exact size
+    pub const SPACE: usize = 8 + mem::size_of::<LegacyLocker>() + 100;
+
+    // The locker in the current layout, the price locks are bound to the given price feed
+    // Without deposits recorded as lots, the locks lock any funds of their asset like before
+    pub fn migrate(&self, price_feed: Option<Pubkey>, time_now: u32) -> std::result::Result<Locker, LockerErrorCode> {
+        let locks = self.locks.iter().map(|legacy_lock| legacy_lock.migrate(price_feed)).collect::<std::result::Result<Vec<Lock>, _>>()?;
+        let mut locker = Locker {
+            authority: self.authority,
+            creation_ts: self.creation_ts,
+            locked_balance: self.locked_balance,
+            locks,
+            locked: self.locked,
+            staked: self.staked,
+            bump: self.bump,
+            lots: Vec::new(),
+            next_lot_id: 0,
+            last_activity_ts: time_now,
+            guardians: None,
+            withdrawal_allowlist: WithdrawalAllowlist::default(),
+            withdrawal_cooldown: WithdrawalCooldown::default(),
+            withdrawal_request: None,
+        };
+        locker.update_locked_balance();
+        Ok(locker)
+    }
+}
+
+impl LegacyLock {
+    fn migrate(&self, price_feed: Option<Pubkey>) -> std::result::Result<Lock, LockerErrorCode> {
+        match *self {
+            LegacyLock::TimeLock { id, strike_time, amount, token_mint, locked, join } => {
+                Ok(Lock::TimeLock { id, strike_time, amount, token_mint, locked, join, lot: None, beneficiary: None, early_exit: None })
+            },
+            LegacyLock::PriceLock { id, strike_price, amount, token_mint, locked, join } => {
+                let price_feed = price_feed.ok_or(LockerErrorCode::MissingPriceFeedAccounts)?;
+                Ok(Lock::PriceLock { id, strike_price, price_feed, amount, token_mint, locked, join, lot: None, beneficiary: None })
+            },
+        }
+    }
+}
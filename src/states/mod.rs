@@ -1,5 +1,11 @@
 pub mod states;
 pub mod errors;
+pub mod events;
+pub mod results;
+pub mod legacy;
 
 pub use states::*;
 pub use errors::*;
+pub use events::*;
+pub use results::*;
+pub use legacy::*;
@@ -0,0 +1,22 @@
+use anchor_lang::prelude::*;
+
+use crate::LockerErrorCode;
+
+
+// Results of instructions that are returned to the caller via return data
+
+// A locked lock that was not unlocked, with the reason it could not be unlocked
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct SkippedLock {
+    pub lock_id: u8,
+    pub reason: LockerErrorCode,
+}
+
+// Summary of an unlock of one lock, or of all locks (index 255)
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct UnlockResult {
+    // ids of the locks that were unlocked
+    pub unlocked: Vec<u8>,
+    // locked locks that were not (yet) eligible to be unlocked, only reported when unlocking all locks
+    pub skipped: Vec<SkippedLock>,
+}
@@ -1,7 +1,6 @@
 use crate::*;
 use pyth_sdk_solana::state::load_price_account;
 use std::ops::Deref;
-use std::str::FromStr;
 
 use crate::PythErrorCode;
 
@@ -13,6 +12,111 @@ pub struct Pricefeedaccount {
     pub pricefeed_alias: String,
     pub pricefeed_id: Pubkey,
     pub bump: u8,
+    // Start of the current window of the circuit breaker, updated by the price unlocks
+    pub price_reference: PriceReference,
+}
+
+// Price of a feed at the start of a window of the circuit breaker, zero before the first price unlock
+#[derive(Debug, Default, AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub struct PriceReference {
+    pub price: u32,
+    pub ts: u32,
+}
+
+// Current price of a registered price feed, at which the price locks created on that feed are evaluated
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PriceReading {
+    // Address of the Pricefeedaccount the price is read through
+    pub price_feed: Pubkey,
+    pub price: u32,
+}
+
+
+// Program wide settings of the admin, a single account at the config PDA
+#[account]
+pub struct ProgramConfig {
+    pub admin: Pubkey,
+    pub fees: Fees,
+    pub bump: u8,
+    pub treasury_bump: u8,
+    // Blocks new deposits, new locks and price unlocks, withdrawals of unlocked funds are always allowed
+    pub paused: bool,
+    pub circuit_breaker: CircuitBreaker,
+}
+
+impl ProgramConfig {
+    // Read the config account, None while the config is not initialized
+    pub fn load(config_info: &AccountInfo) -> Result<Option<ProgramConfig>> {
+        if *config_info.owner != crate::ID || config_info.data_is_empty() {
+            return Ok(None);
+        }
+        let config = ProgramConfig::try_deserialize(&mut &config_info.try_borrow_data()?[..])?;
+        Ok(Some(config))
+    }
+
+    // Read the fees from the config account, no fees are charged while the config is not initialized
+    pub fn load_fees(config_info: &AccountInfo) -> Result<Fees> {
+        Ok(Self::load(config_info)?.map(|config| config.fees).unwrap_or_default())
+    }
+
+    // Read the circuit breaker from the config account, disabled while the config is not initialized
+    pub fn load_circuit_breaker(config_info: &AccountInfo) -> Result<CircuitBreaker> {
+        Ok(Self::load(config_info)?.map(|config| config.circuit_breaker).unwrap_or_default())
+    }
+
+    // Fail while the admin paused the program
+    pub fn check_not_paused(config_info: &AccountInfo) -> Result<()> {
+        let paused = Self::load(config_info)?.is_some_and(|config| config.paused);
+        require!(!paused, LockerErrorCode::ProgramPaused);
+        Ok(())
+    }
+}
+
+// Halts the price unlocks of a feed when its price moves too much within a short window,
+//  e.g. a flash crash or a manipulated oracle price
+#[derive(Debug, Default, AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub struct CircuitBreaker {
+    // Largest move in basis points from the price at the start of the window, and of the first price of a window
+    //  from the EMA price of the oracle, 0 disables the circuit breaker
+    pub max_price_move_bps: u16,
+    pub window_secs: u32,
+}
+
+impl CircuitBreaker {
+    pub fn is_enabled(&self) -> bool {
+        self.max_price_move_bps > 0
+    }
+
+    pub fn validate(&self) -> std::result::Result<(), LockerErrorCode> {
+        if self.is_enabled() && self.window_secs == 0 { Err(LockerErrorCode::InvalidCircuitBreaker) } else { Ok(()) }
+    }
+}
+
+// Protocol fees in basis points, collected in the treasury
+#[derive(Debug, Default, AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub struct Fees {
+    // Of every deposit of fungible funds
+    pub deposit_bps: u16,
+    // Of the funds released by an early exit or a guardian release, on top of the penalty
+    pub early_exit_bps: u16,
+    // Of the rewards of staked funds, not charged yet as no instruction withdraws staking rewards
+    pub staking_reward_bps: u16,
+}
+
+impl Fees {
+    // Upper bound of every fee, so the admin can never take a large share of the funds
+    pub const MAX_FEE_BPS: u16 = 1_000;
+
+    pub fn validate(&self) -> std::result::Result<(), LockerErrorCode> {
+        let valid = [self.deposit_bps, self.early_exit_bps, self.staking_reward_bps].iter().all(|fee_bps| *fee_bps <= Self::MAX_FEE_BPS);
+        if valid { Ok(()) } else { Err(LockerErrorCode::InvalidFee) }
+    }
+}
+
+// Collects the fees, SOL as lamports of this PDA and SPL tokens in its associated token accounts
+#[account]
+pub struct Treasury {
+    pub bump: u8,
 }
 
 
@@ -25,8 +129,237 @@ pub struct Locker {
     pub locked: bool,
     pub staked: bool,
     pub bump: u8,
+    // Deposits that were not withdrawn yet, oldest first
+    pub lots: Vec<Lot>,
+    pub next_lot_id: u16,
+    // Last time the authority signed an instruction, inactivity locks unlock a period after it
+    pub last_activity_ts: u32,
+    // Guardians that can approve the early release of a lock, see guardian_release
+    pub guardians: Option<GuardianConfig>,
+    // Wallets the funds of the locker can be withdrawn or paid out to, see set_withdrawal_allowlist
+    pub withdrawal_allowlist: WithdrawalAllowlist,
+    // Withdrawals are requested and executed after the cooldown when it is set, see request_withdrawal
+    pub withdrawal_cooldown: WithdrawalCooldown,
+    pub withdrawal_request: Option<WithdrawalRequest>,
+}
+
+impl Locker {
+    // Lock ids are the u8 positions of the locks and index 255 unlocks all locks, so the ids go from 0 to 254
+    pub const MAX_LOCKS: usize = 255;
+
+    // Record that the authority signed an instruction, which restarts the period of the inactivity locks
+    pub fn record_activity(&mut self) -> Result<()> {
+        self.last_activity_ts = Clock::get()?.unix_timestamp as u32;
+        Ok(())
+    }
+
+    // Get the balance of an asset (None for SOL) that is still locked by the locks
+    pub fn get_locked_balance(&self, token_mint: &Option<Pubkey>) -> u64 {
+        accounting::locked_balance(&self.locks, token_mint)
+    }
+
+    // Record a deposit as a new lot, and return the id of the lot
+    pub fn add_lot(&mut self, token_mint: Option<Pubkey>, amount: u32, deposit_ts: u32, deposit_price: Option<u32>) -> u16 {
+        let id = self.next_lot_id;
+        self.next_lot_id += 1;
+        self.lots.push(Lot { id, token_mint, amount, deposit_ts, deposit_price });
+        id
+    }
+
+    // Consume a withdrawal from the lots of the asset, oldest lots first
+    pub fn consume_lots(&mut self, token_mint: &Option<Pubkey>, amount: u64) -> u64 {
+        accounting::consume_lots(&mut self.lots, &self.locks, token_mint, amount)
+    }
+
+    // Consume a claim of a beneficiary from the lot of the lock, or from the oldest lots of the asset
+    pub fn consume_claim(&mut self, lock_item: &Lock, amount: u64) -> u64 {
+        match lock_item.lot() {
+            Some(lot_id) => accounting::consume_lot(&mut self.lots, lot_id, amount),
+            None => self.consume_lots(&lock_item.token_mint(), amount),
+        }
+    }
+
+    // Fail when the funds of the locker can't go to the wallet under the withdrawal allowlist in effect
+    pub fn check_destination(&self, wallet: &Pubkey) -> Result<()> {
+        let time_now = Clock::get()?.unix_timestamp as u32;
+        require!(self.withdrawal_allowlist.allows(wallet, time_now), LockerErrorCode::DestinationNotAllowed);
+        Ok(())
+    }
+
+    pub fn get_lot(&self, lot_id: u16) -> Option<&Lot> {
+        self.lots.iter().find(|lot| lot.id == lot_id)
+    }
+
+    // Account space to store the locker with additional lots and locks
+    pub fn required_space(&self, new_lots: usize, new_locks: usize) -> usize {
+        let data_len = self.try_to_vec().map_or(0, |data| data.len());
+        8 + data_len + new_lots * Lot::MAX_SPACE + new_locks * Lock::MAX_SPACE
+    }
+
+    // Recompute the locked SOL balance after the locks or the balances changed
+    pub fn update_locked_balance(&mut self) {
+        self.locked_balance = u32::try_from(self.get_locked_balance(&None)).unwrap_or(u32::MAX);
+    }
+}
+
+// Grow the locker account when there is no space left for additional lots and locks, the account never shrinks
+// The payer adds the extra rent, the lamports of the locker are deposits and stay untouched
+pub(crate) fn grow_locker<'info>(locker: &Account<'info, Locker>, payer: &Signer<'info>, new_lots: usize, new_locks: usize) -> Result<()> {
+    let locker_info = locker.to_account_info();
+    let current_space = locker_info.data_len();
+    let required_space = locker.required_space(new_lots, new_locks);
+    if required_space <= current_space {
+        return Ok(());
+    }
+
+    let rent = Rent::get()?;
+    let extra_rent = rent.minimum_balance(required_space).saturating_sub(rent.minimum_balance(current_space));
+    invoke(
+        &system_instruction::transfer(payer.key, locker_info.key, extra_rent),
+        &[payer.to_account_info(), locker_info.clone()],
+    )?;
+
+    locker_info.realloc(required_space, false)?;
+    Ok(())
+}
+
+// M-of-N guardians of a locker, the penalty of an early release goes to the penalty destination
+// e.g. 2 of 3 friends can release a lock in an emergency, and 10% of the funds go to a charity
+#[derive(Debug, AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
+pub struct GuardianConfig {
+    pub guardians: Vec<Pubkey>,
+    // Number of guardians that must approve an early release
+    pub threshold: u8,
+    // Penalty in basis points of the released funds
+    pub penalty_bps: u16,
+    pub penalty_destination: Pubkey,
+}
+
+impl GuardianConfig {
+    pub const MAX_GUARDIANS: usize = 10;
+
+    // Check the guardians are distinct, and the threshold and penalty are reachable
+    // The authority can't be a guardian or receive the penalty, otherwise it could release its own locks for free
+    pub fn validate(&self, authority: &Pubkey) -> std::result::Result<(), LockerErrorCode> {
+        let distinct = self.guardians.iter().enumerate().all(|(index, guardian)| !self.guardians[..index].contains(guardian));
+        let valid = !self.guardians.is_empty()
+            && self.guardians.len() <= Self::MAX_GUARDIANS
+            && distinct
+            && self.threshold >= 1
+            && self.threshold as usize <= self.guardians.len()
+            && self.penalty_bps as u64 <= accounting::BPS_DENOMINATOR
+            && !self.guardians.contains(authority)
+            && self.penalty_destination != *authority;
+        if valid { Ok(()) } else { Err(LockerErrorCode::InvalidGuardianConfig) }
+    }
+
+    // Number of distinct guardians among the signers
+    pub fn approvals(&self, signers: &[Pubkey]) -> usize {
+        self.guardians.iter().filter(|guardian| signers.contains(guardian)).count()
+    }
+}
+
+// Destinations of the withdrawals and payouts of a locker, unrestricted while there are none
+// A change only takes effect after a delay and can be cancelled until then,
+//  so a compromised key can't redirect the unlocked funds right away
+#[derive(Debug, Default, AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
+pub struct WithdrawalAllowlist {
+    pub destinations: Vec<Pubkey>,
+    // Replaces the destinations at its activation time, unless it is cancelled before
+    pub pending: Option<PendingAllowlist>,
+}
+
+#[derive(Debug, AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
+pub struct PendingAllowlist {
+    pub destinations: Vec<Pubkey>,
+    pub active_ts: u32,
 }
 
+impl WithdrawalAllowlist {
+    pub const MAX_DESTINATIONS: usize = 10;
+    // 48 hours
+    pub const CHANGE_DELAY_SECS: u32 = 48 * 60 * 60;
+
+    // Destinations in effect at time_now, the pending change once its activation time passed
+    pub fn active_destinations(&self, time_now: u32) -> &[Pubkey] {
+        match &self.pending {
+            Some(pending) if pending.active_ts <= time_now => &pending.destinations,
+            _ => &self.destinations,
+        }
+    }
+
+    pub fn allows(&self, wallet: &Pubkey, time_now: u32) -> bool {
+        let destinations = self.active_destinations(time_now);
+        destinations.is_empty() || destinations.contains(wallet)
+    }
+
+    // Store a pending change that took effect as the destinations, a change can't be cancelled after its activation time
+    pub fn apply_due_change(&mut self, time_now: u32) {
+        if self.pending.as_ref().is_some_and(|pending| pending.active_ts <= time_now) {
+            self.destinations = self.pending.take().map(|pending| pending.destinations).unwrap_or_default();
+        }
+    }
+
+    pub fn validate_destinations(destinations: &[Pubkey]) -> std::result::Result<(), LockerErrorCode> {
+        let distinct = destinations.iter().enumerate().all(|(index, destination)| !destinations[..index].contains(destination));
+        if distinct && destinations.len() <= Self::MAX_DESTINATIONS { Ok(()) } else { Err(LockerErrorCode::InvalidWithdrawalAllowlist) }
+    }
+}
+
+// Time between requesting and executing a withdrawal, withdrawals are direct while it is zero
+#[derive(Debug, Default, AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub struct WithdrawalCooldown {
+    pub cooldown_secs: u32,
+    // A shorter cooldown only takes effect once the current cooldown passed, so a compromised key can't skip it
+    pub pending: Option<PendingCooldown>,
+}
+
+#[derive(Debug, AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub struct PendingCooldown {
+    pub cooldown_secs: u32,
+    pub active_ts: u32,
+}
+
+impl WithdrawalCooldown {
+    // 30 days
+    pub const MAX_COOLDOWN_SECS: u32 = 30 * 24 * 60 * 60;
+
+    // Cooldown in effect at time_now, the pending cooldown once its activation time passed
+    pub fn active_cooldown_secs(&self, time_now: u32) -> u32 {
+        match self.pending {
+            Some(pending) if pending.active_ts <= time_now => pending.cooldown_secs,
+            _ => self.cooldown_secs,
+        }
+    }
+
+    // Change the cooldown at time_now, and return when the new cooldown takes effect
+    // A longer cooldown takes effect right away, a shorter one after the cooldown in effect
+    pub fn set(&mut self, cooldown_secs: u32, time_now: u32) -> u32 {
+        let active_cooldown_secs = self.active_cooldown_secs(time_now);
+        self.pending = None;
+        if cooldown_secs >= active_cooldown_secs {
+            self.cooldown_secs = cooldown_secs;
+            return time_now;
+        }
+        self.cooldown_secs = active_cooldown_secs;
+        let active_ts = time_now.saturating_add(active_cooldown_secs);
+        self.pending = Some(PendingCooldown { cooldown_secs, active_ts });
+        active_ts
+    }
+}
+
+// Withdrawal of a locker with a cooldown, which the authority executes once the cooldown passed
+// Until then the authority or a guardian can cancel it, e.g. when the key of the authority is compromised
+#[derive(Debug, AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub struct WithdrawalRequest {
+    pub amount: u64,
+    // None for SOL
+    pub token_mint: Option<Pubkey>,
+    pub destination: Pubkey,
+    pub executable_ts: u32,
+}
+
+
 #[derive(Debug, AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
 // added the initspace macro here against ChatGPT's advice, but seems necessary
 // look here if the account structure is throwing errors
@@ -38,30 +371,192 @@ pub enum Lock {
         token_mint: Option<Pubkey>,
         locked: bool,
         join: Option<u8>,
+        // The lot (deposit) of which the lock locks funds, or any funds of the asset
+        lot: Option<u16>,
+        // Wallet that claims the funds once unlocked, instead of the authority
+        beneficiary: Option<Pubkey>,
+        // Terms under which the authority may exit before the strike time, None when it can't
+        early_exit: Option<EarlyExit>,
     },
     PriceLock {
         id: u8,
         strike_price: u32,
+        // Registered price feed the lock was created on, only a price of this feed unlocks it
+        price_feed: Pubkey,
         amount: u32, 
         token_mint: Option<Pubkey>,
         locked: bool,
         join: Option<u8>,
+        // The lot (deposit) of which the lock locks funds, or any funds of the asset
+        lot: Option<u16>,
+        // Wallet that claims the funds once unlocked, instead of the authority
+        beneficiary: Option<Pubkey>,
+    },
+    // Dead man's switch, unlocks when the authority was inactive for the period (in seconds), see Locker::last_activity_ts
+    // The funds are claimed by the heir, which is the beneficiary of the lock
+    Inactivity {
+        id: u8,
+        period: u32,
+        amount: u32,
+        token_mint: Option<Pubkey>,
+        locked: bool,
+        join: Option<u8>,
+        lot: Option<u16>,
+        beneficiary: Option<Pubkey>,
     },
 }
 
+impl Lock {
+    // Maximum serialized size of a lock, with a token mint, a join, a lot, a beneficiary and an early exit
+    pub const MAX_SPACE: usize = 1 + 1 + 4 + 4 + 33 + 1 + 2 + 3 + 33 + 1 + EarlyExit::SPACE;
+
+    // The id of the lock, which is equivalent to its position in the locks vector
+    pub fn id(&self) -> u8 {
+        match self {
+            Lock::TimeLock { id, .. } | Lock::PriceLock { id, .. } | Lock::Inactivity { id, .. } => *id,
+        }
+    }
+
+    pub fn amount(&self) -> u32 {
+        match self {
+            Lock::TimeLock { amount, .. } | Lock::PriceLock { amount, .. } | Lock::Inactivity { amount, .. } => *amount,
+        }
+    }
+
+    pub fn token_mint(&self) -> Option<Pubkey> {
+        match self {
+            Lock::TimeLock { token_mint, .. } | Lock::PriceLock { token_mint, .. } | Lock::Inactivity { token_mint, .. } => *token_mint,
+        }
+    }
+
+    pub fn join(&self) -> Option<u8> {
+        match self {
+            Lock::TimeLock { join, .. } | Lock::PriceLock { join, .. } | Lock::Inactivity { join, .. } => *join,
+        }
+    }
+
+    pub fn lot(&self) -> Option<u16> {
+        match self {
+            Lock::TimeLock { lot, .. } | Lock::PriceLock { lot, .. } | Lock::Inactivity { lot, .. } => *lot,
+        }
+    }
+
+    pub fn beneficiary(&self) -> Option<Pubkey> {
+        match self {
+            Lock::TimeLock { beneficiary, .. } | Lock::PriceLock { beneficiary, .. } | Lock::Inactivity { beneficiary, .. } => *beneficiary,
+        }
+    }
+
+    pub fn is_locked(&self) -> bool {
+        match self {
+            Lock::TimeLock { locked, .. } | Lock::PriceLock { locked, .. } | Lock::Inactivity { locked, .. } => *locked,
+        }
+    }
+
+    // Only time locks can have an early exit, the penalty decays toward their strike time
+    pub fn early_exit(&self) -> Option<EarlyExit> {
+        match self {
+            Lock::TimeLock { early_exit, .. } => *early_exit,
+            _ => None,
+        }
+    }
+}
+
+// Soft commitment of a time lock, the authority alone may exit before the strike time by forfeiting a penalty
+// The penalty decays linearly from max_penalty_bps at the start to zero at the strike time
+#[derive(Debug, AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub struct EarlyExit {
+    // Time the lock was created, when the penalty is at its maximum
+    pub start_ts: u32,
+    pub max_penalty_bps: u16,
+    // Wallet that receives the forfeited funds
+    pub penalty_destination: Pubkey,
+}
+
+impl EarlyExit {
+    pub const SPACE: usize = 4 + 2 + 32;
+}
+
+
+// A deposit of SOL or an SPL token, that locks can lock funds of
+// Withdrawals consume the funds of the oldest lots first, lots that are consumed entirely are removed
+#[derive(Debug, AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub struct Lot {
+    pub id: u16,
+    pub token_mint: Option<Pubkey>,
+    // Amount of the deposit that is not withdrawn yet
+    pub amount: u32,
+    pub deposit_ts: u32,
+    // Oracle price at the time of the deposit, if a price feed was passed
+    pub deposit_price: Option<u32>,
+}
+
+impl Lot {
+    // Maximum serialized size of a lot, with a token mint and a deposit price
+    pub const MAX_SPACE: usize = 2 + 33 + 4 + 4 + 5;
+}
+
+
+// Condition of a lock on a lot, see deposit_and_lock and lock_lot
+#[derive(Debug, AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum LockCondition {
+    Time { strike_time: u32 },
+    // The price of the registered price feed (its Pricefeedaccount address) named by the authority
+    Price { strike_price: u32, price_feed: Pubkey },
+    // Relative time lock, unlocks a duration (in seconds) after the deposit of the lot e.g. 90 days
+    AfterDeposit { duration: u32 },
+    // Unlocks to the beneficiary (the heir) when the authority was inactive for the period (in seconds)
+    Inactivity { period: u32 },
+    // Time lock the authority may exit before the strike time, see EarlyExit
+    // Not with a beneficiary, and the penalty destination can't be the authority
+    TimeWithEarlyExit { strike_time: u32, max_penalty_bps: u16, penalty_destination: Pubkey },
+}
+
+impl LockCondition {
+    // The lock with this condition on an amount of a lot, created at time_now
+    pub fn to_lock(&self, id: u8, amount: u32, lot: &Lot, join: Option<u8>, beneficiary: Option<Pubkey>, time_now: u32) -> Lock {
+        let (token_mint, lot_id) = (lot.token_mint, Some(lot.id));
+        match *self {
+            LockCondition::Time { strike_time } => Lock::TimeLock { id, strike_time, amount, token_mint, locked: true, join, lot: lot_id, beneficiary, early_exit: None },
+            LockCondition::Price { strike_price, price_feed } => Lock::PriceLock { id, strike_price, price_feed, amount, token_mint, locked: true, join, lot: lot_id, beneficiary },
+            LockCondition::AfterDeposit { duration } => {
+                let strike_time = lot.deposit_ts.saturating_add(duration);
+                Lock::TimeLock { id, strike_time, amount, token_mint, locked: true, join, lot: lot_id, beneficiary, early_exit: None }
+            },
+            LockCondition::Inactivity { period } => Lock::Inactivity { id, period, amount, token_mint, locked: true, join, lot: lot_id, beneficiary },
+            LockCondition::TimeWithEarlyExit { strike_time, max_penalty_bps, penalty_destination } => {
+                let early_exit = Some(EarlyExit { start_ts: time_now, max_penalty_bps, penalty_destination });
+                Lock::TimeLock { id, strike_time, amount, token_mint, locked: true, join, lot: lot_id, beneficiary, early_exit }
+            },
+        }
+    }
+}
 
 
 // PYTH integrations
 
 
+// Program id of the Pyth oracle on solana devnet, parsed at compile time
+pub mod pyth_oracle {
+    use anchor_lang::declare_id;
+
+    declare_id!("gSbePebfvPy7tRqimPoVecS2UsBvYv46ynrzWocc92s");
+}
+
 #[derive(Clone)]
 pub struct PriceFeed(pyth_sdk::PriceFeed);
 
 impl anchor_lang::Owner for PriceFeed {
+    #[cfg(not(feature = "localnet"))]
     fn owner() -> Pubkey {
         // Make sure the owner is the pyth oracle account on solana devnet
-        let oracle_addr = "gSbePebfvPy7tRqimPoVecS2UsBvYv46ynrzWocc92s";
-        return Pubkey::from_str(&oracle_addr).unwrap();
+        pyth_oracle::ID
+    }
+
+    // On a local validator there is no Pyth oracle, the price accounts are written by the mock oracle instead
+    #[cfg(feature = "localnet")]
+    fn owner() -> Pubkey {
+        mock_oracle::ID
     }
 }
 
@@ -0,0 +1,334 @@
+// Property tests of the lock evaluation, balance accounting and lots
+use anchor_lang::prelude::Pubkey;
+use pricelocker::accounting::*;
+use pricelocker::{CircuitBreaker, EarlyExit, Lock, Locker, LockerErrorCode, Lot, PriceReading, PriceReference};
+use proptest::prelude::*;
+
+
+// The price feed all price locks are created on
+const PRICE_FEED: Pubkey = Pubkey::new_from_array([8; 32]);
+
+fn reading(price: u32) -> Option<PriceReading> {
+    Some(PriceReading { price_feed: PRICE_FEED, price })
+}
+
+// Locks on SOL and on one SPL token, with small ids so joins often hit existing locks
+fn token_mint() -> impl Strategy<Value = Option<Pubkey>> {
+    prop_oneof![Just(None), Just(Some(Pubkey::new_from_array([7; 32])))]
+}
+
+fn lock_item(id: u8) -> impl Strategy<Value = Lock> {
+    (0..3u8, 0..2_000u32, 1..1_000_000u32, token_mint(), any::<bool>(), proptest::option::of(0..8u8), proptest::option::of(0..4u16)).prop_map(
+        move |(kind, strike, amount, token_mint, locked, join, lot)| match kind {
+            0 => Lock::TimeLock { id, strike_time: strike, amount, token_mint, locked, join, lot, beneficiary: None, early_exit: None },
+            1 => Lock::PriceLock { id, strike_price: strike, price_feed: PRICE_FEED, amount, token_mint, locked, join, lot, beneficiary: None },
+            _ => Lock::Inactivity { id, period: strike, amount, token_mint, locked, join, lot, beneficiary: None },
+        },
+    )
+}
+
+// Locks with ids equal to their position, like the locks of a locker
+fn locks() -> impl Strategy<Value = Vec<Lock>> {
+    (0..8u8).prop_flat_map(|count| (0..count).map(lock_item).collect::<Vec<_>>())
+}
+
+// Lots with ids equal to their position, like the lots of a locker where nothing was withdrawn
+fn lots() -> impl Strategy<Value = Vec<Lot>> {
+    proptest::collection::vec((token_mint(), 1..1_000_000u32, 0..2_000u32, proptest::option::of(0..2_000u32)), 0..4).prop_map(|lots| {
+        lots.into_iter().enumerate()
+            .map(|(id, (token_mint, amount, deposit_ts, deposit_price))| Lot { id: id as u16, token_mint, amount, deposit_ts, deposit_price })
+            .collect()
+    })
+}
+
+// A lock that is added to the locks, which is always locked
+fn new_lock() -> impl Strategy<Value = Lock> {
+    lock_item(8).prop_map(|mut lock_item| {
+        match &mut lock_item {
+            Lock::TimeLock { locked, .. } | Lock::PriceLock { locked, .. } | Lock::Inactivity { locked, .. } => *locked = true,
+        }
+        lock_item
+    })
+}
+
+
+proptest! {
+    #[test]
+    fn withdrawable_never_exceeds_unlocked_funds(total in 0..10_000_000u64, locks in locks(), token_mint in token_mint()) {
+        let locked = locked_balance(&locks, &token_mint);
+        let withdrawable = withdrawable_balance(total, &locks, &token_mint);
+
+        prop_assert!(withdrawable <= total);
+        prop_assert_eq!(withdrawable, total.saturating_sub(locked));
+    }
+
+    #[test]
+    fn locked_balance_never_exceeds_sum_of_locked_locks(locks in locks(), token_mint in token_mint()) {
+        let sum: u64 = locks.iter()
+            .filter(|lock_item| lock_item.is_locked() && lock_item.token_mint() == token_mint)
+            .map(|lock_item| lock_item.amount() as u64)
+            .sum();
+
+        prop_assert!(locked_balance(&locks, &token_mint) <= sum);
+    }
+
+    #[test]
+    fn accepted_lock_fits_in_total_balance(total in 0..10_000_000u64, locks in locks(), new_lock in new_lock()) {
+        if check_new_lock(total, &locks, &new_lock).is_ok() {
+            let mut new_locks = locks.clone();
+            new_locks.push(new_lock);
+            prop_assert!(locked_balance(&new_locks, &new_lock.token_mint()) <= total);
+            prop_assert!(new_lock.amount() > 0);
+        }
+    }
+
+    #[test]
+    fn new_lock_on_free_funds_is_accepted(total in 0..10_000_000u64, locks in locks(), mut new_lock in new_lock()) {
+        let (Lock::TimeLock { join, .. } | Lock::PriceLock { join, .. } | Lock::Inactivity { join, .. }) = &mut new_lock;
+        *join = None;
+
+        let free = withdrawable_balance(total, &locks, &new_lock.token_mint());
+        let result = check_new_lock(total, &locks, &new_lock);
+
+        prop_assert_eq!(result.is_ok(), new_lock.amount() as u64 <= free);
+    }
+
+    #[test]
+    fn new_lock_does_not_change_other_assets(total in 0..10_000_000u64, locks in locks(), new_lock in new_lock()) {
+        prop_assume!(check_new_lock(total, &locks, &new_lock).is_ok());
+
+        let mut new_locks = locks.clone();
+        new_locks.push(new_lock);
+
+        for token_mint in [None, Some(Pubkey::new_from_array([7; 32]))] {
+            if token_mint != new_lock.token_mint() {
+                prop_assert_eq!(locked_balance(&new_locks, &token_mint), locked_balance(&locks, &token_mint));
+            }
+        }
+    }
+
+    #[test]
+    fn unlocking_never_increases_locked_balance(
+        locks in locks(), index in any::<prop::sample::Index>(), time_now in 0..2_000u32, price in proptest::option::of(0..2_000u32), last_activity_ts in 0..2_000u32,
+    ) {
+        prop_assume!(!locks.is_empty());
+        let index = index.index(locks.len());
+
+        let mut unlocked_locks = locks.clone();
+        let price = price.and_then(reading);
+        let unlockable = is_unlockable(&locks[index], time_now, price, last_activity_ts);
+        let result = unlock(&mut unlocked_locks[index], time_now, price, last_activity_ts);
+
+        // A locked lock is unlocked exactly when it is unlockable, and then stays unlocked
+        if locks[index].is_locked() {
+            prop_assert_eq!(result.is_ok(), unlockable);
+        }
+        prop_assert_eq!(unlocked_locks[index].is_locked(), locks[index].is_locked() && result.is_err());
+
+        for token_mint in [None, Some(Pubkey::new_from_array([7; 32]))] {
+            prop_assert!(locked_balance(&unlocked_locks, &token_mint) <= locked_balance(&locks, &token_mint));
+        }
+    }
+
+    #[test]
+    fn accepted_lot_lock_fits_in_lot(lots in lots(), locks in locks(), new_lock in new_lock()) {
+        if check_lot_lock(&lots, &locks, &new_lock).is_ok() {
+            if let Some(lot_id) = new_lock.lot() {
+                let mut new_locks = locks.clone();
+                new_locks.push(new_lock);
+                let lot = lots.iter().find(|lot| lot.id == lot_id).unwrap();
+                prop_assert_eq!(lot.token_mint, new_lock.token_mint());
+                prop_assert!(lot_locked_balance(&new_locks, lot_id) <= lot.amount as u64);
+            }
+        }
+    }
+
+    #[test]
+    fn consuming_lots_never_takes_locked_funds(lots in lots(), locks in locks(), token_mint in token_mint(), amount in 0..3_000_000u64) {
+        let mut consumed_lots = lots.clone();
+        let consumed = consume_lots(&mut consumed_lots, &locks, &token_mint, amount);
+
+        let free: u64 = lots.iter()
+            .filter(|lot| lot.token_mint == token_mint)
+            .map(|lot| (lot.amount as u64).saturating_sub(lot_locked_balance(&locks, lot.id)))
+            .sum();
+        prop_assert_eq!(consumed, amount.min(free));
+
+        // Locked funds of a lot stay, and lots of other assets are untouched
+        for lot in &lots {
+            let remaining = consumed_lots.iter().find(|consumed_lot| consumed_lot.id == lot.id).map_or(0, |consumed_lot| consumed_lot.amount);
+            prop_assert!(remaining as u64 >= (lot.amount as u64).min(lot_locked_balance(&locks, lot.id)));
+            if lot.token_mint != token_mint {
+                prop_assert_eq!(remaining, lot.amount);
+            }
+        }
+        prop_assert!(consumed_lots.iter().all(|lot| lot.amount > 0));
+    }
+
+    #[test]
+    fn committed_balance_never_exceeds_locked_balance(locks in locks(), token_mint in token_mint(), min_unlock_ts in 0..2_000u32) {
+        prop_assert!(committed_balance(&locks, &token_mint, min_unlock_ts) <= locked_balance(&locks, &token_mint));
+    }
+}
+
+
+#[test]
+fn joined_locks_lock_the_same_funds_once() {
+    let locks = [
+        Lock::TimeLock { id: 0, strike_time: 100, amount: 500, token_mint: None, locked: true, join: None, lot: None, beneficiary: None, early_exit: None },
+        Lock::PriceLock { id: 1, strike_price: 100, price_feed: PRICE_FEED, amount: 300, token_mint: None, locked: true, join: Some(0), lot: None, beneficiary: None },
+    ];
+    assert_eq!(locked_balance(&locks, &None), 500);
+
+    // Unlocking either lock unlocks the funds of both
+    let mut unlocked_locks = locks;
+    unlock(&mut unlocked_locks[1], 0, reading(100), 0).unwrap();
+    assert_eq!(locked_balance(&unlocked_locks, &None), 0);
+}
+
+#[test]
+fn price_locks_only_unlock_at_the_price_of_their_price_feed() {
+    let lock_item = Lock::PriceLock { id: 0, strike_price: 100, price_feed: PRICE_FEED, amount: 500, token_mint: None, locked: true, join: None, lot: None, beneficiary: None };
+
+    // A higher price of another registered price feed doesn't unlock the lock
+    let other_feed = Some(PriceReading { price_feed: Pubkey::new_unique(), price: 1_000 });
+    assert!(matches!(check_unlock_condition(&lock_item, 0, other_feed, 0), Err(LockerErrorCode::WrongPriceFeed)));
+    assert!(!is_unlockable(&lock_item, 0, other_feed, 0));
+
+    assert!(matches!(check_unlock_condition(&lock_item, 0, reading(99), 0), Err(LockerErrorCode::StrikePriceTooLow)));
+    assert!(is_unlockable(&lock_item, 0, reading(100), 0));
+}
+
+#[test]
+fn time_locks_with_an_early_exit_are_not_committed() {
+    let early_exit = Some(EarlyExit { start_ts: 0, max_penalty_bps: 1_000, penalty_destination: Pubkey::new_unique() });
+    let locks = [
+        Lock::TimeLock { id: 0, strike_time: 100, amount: 500, token_mint: None, locked: true, join: None, lot: None, beneficiary: None, early_exit: None },
+        Lock::TimeLock { id: 1, strike_time: 100, amount: 300, token_mint: None, locked: true, join: None, lot: None, beneficiary: None, early_exit },
+    ];
+
+    // The authority can exit the second lock at any moment
+    assert_eq!(locked_balance(&locks, &None), 800);
+    assert_eq!(committed_balance(&locks, &None, 100), 500);
+}
+
+#[test]
+fn time_locks_of_a_beneficiary_are_not_committed() {
+    let beneficiary = Some(Pubkey::new_unique());
+    let locks = [
+        Lock::TimeLock { id: 0, strike_time: 100, amount: 500, token_mint: None, locked: true, join: None, lot: None, beneficiary: None, early_exit: None },
+        Lock::TimeLock { id: 1, strike_time: 100, amount: 300, token_mint: None, locked: true, join: None, lot: None, beneficiary, early_exit: None },
+    ];
+
+    // The second lock is paid out to the beneficiary, not to the authority
+    assert_eq!(locked_balance(&locks, &None), 800);
+    assert_eq!(committed_balance(&locks, &None, 100), 500);
+}
+
+#[test]
+fn new_lock_rejects_invalid_join() {
+    let locks = [
+        Lock::TimeLock { id: 0, strike_time: 100, amount: 500, token_mint: None, locked: false, join: None, lot: None, beneficiary: None, early_exit: None },
+        Lock::TimeLock { id: 1, strike_time: 100, amount: 500, token_mint: Some(Pubkey::new_unique()), locked: true, join: None, lot: None, beneficiary: None, early_exit: None },
+    ];
+
+    for join in [0, 1, 2] {
+        let new_lock = Lock::PriceLock { id: 2, strike_price: 100, price_feed: PRICE_FEED, amount: 100, token_mint: None, locked: true, join: Some(join), lot: None, beneficiary: None };
+        assert!(matches!(check_new_lock(1_000, &locks, &new_lock), Err(LockerErrorCode::InvalidJoin)));
+    }
+}
+
+#[test]
+fn new_lock_rejects_more_locks_than_lock_ids() {
+    let time_lock = |id| Lock::TimeLock { id, strike_time: 100, amount: 1, token_mint: None, locked: true, join: None, lot: None, beneficiary: None, early_exit: None };
+    let mut locks: Vec<Lock> = (0..254).map(time_lock).collect();
+
+    // Id 254 is the last one, id 255 is reserved to unlock all locks
+    assert!(check_new_lock(1_000, &locks, &time_lock(254)).is_ok());
+    locks.push(time_lock(254));
+    assert_eq!(locks.len(), Locker::MAX_LOCKS);
+    assert!(matches!(check_new_lock(1_000, &locks, &time_lock(255)), Err(LockerErrorCode::TooManyLocks)));
+}
+
+#[test]
+fn unlocked_funds_of_a_beneficiary_stay_locked_until_claimed() {
+    let beneficiary = Some(Pubkey::new_unique());
+    let mut locks = [
+        Lock::TimeLock { id: 0, strike_time: 100, amount: 500, token_mint: None, locked: true, join: None, lot: None, beneficiary, early_exit: None },
+        Lock::PriceLock { id: 1, strike_price: 100, price_feed: PRICE_FEED, amount: 500, token_mint: None, locked: true, join: Some(0), lot: None, beneficiary },
+    ];
+    assert!(matches!(claimable_balance(&locks, 0), Err(LockerErrorCode::LockNotUnlocked)));
+
+    unlock(&mut locks[0], 100, None, 0).unwrap();
+    assert_eq!(locked_balance(&locks, &None), 500);
+    assert_eq!(claimable_balance(&locks, 1), Ok(500));
+
+    // A claim reduces the joined locks together
+    claim(&mut locks, 1, 200).unwrap();
+    assert_eq!(locked_balance(&locks, &None), 300);
+    assert!(matches!(claim(&mut locks, 0, 301), Err(LockerErrorCode::PayoutAmountExceedsAvailableBalance)));
+    claim(&mut locks, 0, 300).unwrap();
+    assert_eq!(locked_balance(&locks, &None), 0);
+}
+
+#[test]
+fn inactivity_lock_unlocks_a_period_after_the_last_activity() {
+    let lock_item = Lock::Inactivity { id: 0, period: 100, amount: 500, token_mint: None, locked: true, join: None, lot: None, beneficiary: Some(Pubkey::new_unique()) };
+
+    assert!(matches!(check_unlock_condition(&lock_item, 1_099, None, 1_000), Err(LockerErrorCode::AuthorityStillActive)));
+    assert!(check_unlock_condition(&lock_item, 1_100, None, 1_000).is_ok());
+
+    // Activity of the authority restarts the period
+    assert!(!is_unlockable(&lock_item, 1_100, None, 1_050));
+}
+
+#[test]
+fn penalty_is_a_share_of_the_amount_in_basis_points() {
+    assert_eq!(bps_amount(500_000, 1_000), 50_000);
+    assert_eq!(bps_amount(999, 1), 0);
+    assert_eq!(bps_amount(u64::MAX, BPS_DENOMINATOR as u16), u64::MAX);
+}
+
+#[test]
+fn early_exit_penalty_decays_linearly_to_the_strike_time() {
+    let early_exit = EarlyExit { start_ts: 1_000, max_penalty_bps: 2_000, penalty_destination: Pubkey::new_unique() };
+
+    assert_eq!(early_exit_penalty_bps(&early_exit, 2_000, 900), 2_000);
+    assert_eq!(early_exit_penalty_bps(&early_exit, 2_000, 1_500), 1_000);
+    assert_eq!(early_exit_penalty_bps(&early_exit, 2_000, 1_999), 2);
+    assert_eq!(early_exit_penalty_bps(&early_exit, 2_000, 2_000), 0);
+}
+
+#[test]
+fn circuit_breaker_trips_on_a_large_move_within_the_window() {
+    let circuit_breaker = CircuitBreaker { max_price_move_bps: 1_000, window_secs: 300 };
+    let reference = PriceReference { price: 100, ts: 1_000 };
+
+    // Without a reference or after the window, the price starts a new window
+    assert_eq!(check_price_move(&circuit_breaker, PriceReference::default(), 100, 100, 1_000), Ok(reference));
+    assert_eq!(check_price_move(&circuit_breaker, reference, 200, 190, 1_300), Ok(PriceReference { price: 200, ts: 1_300 }));
+
+    // Moves up or down within the window are compared with the reference price, not the EMA price
+    assert_eq!(check_price_move(&circuit_breaker, reference, 90, 200, 1_299), Ok(reference));
+    assert_eq!(check_price_move(&circuit_breaker, reference, 111, 111, 1_100), Err(LockerErrorCode::CircuitBreakerTripped));
+    assert_eq!(check_price_move(&circuit_breaker, reference, 89, 89, 1_100), Err(LockerErrorCode::CircuitBreakerTripped));
+
+    // A disabled circuit breaker never trips
+    assert_eq!(check_price_move(&CircuitBreaker::default(), reference, 1, 1, 1_100), Ok(reference));
+}
+
+#[test]
+fn circuit_breaker_checks_the_first_price_of_a_window_against_the_ema_price() {
+    let circuit_breaker = CircuitBreaker { max_price_move_bps: 1_000, window_secs: 300 };
+    let reference = PriceReference { price: 100, ts: 1_000 };
+
+    // A manipulated price right after the window, or before the first window, is not accepted as the new reference
+    assert_eq!(check_price_move(&circuit_breaker, reference, 200, 100, 1_300), Err(LockerErrorCode::CircuitBreakerTripped));
+    assert_eq!(check_price_move(&circuit_breaker, PriceReference::default(), 200, 100, 1_000), Err(LockerErrorCode::CircuitBreakerTripped));
+
+    // Without an EMA price the first price of a window can't be checked
+    assert_eq!(check_price_move(&circuit_breaker, reference, 100, 0, 1_300), Err(LockerErrorCode::CircuitBreakerTripped));
+
+    // Once the EMA price followed the move, the price starts the new window
+    assert_eq!(check_price_move(&circuit_breaker, reference, 200, 185, 1_300), Ok(PriceReference { price: 200, ts: 1_300 }));
+}
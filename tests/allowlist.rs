@@ -0,0 +1,163 @@
+mod common;
+
+use common::*;
+use pricelocker::{LockCondition, LockerErrorCode, WithdrawalAllowlist, WITHDRAW_MAX};
+use pricelocker_client as client;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signer};
+
+
+const CHANGE_DELAY: i64 = WithdrawalAllowlist::CHANGE_DELAY_SECS as i64;
+
+
+impl TestEnv {
+    async fn set_allowlist(&mut self, destinations: &[Pubkey]) {
+        let instruction = client::set_withdrawal_allowlist(&pricelocker::ID, &self.authority.pubkey(), LOCKER_NAME, destinations);
+        self.process(&[instruction], &[]).await.unwrap();
+    }
+
+    async fn withdraw_to(&mut self, amount: u32, destination: &Pubkey) -> std::result::Result<(), solana_program_test::BanksClientError> {
+        let instruction = client::withdraw_to_destination(&pricelocker::ID, &self.authority.pubkey(), LOCKER_NAME, amount, None, destination);
+        self.process(&[instruction], &[]).await
+    }
+}
+
+
+#[tokio::test]
+async fn withdrawals_are_restricted_to_the_allowlist_after_the_delay() {
+    let mut env = TestEnv::start().await;
+    env.create_locker(5_000_000).await;
+    let savings = Pubkey::new_unique();
+    let thief = Pubkey::new_unique();
+
+    env.set_allowlist(&[savings]).await;
+    let locker = env.fetch_locker().await;
+    assert!(locker.withdrawal_allowlist.destinations.is_empty());
+    assert_eq!(locker.withdrawal_allowlist.pending.unwrap().active_ts, START_TS as u32 + CHANGE_DELAY as u32);
+
+    // Unrestricted until the allowlist takes effect
+    env.withdraw_to(1_000_000, &thief).await.unwrap();
+
+    env.set_time(START_TS + CHANGE_DELAY).await;
+    let result = env.withdraw_to(1_000_000, &thief).await;
+    assert_error(result, LockerErrorCode::DestinationNotAllowed);
+
+    // Without a destination the funds go to the authority, which is not on the allowlist either
+    let instruction = client::withdraw_unlocked_funds(&pricelocker::ID, &env.authority.pubkey(), LOCKER_NAME, 1_000_000, None);
+    let result = env.process(&[instruction], &[]).await;
+    assert_error(result, LockerErrorCode::DestinationNotAllowed);
+
+    env.withdraw_to(1_000_000, &savings).await.unwrap();
+    assert_eq!(env.lamports(&savings).await, 1_000_000);
+}
+
+#[tokio::test]
+async fn pending_allowlist_change_can_be_cancelled() {
+    let mut env = TestEnv::start().await;
+    env.create_locker(5_000_000).await;
+    let savings = Pubkey::new_unique();
+    let thief = Pubkey::new_unique();
+    env.set_allowlist(&[savings]).await;
+    env.set_time(START_TS + CHANGE_DELAY).await;
+
+    // A compromised key proposes its own destination, the owner cancels it within the delay
+    env.set_allowlist(&[thief]).await;
+    env.set_time(START_TS + 2 * CHANGE_DELAY - 1).await;
+    let result = env.withdraw_to(1_000_000, &thief).await;
+    assert_error(result, LockerErrorCode::DestinationNotAllowed);
+
+    let instruction = client::cancel_withdrawal_allowlist_change(&pricelocker::ID, &env.authority.pubkey(), LOCKER_NAME);
+    env.process(std::slice::from_ref(&instruction), &[]).await.unwrap();
+    assert_eq!(env.fetch_locker().await.withdrawal_allowlist.destinations, vec![savings]);
+
+    env.set_time(START_TS + 3 * CHANGE_DELAY).await;
+    let result = env.withdraw_to(1_000_000, &thief).await;
+    assert_error(result, LockerErrorCode::DestinationNotAllowed);
+
+    let result = env.process(&[instruction], &[]).await;
+    assert_error(result, LockerErrorCode::NoPendingAllowlistChange);
+}
+
+#[tokio::test]
+async fn payouts_of_new_locks_and_guardians_are_restricted_to_the_allowlist() {
+    let mut env = TestEnv::start().await;
+    env.create_locker(0).await;
+    let savings = Pubkey::new_unique();
+    let thief = Pubkey::new_unique();
+    env.set_allowlist(&[savings]).await;
+    env.set_time(START_TS + CHANGE_DELAY).await;
+
+    let conditions = [LockCondition::Time { strike_time: START_TS as u32 + CHANGE_DELAY as u32 + 60 }];
+    let instruction = client::deposit_and_lock(&pricelocker::ID, &env.authority.pubkey(), LOCKER_NAME, 100_000, None, None, &conditions, Some(thief));
+    let result = env.process(&[instruction], &[]).await;
+    assert_error(result, LockerErrorCode::DestinationNotAllowed);
+
+    let conditions = [LockCondition::TimeWithEarlyExit { strike_time: START_TS as u32 + CHANGE_DELAY as u32 + 60, max_penalty_bps: 10_000, penalty_destination: thief }];
+    let instruction = client::deposit_and_lock(&pricelocker::ID, &env.authority.pubkey(), LOCKER_NAME, 100_000, None, None, &conditions, None);
+    let result = env.process(&[instruction], &[]).await;
+    assert_error(result, LockerErrorCode::DestinationNotAllowed);
+
+    let instruction = client::set_guardians(&pricelocker::ID, &env.authority.pubkey(), LOCKER_NAME, &[Pubkey::new_unique()], 1, 10_000, &thief);
+    let result = env.process(&[instruction], &[]).await;
+    assert_error(result, LockerErrorCode::DestinationNotAllowed);
+
+    let conditions = [LockCondition::Time { strike_time: START_TS as u32 + CHANGE_DELAY as u32 + 60 }];
+    let instruction = client::deposit_and_lock(&pricelocker::ID, &env.authority.pubkey(), LOCKER_NAME, 100_000, None, None, &conditions, Some(savings));
+    env.process(&[instruction], &[]).await.unwrap();
+}
+
+#[tokio::test]
+async fn penalties_are_restricted_to_the_allowlist_in_effect_at_payout() {
+    let mut env = TestEnv::start().await;
+    env.create_locker(0).await;
+    let savings = Pubkey::new_unique();
+    let thief = Pubkey::new_unique();
+    let guardian = Keypair::new();
+    let strike_time = START_TS as u32 + CHANGE_DELAY as u32 + 60;
+
+    // The penalty destinations are set before the allowlist takes effect
+    let instruction = client::set_guardians(&pricelocker::ID, &env.authority.pubkey(), LOCKER_NAME, &[guardian.pubkey()], 1, 10_000, &thief);
+    env.process(&[instruction], &[]).await.unwrap();
+    let conditions = [LockCondition::TimeWithEarlyExit { strike_time, max_penalty_bps: 10_000, penalty_destination: thief }];
+    let instruction = client::deposit_and_lock(&pricelocker::ID, &env.authority.pubkey(), LOCKER_NAME, 1_000_000, None, None, &conditions, None);
+    env.process(&[instruction], &[]).await.unwrap();
+    env.set_allowlist(&[savings]).await;
+    env.set_time(START_TS + CHANGE_DELAY).await;
+
+    let instruction = client::exit_lock_early(&pricelocker::ID, &env.authority.pubkey(), LOCKER_NAME, 0, &thief, None);
+    let result = env.process(&[instruction], &[]).await;
+    assert_error(result, LockerErrorCode::DestinationNotAllowed);
+
+    let instruction = client::guardian_release(&pricelocker::ID, &env.authority.pubkey(), LOCKER_NAME, 0, &[guardian.pubkey()], &thief, None);
+    let result = env.process(&[instruction], &[&guardian]).await;
+    assert_error(result, LockerErrorCode::DestinationNotAllowed);
+    assert!(env.fetch_locker().await.locks[0].is_locked());
+
+    // Without a penalty nothing goes to the destination, the lock can still be exited at its strike time
+    env.set_time(strike_time as i64).await;
+    let instruction = client::exit_lock_early(&pricelocker::ID, &env.authority.pubkey(), LOCKER_NAME, 0, &thief, None);
+    env.process(&[instruction], &[]).await.unwrap();
+    assert_eq!(env.lamports(&thief).await, 0);
+}
+
+#[tokio::test]
+async fn beneficiaries_claim_the_funds_promised_before_the_allowlist() {
+    let mut env = TestEnv::start().await;
+    env.create_locker(0).await;
+    let savings = Pubkey::new_unique();
+    let beneficiary = Keypair::new();
+    let strike_time = START_TS as u32 + CHANGE_DELAY as u32 + 60;
+
+    let conditions = [LockCondition::Time { strike_time }];
+    let instruction = client::deposit_and_lock(&pricelocker::ID, &env.authority.pubkey(), LOCKER_NAME, 1_000_000, None, None, &conditions, Some(beneficiary.pubkey()));
+    env.process(&[instruction], &[]).await.unwrap();
+    env.set_allowlist(&[savings]).await;
+
+    // The allowlist of the authority restricts its own withdrawals, not the claims of funds promised to a beneficiary
+    env.set_time(strike_time as i64).await;
+    let instruction = client::time_unlock_funds(&pricelocker::ID, &env.authority.pubkey(), LOCKER_NAME, 0);
+    env.process(&[instruction], &[]).await.unwrap();
+    let instruction = client::claim_beneficiary_funds(&pricelocker::ID, &beneficiary.pubkey(), &env.authority.pubkey(), LOCKER_NAME, 0, WITHDRAW_MAX, None);
+    env.process(&[instruction], &[&beneficiary]).await.unwrap();
+    assert_eq!(env.lamports(&beneficiary.pubkey()).await, 1_000_000);
+}
@@ -0,0 +1,122 @@
+mod common;
+
+use anchor_spl::token::spl_token;
+use common::*;
+use pricelocker::{LockCondition, LockerErrorCode, WITHDRAW_MAX};
+use pricelocker_client as client;
+use pricelocker_client::TokenAsset;
+use solana_sdk::signature::{Keypair, Signer};
+
+
+impl TestEnv {
+    async fn claim(&mut self, beneficiary: &Keypair, lock_id: u8, amount: u32, token: Option<&TokenAsset>) -> std::result::Result<(), solana_program_test::BanksClientError> {
+        let instruction = client::claim_beneficiary_funds(&pricelocker::ID, &beneficiary.pubkey(), &self.authority.pubkey(), LOCKER_NAME, lock_id, amount, token);
+        self.process(&[instruction], &[beneficiary]).await
+    }
+
+    async fn withdraw(&mut self, amount: u32) -> std::result::Result<(), solana_program_test::BanksClientError> {
+        let instruction = client::withdraw_unlocked_funds(&pricelocker::ID, &self.authority.pubkey(), LOCKER_NAME, amount, None);
+        self.process(&[instruction], &[]).await
+    }
+}
+
+
+#[tokio::test]
+async fn only_the_beneficiary_claims_the_unlocked_funds() {
+    let mut env = TestEnv::start().await;
+    env.create_locker(0).await;
+    let beneficiary = env.fund_new_wallet(1_000_000_000).await;
+
+    let conditions = [LockCondition::Time { strike_time: START_TS as u32 + 60 }];
+    let instructions = [
+        client::deposit_and_lock(&pricelocker::ID, &env.authority.pubkey(), LOCKER_NAME, 600_000, None, None, &conditions, Some(beneficiary.pubkey())),
+        client::deposit_funds(&pricelocker::ID, &env.authority.pubkey(), LOCKER_NAME, 400_000, None, None),
+    ];
+    env.process(&instructions, &[]).await.unwrap();
+
+    let result = env.claim(&beneficiary, 0, WITHDRAW_MAX, None).await;
+    assert_error(result, LockerErrorCode::LockNotUnlocked);
+
+    // Once unlocked, the funds stay reserved for the beneficiary
+    env.set_time(START_TS + 60).await;
+    let instruction = client::time_unlock_funds(&pricelocker::ID, &env.authority.pubkey(), LOCKER_NAME, 0);
+    env.process(&[instruction], &[]).await.unwrap();
+
+    let result = env.withdraw(400_001).await;
+    assert_error(result, LockerErrorCode::PayoutAmountExceedsAvailableBalance);
+    env.withdraw(WITHDRAW_MAX).await.unwrap();
+
+    let other = env.fund_new_wallet(1_000_000_000).await;
+    let result = env.claim(&other, 0, WITHDRAW_MAX, None).await;
+    assert_error(result, LockerErrorCode::NotTheBeneficiary);
+
+    // The beneficiary can claim in parts
+    let lamports_before = env.lamports(&beneficiary.pubkey()).await;
+    env.claim(&beneficiary, 0, 200_000, None).await.unwrap();
+    let result = env.claim(&beneficiary, 0, 400_001, None).await;
+    assert_error(result, LockerErrorCode::PayoutAmountExceedsAvailableBalance);
+    env.claim(&beneficiary, 0, WITHDRAW_MAX, None).await.unwrap();
+
+    // The authority pays the transaction fees
+    assert_eq!(env.lamports(&beneficiary.pubkey()).await, lamports_before + 600_000);
+    let locker = env.fetch_locker().await;
+    assert_eq!(locker.locks[0].amount(), 0);
+    assert_eq!(locker.locked_balance, 0);
+    assert!(locker.lots.is_empty());
+
+    let result = env.claim(&beneficiary, 0, WITHDRAW_MAX, None).await;
+    assert_error(result, LockerErrorCode::PayoutAmountNotPositive);
+}
+
+#[tokio::test]
+async fn beneficiary_claims_tokens_to_a_new_token_account() {
+    let mut env = TestEnv::start().await;
+    env.create_locker(0).await;
+    let beneficiary = env.fund_new_wallet(1_000_000_000).await;
+    let token = env.create_mint(&spl_token::ID, &[], |_| vec![]).await;
+    env.mint_to_authority(&token, 1_000_000).await;
+
+    // Joined conditions release the deposit to the beneficiary when either is met
+    let conditions = [LockCondition::Time { strike_time: START_TS as u32 + 60 }, LockCondition::Price { strike_price: 100, price_feed: price_feed_address() }];
+    let instruction = client::deposit_and_lock(&pricelocker::ID, &env.authority.pubkey(), LOCKER_NAME, 250_000, Some(&token), None, &conditions, Some(beneficiary.pubkey()));
+    env.process(&[instruction], &[]).await.unwrap();
+
+    env.set_time(START_TS + 60).await;
+    let instruction = client::time_unlock_funds(&pricelocker::ID, &env.authority.pubkey(), LOCKER_NAME, 0);
+    env.process(&[instruction], &[]).await.unwrap();
+
+    // The price lock is still locked, claiming through it claims the same funds
+    env.claim(&beneficiary, 1, WITHDRAW_MAX, Some(&token)).await.unwrap();
+
+    let beneficiary_token_address = client::wallet_token_address(&beneficiary.pubkey(), &token);
+    assert_eq!(env.token_balance(&beneficiary_token_address).await, 250_000);
+    let locker_token_address = client::locker_token_address(&env.locker_address(), &token);
+    assert_eq!(env.token_balance(&locker_token_address).await, 0);
+    assert!(env.fetch_locker().await.locks.iter().all(|lock_item| lock_item.amount() == 0));
+}
+
+#[tokio::test]
+async fn authority_can_not_redirect_the_funds_of_a_beneficiary() {
+    let mut env = TestEnv::start().await;
+    env.create_locker(0).await;
+    let beneficiary = env.fund_new_wallet(1_000_000_000).await;
+
+    let conditions = [LockCondition::Time { strike_time: START_TS as u32 + 3_600 }];
+    let instruction = client::deposit_and_lock(&pricelocker::ID, &env.authority.pubkey(), LOCKER_NAME, 500_000, None, None, &conditions, Some(beneficiary.pubkey()));
+    env.process(&[instruction], &[]).await.unwrap();
+
+    // A lock joined to the lock of the beneficiary would release the funds to the authority
+    let instruction = client::time_lock_funds(&pricelocker::ID, &env.authority.pubkey(), LOCKER_NAME, START_TS as u32 + 60, 500_000, None, Some(0));
+    let result = env.process(&[instruction], &[]).await;
+    assert_error(result, LockerErrorCode::InvalidJoin);
+
+    // The authority can't claim the funds of the beneficiary, nor claim a lock without a beneficiary
+    let authority = env.authority.insecure_clone();
+    let result = env.claim(&authority, 0, WITHDRAW_MAX, None).await;
+    assert_error(result, LockerErrorCode::NotTheBeneficiary);
+
+    let instruction = client::deposit_and_lock(&pricelocker::ID, &env.authority.pubkey(), LOCKER_NAME, 100_000, None, None, &conditions, None);
+    env.process(&[instruction], &[]).await.unwrap();
+    let result = env.claim(&beneficiary, 1, WITHDRAW_MAX, None).await;
+    assert_error(result, LockerErrorCode::NotTheBeneficiary);
+}
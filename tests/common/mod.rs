@@ -0,0 +1,379 @@
+// Shared setup of the integration tests
+// The program runs natively in solana-program-test, with synthetic Pyth price accounts and a controllable clock
+
+#![allow(dead_code)]
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::bpf_loader_upgradeable;
+use anchor_lang::solana_program::entrypoint::ProgramResult;
+use anchor_lang::solana_program::program_pack::Pack;
+use anchor_spl::associated_token;
+use anchor_spl::token::spl_token;
+use anchor_spl::token_2022::spl_token_2022;
+use anchor_spl::token_2022::spl_token_2022::extension::{BaseStateWithExtensions, ExtensionType, StateWithExtensions};
+use pricelocker::{Fees, Locker, PriceFeed};
+use pricelocker_client as client;
+use pricelocker_client::{PriceFeedAccounts, TokenAsset};
+use pyth_sdk_solana::state::{AccountType, PriceAccount, PriceStatus, MAGIC, VERSION_2};
+use solana_program_test::{processor, BanksClientError, ProgramTest, ProgramTestContext};
+use solana_sdk::account::Account as SolanaAccount;
+use solana_sdk::instruction::{AccountMeta, Instruction, InstructionError};
+use solana_sdk::signature::{Keypair, Signer};
+use solana_sdk::transaction::{Transaction, TransactionError};
+use std::panic::AssertUnwindSafe;
+
+#[cfg(feature = "localnet")]
+mod oracle;
+#[cfg(feature = "localnet")]
+pub use oracle::*;
+
+
+// Start of the clock in every test, 2024-01-01
+pub const START_TS: i64 = 1_704_067_200;
+
+pub const LOCKER_NAME: &str = "savings";
+pub const PRICEFEED_ALIAS: &str = "SOL/USD";
+
+
+// Custom error code reported when the program panics, instead of returning an error
+pub const PROGRAM_PANICKED: u32 = u32::MAX;
+
+
+// Anchor generates an entrypoint which requires the accounts to live as long as the account infos,
+//  the accounts are leaked to satisfy that lifetime in the native test processor
+// A panic in a native program would take down the test validator, so it fails the instruction like on chain
+fn process_instruction(program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let accounts = Box::leak(accounts.to_vec().into_boxed_slice());
+    std::panic::catch_unwind(AssertUnwindSafe(|| pricelocker::entry(program_id, accounts, data)))
+        .unwrap_or(Err(ProgramError::Custom(PROGRAM_PANICKED)))
+}
+
+#[cfg(feature = "localnet")]
+fn process_mock_oracle_instruction(program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let accounts = Box::leak(accounts.to_vec().into_boxed_slice());
+    mock_oracle::entry(program_id, accounts, data)
+}
+
+
+// Price written into a synthetic Pyth price account
+#[derive(Clone, Copy, Debug)]
+pub struct MockPrice {
+    pub price: i64,
+    pub conf: u64,
+    pub expo: i32,
+    pub status: PriceStatus,
+    pub publish_time: i64,
+    // EMA price of the oracle, the reference of the circuit breaker at the start of a window
+    pub ema_price: i64,
+}
+
+impl MockPrice {
+    pub fn trading(price: i64, publish_time: i64) -> Self {
+        MockPrice {
+            price,
+            conf: 1,
+            expo: -8,
+            status: PriceStatus::Trading,
+            publish_time,
+            ema_price: price,
+        }
+    }
+}
+
+
+pub struct TestEnv {
+    pub context: ProgramTestContext,
+    pub authority: Keypair,
+}
+
+impl TestEnv {
+    pub async fn start() -> Self {
+        #[allow(unused_mut)]
+        let mut program_test = ProgramTest::new("pricelocker", pricelocker::ID, processor!(process_instruction));
+        #[cfg(feature = "localnet")]
+        program_test.add_program("mock_oracle", mock_oracle::ID, processor!(process_mock_oracle_instruction));
+        let context = program_test.start_with_context().await;
+        let authority = context.payer.insecure_clone();
+
+        let mut env = TestEnv { context, authority };
+        env.set_time(START_TS).await;
+        env
+    }
+
+    pub fn locker_address(&self) -> Pubkey {
+        self.named_locker_address(LOCKER_NAME)
+    }
+
+    pub fn named_locker_address(&self, locker_name: &str) -> Pubkey {
+        client::find_locker_address(&pricelocker::ID, &self.authority.pubkey(), locker_name).0
+    }
+
+    // Sign with the payer (the authority) and the given signers, and process the instructions in one transaction
+    pub async fn process(&mut self, instructions: &[Instruction], signers: &[&Keypair]) -> std::result::Result<(), BanksClientError> {
+        let blockhash = self.context.get_new_latest_blockhash().await.unwrap();
+        let mut all_signers = vec![&self.context.payer];
+        all_signers.extend_from_slice(signers);
+
+        let transaction = Transaction::new_signed_with_payer(instructions, Some(&self.context.payer.pubkey()), &all_signers, blockhash);
+        self.context.banks_client.process_transaction(transaction).await
+    }
+
+    // Simulate a view instruction and decode its return data
+    pub async fn simulate<T: AnchorDeserialize>(&mut self, instruction: Instruction) -> std::result::Result<T, BanksClientError> {
+        let blockhash = self.context.get_new_latest_blockhash().await.unwrap();
+        let transaction = Transaction::new_signed_with_payer(&[instruction], Some(&self.context.payer.pubkey()), &[&self.context.payer], blockhash);
+
+        let simulation = self.context.banks_client.simulate_transaction(transaction).await?;
+        simulation.result.unwrap()?;
+
+        let return_data = simulation.simulation_details.unwrap().return_data.expect("no return data");
+        Ok(client::decode_return_data(&return_data.data).unwrap())
+    }
+
+    pub async fn set_time(&mut self, unix_timestamp: i64) {
+        let mut clock: Clock = self.context.banks_client.get_sysvar().await.unwrap();
+        clock.unix_timestamp = unix_timestamp;
+        self.context.set_sysvar(&clock);
+    }
+
+    pub async fn now(&mut self) -> u32 {
+        let clock: Clock = self.context.banks_client.get_sysvar().await.unwrap();
+        clock.unix_timestamp as u32
+    }
+
+    pub async fn get_account(&mut self, address: &Pubkey) -> Option<SolanaAccount> {
+        self.context.banks_client.get_account(*address).await.unwrap()
+    }
+
+    pub async fn fetch_locker(&mut self) -> Locker {
+        self.fetch_named_locker(LOCKER_NAME).await
+    }
+
+    pub async fn fetch_named_locker(&mut self, locker_name: &str) -> Locker {
+        let address = self.named_locker_address(locker_name);
+        let account = self.get_account(&address).await.expect("locker not found");
+        client::decode_locker(&account.data).unwrap()
+    }
+
+    pub async fn lamports(&mut self, address: &Pubkey) -> u64 {
+        self.get_account(address).await.map_or(0, |account| account.lamports)
+    }
+
+
+    // A new wallet with SOL from the authority, e.g. a beneficiary, a guardian or another signer
+    pub async fn fund_new_wallet(&mut self, lamports: u64) -> Keypair {
+        let wallet = Keypair::new();
+        let instruction = anchor_lang::solana_program::system_instruction::transfer(&self.authority.pubkey(), &wallet.pubkey(), lamports);
+        self.process(&[instruction], &[]).await.unwrap();
+        wallet
+    }
+
+    // Create the locker of the authority and deposit SOL into it
+    pub async fn create_locker(&mut self, deposit: u32) {
+        self.create_named_locker(LOCKER_NAME, deposit).await
+    }
+
+    pub async fn create_named_locker(&mut self, locker_name: &str, deposit: u32) {
+        let authority = self.authority.pubkey();
+        let mut instructions = vec![client::create_new_locker(&pricelocker::ID, &authority, locker_name)];
+        if deposit > 0 {
+            instructions.push(client::deposit_funds(&pricelocker::ID, &authority, locker_name, deposit, None, None));
+        }
+        self.process(&instructions, &[]).await.unwrap();
+    }
+
+    // Add a SOL time lock to the locker
+    pub async fn time_lock(&mut self, locker_name: &str, strike_time: i64, amount: u32) -> std::result::Result<(), BanksClientError> {
+        let instruction = client::time_lock_funds(&pricelocker::ID, &self.authority.pubkey(), locker_name, strike_time as u32, amount, None, None);
+        self.process(&[instruction], &[]).await
+    }
+
+    // Add a SOL price lock to the locker, on the price feed created by create_price_feed
+    pub async fn price_lock(&mut self, locker_name: &str, strike_price: u32, amount: u32) -> std::result::Result<(), BanksClientError> {
+        let instruction = client::price_lock_funds(&pricelocker::ID, &self.authority.pubkey(), locker_name, &price_feed_address(), strike_price, amount, None, None);
+        self.process(&[instruction], &[]).await
+    }
+
+    // Create the program config with the authority as the admin, and as the upgrade authority of the program
+    pub async fn init_config(&mut self, fees: Fees) -> std::result::Result<(), BanksClientError> {
+        let authority = self.authority.pubkey();
+        self.set_upgrade_authority(Some(&authority));
+        let instruction = client::init_config(&pricelocker::ID, &self.authority.pubkey(), fees);
+        self.process(&[instruction], &[]).await
+    }
+
+    // Write the program data account of the deployment, the program runs natively without one
+    // Layout of the upgradeable loader state: the ProgramData variant (3), the deployment slot and the upgrade authority
+    pub fn set_upgrade_authority(&mut self, upgrade_authority: Option<&Pubkey>) {
+        let mut data = 3u32.to_le_bytes().to_vec();
+        data.extend(0u64.to_le_bytes());
+        match upgrade_authority {
+            Some(upgrade_authority) => {
+                data.push(1);
+                data.extend(upgrade_authority.to_bytes());
+            },
+            None => data.push(0),
+        }
+        let program_data = client::find_program_data_address(&pricelocker::ID).0;
+        self.set_account(&program_data, data, bpf_loader_upgradeable::ID);
+    }
+
+    // Write a Pyth price account owned by the oracle, and register it as a price feed
+    pub async fn create_price_feed(&mut self, price: i64, publish_time: i64) -> PriceFeedAccounts {
+        let pyth_price_account = Pubkey::new_unique();
+        self.set_pyth_price(&pyth_price_account, MockPrice::trading(price, publish_time));
+
+        let instruction = client::create_pricefeed_account(&pricelocker::ID, &self.authority.pubkey(), PRICEFEED_ALIAS, &pyth_price_account);
+        self.process(&[instruction], &[]).await.unwrap();
+
+        PriceFeedAccounts {
+            price_feed: price_feed_address(),
+            pyth_price_account,
+        }
+    }
+
+    // Write a synthetic Pyth price account, with the price in the aggregate and as previous price, and the EMA price
+    pub fn set_pyth_price(&mut self, address: &Pubkey, mock_price: MockPrice) {
+        let mut price_account: PriceAccount = bytemuck::Zeroable::zeroed();
+        price_account.magic = MAGIC;
+        price_account.ver = VERSION_2;
+        price_account.atype = AccountType::Price as u32;
+        price_account.expo = mock_price.expo;
+        price_account.timestamp = mock_price.publish_time;
+        price_account.prev_timestamp = mock_price.publish_time;
+        price_account.prev_price = mock_price.price;
+        price_account.prev_conf = mock_price.conf;
+        price_account.agg.price = mock_price.price;
+        price_account.agg.conf = mock_price.conf;
+        price_account.agg.status = mock_price.status;
+        price_account.ema_price.val = mock_price.ema_price;
+        price_account.ema_conf.val = mock_price.conf as i64;
+
+        let data = bytemuck::bytes_of(&price_account).to_vec();
+        self.set_account(address, data, PriceFeed::owner());
+    }
+
+    // Create a mint of the token program with the authority as mint authority and 6 decimals
+    // The instructions initializing the extensions of a Token-2022 mint run before the mint is initialized
+    pub async fn create_mint(&mut self, token_program: &Pubkey, extension_types: &[ExtensionType], extension_instructions: impl FnOnce(&Pubkey) -> Vec<Instruction>) -> TokenAsset {
+        self.create_mint_with_decimals(token_program, 6, extension_types, extension_instructions).await
+    }
+
+    // An NFT is a mint without decimals
+    pub async fn create_nft(&mut self, token_program: &Pubkey) -> TokenAsset {
+        self.create_mint_with_decimals(token_program, 0, &[], |_| vec![]).await
+    }
+
+    pub async fn create_mint_with_decimals(&mut self, token_program: &Pubkey, decimals: u8, extension_types: &[ExtensionType], extension_instructions: impl FnOnce(&Pubkey) -> Vec<Instruction>) -> TokenAsset {
+        let mint = Keypair::new();
+        let authority = self.authority.pubkey();
+        let space = ExtensionType::try_calculate_account_len::<spl_token_2022::state::Mint>(extension_types).unwrap();
+        let rent: Rent = self.context.banks_client.get_sysvar().await.unwrap();
+
+        let mut instructions = vec![anchor_lang::solana_program::system_instruction::create_account(&authority, &mint.pubkey(), rent.minimum_balance(space), space as u64, token_program)];
+        instructions.extend(extension_instructions(&mint.pubkey()));
+        instructions.push(spl_token_2022::instruction::initialize_mint2(token_program, &mint.pubkey(), &authority, None, decimals).unwrap());
+        self.process(&instructions, &[&mint]).await.unwrap();
+
+        TokenAsset { mint: mint.pubkey(), token_program: *token_program }
+    }
+
+    // Create the associated token account of the authority and mint tokens into it
+    pub async fn mint_to_authority(&mut self, token: &TokenAsset, amount: u64) {
+        let authority = self.authority.pubkey();
+        let token_address = client::wallet_token_address(&authority, token);
+
+        // CreateIdempotent of the associated token program
+        let create_instruction = Instruction {
+            program_id: associated_token::ID,
+            accounts: vec![
+                AccountMeta::new(authority, true),
+                AccountMeta::new(token_address, false),
+                AccountMeta::new_readonly(authority, false),
+                AccountMeta::new_readonly(token.mint, false),
+                AccountMeta::new_readonly(anchor_lang::solana_program::system_program::ID, false),
+                AccountMeta::new_readonly(token.token_program, false),
+            ],
+            data: vec![1],
+        };
+        let mint_instruction = spl_token_2022::instruction::mint_to(&token.token_program, &token.mint, &token_address, &authority, &[], amount).unwrap();
+        self.process(&[create_instruction, mint_instruction], &[]).await.unwrap();
+    }
+
+    // Balance of a token account of either token program, 0 when it doesn't exist
+    pub async fn token_balance(&mut self, address: &Pubkey) -> u64 {
+        match self.get_account(address).await {
+            Some(account) => StateWithExtensions::<spl_token_2022::state::Account>::unpack(&account.data).unwrap().base.amount,
+            None => 0,
+        }
+    }
+
+    // Fees withheld in a Token-2022 token account by transfers into it
+    pub async fn withheld_fee(&mut self, address: &Pubkey) -> u64 {
+        let account = self.get_account(address).await.unwrap();
+        let token_account = StateWithExtensions::<spl_token_2022::state::Account>::unpack(&account.data).unwrap();
+        let transfer_fee_amount = token_account.get_extension::<spl_token_2022::extension::transfer_fee::TransferFeeAmount>().unwrap();
+        transfer_fee_amount.withheld_amount.into()
+    }
+
+    // Write an SPL token account with the given owner and balance
+    pub fn set_token_account(&mut self, address: &Pubkey, mint: &Pubkey, owner: &Pubkey, amount: u64) {
+        let token_account = spl_token::state::Account {
+            mint: *mint,
+            owner: *owner,
+            amount,
+            state: spl_token::state::AccountState::Initialized,
+            ..spl_token::state::Account::default()
+        };
+        let mut data = vec![0; spl_token::state::Account::LEN];
+        token_account.pack_into_slice(&mut data);
+        self.set_account(address, data, spl_token::ID);
+    }
+
+    // Write an initialized SPL token mint without mint authority
+    pub fn set_mint(&mut self, address: &Pubkey) {
+        let mint = spl_token::state::Mint {
+            decimals: 6,
+            is_initialized: true,
+            ..spl_token::state::Mint::default()
+        };
+        let mut data = vec![0; spl_token::state::Mint::LEN];
+        mint.pack_into_slice(&mut data);
+        self.set_account(address, data, spl_token::ID);
+    }
+
+    pub fn set_account(&mut self, address: &Pubkey, data: Vec<u8>, owner: Pubkey) {
+        let account = SolanaAccount {
+            lamports: 1_000_000_000,
+            data,
+            owner,
+            executable: false,
+            rent_epoch: 0,
+        };
+        self.context.set_account(address, &account.into());
+    }
+}
+
+
+// Address of the price feed account registered by create_price_feed
+pub fn price_feed_address() -> Pubkey {
+    client::find_pricefeed_address(&pricelocker::ID, PRICEFEED_ALIAS).0
+}
+
+// Assert that the first instruction failed with the given custom error code
+pub fn assert_error(result: std::result::Result<(), BanksClientError>, error: impl Into<u32>) {
+    assert_instruction_error(result, InstructionError::Custom(error.into()));
+}
+
+pub fn assert_instruction_error(result: std::result::Result<(), BanksClientError>, expected: InstructionError) {
+    match result {
+        Err(BanksClientError::TransactionError(TransactionError::InstructionError(_, error)))
+        | Err(BanksClientError::SimulationError { err: TransactionError::InstructionError(_, error), .. }) => assert_eq!(error, expected),
+        other => panic!("expected {expected:?}, got {other:?}"),
+    }
+}
+
+// Anchor error codes of the framework (e.g. a failed account constraint)
+pub fn anchor_error(error: anchor_lang::error::ErrorCode) -> u32 {
+    error.into()
+}
@@ -0,0 +1,66 @@
+// Price accounts of the mock oracle, which the program accepts instead of Pyth with the localnet feature
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::system_program;
+use anchor_lang::InstructionData;
+use pricelocker_client as client;
+use pricelocker_client::PriceFeedAccounts;
+use solana_sdk::signature::Signer;
+
+use super::{price_feed_address, TestEnv, PRICEFEED_ALIAS, START_TS};
+
+
+pub fn find_price_address(admin: &Pubkey, price_alias: &str) -> Pubkey {
+    Pubkey::find_program_address(&[b"price", admin.as_ref(), price_alias.as_bytes()], &mock_oracle::ID).0
+}
+
+pub fn create_price_account(admin: &Pubkey, price_alias: &str, price: i64, publish_time: i64) -> Instruction {
+    let accounts = mock_oracle::accounts::CreatePriceAccount {
+        admin: *admin,
+        price_account: find_price_address(admin, price_alias),
+        system_program: system_program::ID,
+    };
+    let data = mock_oracle::instruction::CreatePriceAccount {
+        price_alias: price_alias.to_string(),
+        price,
+        conf: 1,
+        expo: -8,
+        publish_time,
+    };
+    Instruction::new_with_bytes(mock_oracle::ID, &data.data(), accounts.to_account_metas(None))
+}
+
+pub fn set_price(admin: &Pubkey, price_alias: &str, price: i64, publish_time: i64) -> Instruction {
+    let accounts = mock_oracle::accounts::SetPrice {
+        admin: *admin,
+        price_account: find_price_address(admin, price_alias),
+    };
+    let data = mock_oracle::instruction::SetPrice {
+        _price_alias: price_alias.to_string(),
+        price,
+        conf: 1,
+        expo: -8,
+        publish_time,
+    };
+    Instruction::new_with_bytes(mock_oracle::ID, &data.data(), accounts.to_account_metas(None))
+}
+
+impl TestEnv {
+    // Create a price account in the mock oracle and register it as a price feed of the locker program
+    pub async fn create_mock_price_feed(&mut self, price: i64) -> PriceFeedAccounts {
+        let admin = self.authority.pubkey();
+        let pyth_price_account = find_price_address(&admin, PRICEFEED_ALIAS);
+
+        let instructions = [
+            create_price_account(&admin, PRICEFEED_ALIAS, price, START_TS),
+            client::create_pricefeed_account(&pricelocker::ID, &admin, PRICEFEED_ALIAS, &pyth_price_account),
+        ];
+        self.process(&instructions, &[]).await.unwrap();
+
+        PriceFeedAccounts {
+            price_feed: price_feed_address(),
+            pyth_price_account,
+        }
+    }
+}
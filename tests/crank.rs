@@ -0,0 +1,125 @@
+mod common;
+
+use common::*;
+use pricelocker::{CrankUnlocksResult, LockerErrorCode};
+use pricelocker_client as client;
+use pricelocker_client::{CrankLocker, PriceFeedAccounts};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signer;
+
+
+const OTHER_LOCKER_NAME: &str = "rainy-day";
+const OTHER_PRICEFEED_ALIAS: &str = "SOL/USD-2";
+
+
+#[tokio::test]
+async fn crank_unlocks_eligible_locks_over_many_lockers() {
+    let mut env = TestEnv::start().await;
+    env.create_locker(1_000_000).await;
+    env.create_named_locker(OTHER_LOCKER_NAME, 1_000_000).await;
+    let feed = env.create_price_feed(100, START_TS + 60).await;
+
+    env.time_lock(LOCKER_NAME, START_TS + 60, 300_000).await.unwrap();
+    env.time_lock(LOCKER_NAME, START_TS + 3_600, 200_000).await.unwrap();
+    env.price_lock(OTHER_LOCKER_NAME, 100, 500_000).await.unwrap();
+    env.price_lock(OTHER_LOCKER_NAME, 200, 100_000).await.unwrap();
+
+    env.set_time(START_TS + 60).await;
+    let lockers = [
+        CrankLocker { locker: env.locker_address(), price_feed: None },
+        CrankLocker { locker: env.named_locker_address(OTHER_LOCKER_NAME), price_feed: Some(feed) },
+    ];
+    let instruction = client::crank_unlocks(&pricelocker::ID, &env.authority.pubkey(), &lockers);
+
+    let results: Vec<CrankUnlocksResult> = env.simulate(instruction.clone()).await.unwrap();
+    assert_eq!(results, vec![
+        CrankUnlocksResult { locker: lockers[0].locker, unlocked: vec![0], skipped: 1 },
+        CrankUnlocksResult { locker: lockers[1].locker, unlocked: vec![0], skipped: 1 },
+    ]);
+
+    env.process(&[instruction], &[]).await.unwrap();
+
+    let locker = env.fetch_locker().await;
+    assert!(!locker.locks[0].is_locked());
+    assert!(locker.locks[1].is_locked());
+
+    let other_locker = env.fetch_named_locker(OTHER_LOCKER_NAME).await;
+    assert!(!other_locker.locks[0].is_locked());
+    assert!(other_locker.locks[1].is_locked());
+}
+
+#[tokio::test]
+async fn crank_skips_price_locks_on_stale_price() {
+    let mut env = TestEnv::start().await;
+    env.create_locker(1_000_000).await;
+    let feed = env.create_price_feed(100, START_TS).await;
+    env.price_lock(LOCKER_NAME, 100, 500_000).await.unwrap();
+
+    // The price was published more than a minute ago
+    env.set_time(START_TS + 120).await;
+    let lockers = [CrankLocker { locker: env.locker_address(), price_feed: Some(feed) }];
+    let instruction = client::crank_unlocks(&pricelocker::ID, &env.authority.pubkey(), &lockers);
+
+    let results: Vec<CrankUnlocksResult> = env.simulate(instruction.clone()).await.unwrap();
+    assert_eq!(results, vec![CrankUnlocksResult { locker: lockers[0].locker, unlocked: vec![], skipped: 1 }]);
+
+    env.process(&[instruction], &[]).await.unwrap();
+    assert!(env.fetch_locker().await.locks[0].is_locked());
+}
+
+#[tokio::test]
+async fn price_locks_only_unlock_at_the_price_of_their_price_feed() {
+    let mut env = TestEnv::start().await;
+    env.create_locker(1_000_000).await;
+    env.create_price_feed(90, START_TS).await;
+    env.price_lock(LOCKER_NAME, 100, 500_000).await.unwrap();
+
+    // Anyone can register another alias, here on a Pyth account with a higher price
+    let pyth_price_account = Pubkey::new_unique();
+    env.set_pyth_price(&pyth_price_account, MockPrice::trading(1_000, START_TS));
+    let instruction = client::create_pricefeed_account(&pricelocker::ID, &env.authority.pubkey(), OTHER_PRICEFEED_ALIAS, &pyth_price_account);
+    env.process(&[instruction], &[]).await.unwrap();
+    let other_feed = PriceFeedAccounts {
+        price_feed: client::find_pricefeed_address(&pricelocker::ID, OTHER_PRICEFEED_ALIAS).0,
+        pyth_price_account,
+    };
+
+    // The crank skips the price lock at the price of the other price feed
+    let lockers = [CrankLocker { locker: env.locker_address(), price_feed: Some(other_feed) }];
+    let instruction = client::crank_unlocks(&pricelocker::ID, &env.authority.pubkey(), &lockers);
+    let results: Vec<CrankUnlocksResult> = env.simulate(instruction.clone()).await.unwrap();
+    assert_eq!(results, vec![CrankUnlocksResult { locker: lockers[0].locker, unlocked: vec![], skipped: 1 }]);
+    env.process(&[instruction], &[]).await.unwrap();
+    assert!(env.fetch_locker().await.locks[0].is_locked());
+
+    // And the authority can't unlock it with the other price feed either
+    let instruction = client::price_unlock_funds(&pricelocker::ID, &env.authority.pubkey(), LOCKER_NAME, &other_feed, 0);
+    let result = env.process(&[instruction], &[]).await;
+    assert_error(result, LockerErrorCode::WrongPriceFeed);
+}
+
+#[tokio::test]
+async fn crank_rejects_incomplete_remaining_accounts() {
+    let mut env = TestEnv::start().await;
+    env.create_locker(1_000_000).await;
+
+    let lockers = [CrankLocker { locker: env.locker_address(), price_feed: None }];
+    let mut instruction = client::crank_unlocks(&pricelocker::ID, &env.authority.pubkey(), &lockers);
+    instruction.accounts.pop();
+
+    let result = env.process(&[instruction], &[]).await;
+    assert_error(result, LockerErrorCode::InvalidRemainingAccounts);
+}
+
+#[tokio::test]
+async fn crank_rejects_read_only_locker() {
+    let mut env = TestEnv::start().await;
+    env.create_locker(1_000_000).await;
+
+    let lockers = [CrankLocker { locker: env.locker_address(), price_feed: None }];
+    let mut instruction = client::crank_unlocks(&pricelocker::ID, &env.authority.pubkey(), &lockers);
+    instruction.accounts[2].is_writable = false;
+
+    let result = env.process(&[instruction], &[]).await;
+    assert_error(result, LockerErrorCode::InvalidRemainingAccounts);
+}
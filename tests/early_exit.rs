@@ -0,0 +1,119 @@
+mod common;
+
+use anchor_spl::token::spl_token;
+use common::*;
+use pricelocker::{LockCondition, LockerErrorCode, LockerStatus, WITHDRAW_MAX};
+use pricelocker_client as client;
+use pricelocker_client::TokenAsset;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signer;
+
+
+impl TestEnv {
+    async fn exit_early(&mut self, lock_id: u8, penalty_destination: &Pubkey, token: Option<&TokenAsset>) -> std::result::Result<(), solana_program_test::BanksClientError> {
+        let instruction = client::exit_lock_early(&pricelocker::ID, &self.authority.pubkey(), LOCKER_NAME, lock_id, penalty_destination, token);
+        self.process(&[instruction], &[]).await
+    }
+}
+
+
+#[tokio::test]
+async fn early_exit_penalty_decays_toward_the_strike_time() {
+    let mut env = TestEnv::start().await;
+    env.create_locker(0).await;
+    let treasury = env.fund_new_wallet(1_000_000_000).await;
+
+    // A penalty of 20% at the deposit, decaying to zero over 1000 seconds
+    let conditions = [LockCondition::TimeWithEarlyExit { strike_time: START_TS as u32 + 1_000, max_penalty_bps: 2_000, penalty_destination: treasury.pubkey() }];
+    let instruction = client::deposit_and_lock(&pricelocker::ID, &env.authority.pubkey(), LOCKER_NAME, 1_000_000, None, None, &conditions, None);
+    env.process(&[instruction], &[]).await.unwrap();
+
+    // A quarter of the lock period is left, so a quarter of the penalty
+    env.set_time(START_TS + 750).await;
+    let instruction = client::get_locker_status(&pricelocker::ID, &env.locker_address(), None, &[]);
+    let status: LockerStatus = env.simulate(instruction).await.unwrap();
+    assert_eq!(status.locks[0].early_exit_penalty_bps, Some(500));
+
+    let result = env.exit_early(0, &env.authority.pubkey(), None).await;
+    assert_error(result, LockerErrorCode::InvalidPenaltyDestination);
+
+    let treasury_before = env.lamports(&treasury.pubkey()).await;
+    let locker_before = env.lamports(&env.locker_address()).await;
+    env.exit_early(0, &treasury.pubkey(), None).await.unwrap();
+    assert_eq!(env.lamports(&treasury.pubkey()).await, treasury_before + 50_000);
+
+    let instruction = client::withdraw_unlocked_funds(&pricelocker::ID, &env.authority.pubkey(), LOCKER_NAME, WITHDRAW_MAX, None);
+    env.process(&[instruction], &[]).await.unwrap();
+    assert_eq!(env.lamports(&env.locker_address()).await, locker_before - 1_000_000);
+
+    let result = env.exit_early(0, &treasury.pubkey(), None).await;
+    assert_error(result, LockerErrorCode::LockAlreadyUnlocked);
+}
+
+#[tokio::test]
+async fn early_exit_at_the_start_forfeits_the_maximum_in_tokens() {
+    let mut env = TestEnv::start().await;
+    env.create_locker(0).await;
+    let charity = Pubkey::new_unique();
+    let token = env.create_mint(&spl_token::ID, &[], |_| vec![]).await;
+    env.mint_to_authority(&token, 1_000_000).await;
+
+    let conditions = [LockCondition::TimeWithEarlyExit { strike_time: START_TS as u32 + 3_600, max_penalty_bps: 1_000, penalty_destination: charity }];
+    let instruction = client::deposit_and_lock(&pricelocker::ID, &env.authority.pubkey(), LOCKER_NAME, 300_000, Some(&token), None, &conditions, None);
+    env.process(&[instruction], &[]).await.unwrap();
+
+    // The associated token account of the destination is created for the penalty
+    env.exit_early(0, &charity, Some(&token)).await.unwrap();
+    let charity_token_address = client::wallet_token_address(&charity, &token);
+    assert_eq!(env.token_balance(&charity_token_address).await, 30_000);
+
+    let instruction = client::withdraw_unlocked_funds(&pricelocker::ID, &env.authority.pubkey(), LOCKER_NAME, WITHDRAW_MAX, Some(&token));
+    env.process(&[instruction], &[]).await.unwrap();
+    let locker_token_address = client::locker_token_address(&env.locker_address(), &token);
+    assert_eq!(env.token_balance(&locker_token_address).await, 0);
+}
+
+#[tokio::test]
+async fn only_locks_with_an_early_exit_can_be_exited() {
+    let mut env = TestEnv::start().await;
+    env.create_locker(0).await;
+    let destination = Pubkey::new_unique();
+
+    let conditions = [LockCondition::TimeWithEarlyExit { strike_time: START_TS as u32 + 60, max_penalty_bps: 10_001, penalty_destination: destination }];
+    let instruction = client::deposit_and_lock(&pricelocker::ID, &env.authority.pubkey(), LOCKER_NAME, 100_000, None, None, &conditions, None);
+    let result = env.process(&[instruction], &[]).await;
+    assert_error(result, LockerErrorCode::InvalidEarlyExit);
+
+    // All-or-nothing time and price locks stay locked
+    let conditions = [LockCondition::Time { strike_time: START_TS as u32 + 60 }, LockCondition::Price { strike_price: 100, price_feed: price_feed_address() }];
+    let instruction = client::deposit_and_lock(&pricelocker::ID, &env.authority.pubkey(), LOCKER_NAME, 100_000, None, None, &conditions, None);
+    env.process(&[instruction], &[]).await.unwrap();
+
+    for lock_id in [0, 1] {
+        let result = env.exit_early(lock_id, &destination, None).await;
+        assert_error(result, LockerErrorCode::NoEarlyExit);
+    }
+    assert!(env.fetch_locker().await.locks.iter().all(|lock_item| lock_item.is_locked()));
+}
+
+#[tokio::test]
+async fn early_exit_can_not_take_the_funds_of_a_beneficiary_or_return_the_penalty() {
+    let mut env = TestEnv::start().await;
+    env.create_locker(0).await;
+    let beneficiary = Pubkey::new_unique();
+    let destination = Pubkey::new_unique();
+
+    // The authority could exit the lock early and withdraw the funds promised to the beneficiary
+    let conditions = [LockCondition::TimeWithEarlyExit { strike_time: START_TS as u32 + 60, max_penalty_bps: 1_000, penalty_destination: destination }];
+    let instruction = client::deposit_and_lock(&pricelocker::ID, &env.authority.pubkey(), LOCKER_NAME, 100_000, None, None, &conditions, Some(beneficiary));
+    let result = env.process(&[instruction], &[]).await;
+    assert_error(result, LockerErrorCode::InvalidEarlyExit);
+
+    // A penalty paid to the authority is no penalty
+    let conditions = [LockCondition::TimeWithEarlyExit { strike_time: START_TS as u32 + 60, max_penalty_bps: 1_000, penalty_destination: env.authority.pubkey() }];
+    let instruction = client::deposit_and_lock(&pricelocker::ID, &env.authority.pubkey(), LOCKER_NAME, 100_000, None, None, &conditions, None);
+    let result = env.process(&[instruction], &[]).await;
+    assert_error(result, LockerErrorCode::InvalidEarlyExit);
+
+    assert!(env.fetch_locker().await.locks.is_empty());
+}
@@ -0,0 +1,141 @@
+mod common;
+
+use anchor_spl::token::spl_token;
+use common::*;
+use pricelocker::{Fees, LockCondition, LockerErrorCode, LockerStatus};
+use pricelocker_client as client;
+use solana_sdk::instruction::AccountMeta;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signer};
+
+
+impl TestEnv {
+    fn treasury_address(&self) -> Pubkey {
+        client::find_treasury_address(&pricelocker::ID).0
+    }
+}
+
+
+#[tokio::test]
+async fn deposit_fee_is_collected_in_the_treasury() {
+    let mut env = TestEnv::start().await;
+    env.create_locker(0).await;
+    env.init_config(Fees { deposit_bps: 100, early_exit_bps: 0, staking_reward_bps: 0 }).await.unwrap();
+
+    let treasury = env.treasury_address();
+    let treasury_before = env.lamports(&treasury).await;
+    let instruction = client::deposit_funds(&pricelocker::ID, &env.authority.pubkey(), LOCKER_NAME, 1_000_000, None, None);
+    env.process(&[instruction], &[]).await.unwrap();
+
+    // The lot holds the deposit without the fee
+    assert_eq!(env.lamports(&treasury).await, treasury_before + 10_000);
+    assert_eq!(env.fetch_locker().await.lots[0].amount, 990_000);
+
+    let instruction = client::get_locker_status(&pricelocker::ID, &env.locker_address(), None, &[]);
+    let status: LockerStatus = env.simulate(instruction).await.unwrap();
+    assert_eq!(status.fees.deposit_bps, 100);
+    assert_eq!(status.assets[0].withdrawable, 990_000);
+
+    // Only the admin withdraws, and never more than was collected
+    let other = Keypair::new();
+    let instruction = client::withdraw_fees(&pricelocker::ID, &other.pubkey(), 10_000, None);
+    let result = env.process(&[instruction], &[&other]).await;
+    assert_error(result, LockerErrorCode::NotTheAdmin);
+
+    let instruction = client::withdraw_fees(&pricelocker::ID, &env.authority.pubkey(), 10_001, None);
+    let result = env.process(&[instruction], &[]).await;
+    assert_error(result, LockerErrorCode::PayoutAmountExceedsAvailableBalance);
+
+    let instruction = client::withdraw_fees(&pricelocker::ID, &env.authority.pubkey(), 10_000, None);
+    env.process(&[instruction], &[]).await.unwrap();
+    assert_eq!(env.lamports(&treasury).await, treasury_before);
+}
+
+#[tokio::test]
+async fn early_exit_fee_is_charged_on_top_of_the_penalty() {
+    let mut env = TestEnv::start().await;
+    env.create_locker(0).await;
+    env.init_config(Fees { deposit_bps: 200, early_exit_bps: 300, staking_reward_bps: 0 }).await.unwrap();
+    let charity = Pubkey::new_unique();
+    let token = env.create_mint(&spl_token::ID, &[], |_| vec![]).await;
+    env.mint_to_authority(&token, 1_000_000).await;
+
+    // 2% of the deposit is a fee, the remaining 98_000 are locked
+    let conditions = [LockCondition::TimeWithEarlyExit { strike_time: START_TS as u32 + 3_600, max_penalty_bps: 1_000, penalty_destination: charity }];
+    let instruction = client::deposit_and_lock(&pricelocker::ID, &env.authority.pubkey(), LOCKER_NAME, 100_000, Some(&token), None, &conditions, None);
+    env.process(&[instruction], &[]).await.unwrap();
+    assert_eq!(env.fetch_locker().await.locks[0].amount(), 98_000);
+
+    let instruction = client::exit_lock_early(&pricelocker::ID, &env.authority.pubkey(), LOCKER_NAME, 0, &charity, Some(&token));
+    env.process(&[instruction], &[]).await.unwrap();
+
+    let charity_token_address = client::wallet_token_address(&charity, &token);
+    assert_eq!(env.token_balance(&charity_token_address).await, 9_800);
+    let treasury_token_address = client::wallet_token_address(&env.treasury_address(), &token);
+    assert_eq!(env.token_balance(&treasury_token_address).await, 2_000 + 2_940);
+    let locker_token_address = client::locker_token_address(&env.locker_address(), &token);
+    assert_eq!(env.token_balance(&locker_token_address).await, 98_000 - 9_800 - 2_940);
+
+    // The token account of the treasury without the mint is rejected, instead of paying out SOL
+    let mut instruction = client::withdraw_fees(&pricelocker::ID, &env.authority.pubkey(), 4_940, Some(&token));
+    instruction.accounts[4] = AccountMeta::new_readonly(pricelocker::ID, false);
+    let result = env.process(&[instruction], &[]).await;
+    assert_error(result, LockerErrorCode::MissingTokenAccounts);
+
+    // The admin withdraws the tokens to its own token account
+    let authority_token_address = client::wallet_token_address(&env.authority.pubkey(), &token);
+    let authority_before = env.token_balance(&authority_token_address).await;
+    let instruction = client::withdraw_fees(&pricelocker::ID, &env.authority.pubkey(), 4_940, Some(&token));
+    env.process(&[instruction], &[]).await.unwrap();
+    assert_eq!(env.token_balance(&authority_token_address).await, authority_before + 4_940);
+    assert_eq!(env.token_balance(&treasury_token_address).await, 0);
+}
+
+#[tokio::test]
+async fn only_the_admin_updates_capped_fees() {
+    let mut env = TestEnv::start().await;
+    let fees = Fees { deposit_bps: 50, early_exit_bps: 50, staking_reward_bps: 1_000 };
+
+    let result = env.init_config(Fees { staking_reward_bps: 1_001, ..fees }).await;
+    assert_error(result, LockerErrorCode::InvalidFee);
+    env.init_config(fees).await.unwrap();
+
+    // The config exists once
+    let result = env.init_config(fees).await;
+    assert!(result.is_err());
+
+    let other = Keypair::new();
+    let instruction = client::update_config(&pricelocker::ID, &other.pubkey(), &other.pubkey(), Fees::default());
+    let result = env.process(&[instruction], &[&other]).await;
+    assert_error(result, LockerErrorCode::NotTheAdmin);
+
+    // Hand the config over, after which the old admin can't update it
+    let instruction = client::update_config(&pricelocker::ID, &env.authority.pubkey(), &other.pubkey(), Fees::default());
+    env.process(&[instruction], &[]).await.unwrap();
+    let instruction = client::update_config(&pricelocker::ID, &env.authority.pubkey(), &env.authority.pubkey(), fees);
+    let result = env.process(&[instruction], &[]).await;
+    assert_error(result, LockerErrorCode::NotTheAdmin);
+
+    let instruction = client::update_config(&pricelocker::ID, &other.pubkey(), &other.pubkey(), fees);
+    env.process(&[instruction], &[&other]).await.unwrap();
+}
+
+#[tokio::test]
+async fn only_the_upgrade_authority_initializes_the_config() {
+    let mut env = TestEnv::start().await;
+    let other = env.fund_new_wallet(1_000_000_000).await;
+
+    // Another signer can't take the admin role between the deployment and the initialization
+    env.set_upgrade_authority(Some(&env.authority.pubkey()));
+    let instruction = client::init_config(&pricelocker::ID, &other.pubkey(), Fees::default());
+    let result = env.process(&[instruction], &[&other]).await;
+    assert_error(result, LockerErrorCode::NotTheUpgradeAuthority);
+
+    // Nobody can once the program is immutable, the config is initialized before
+    env.set_upgrade_authority(None);
+    let instruction = client::init_config(&pricelocker::ID, &env.authority.pubkey(), Fees::default());
+    let result = env.process(&[instruction], &[]).await;
+    assert_error(result, LockerErrorCode::NotTheUpgradeAuthority);
+
+    env.init_config(Fees::default()).await.unwrap();
+}
@@ -0,0 +1,157 @@
+mod common;
+
+use anchor_spl::token::spl_token;
+use common::*;
+use pricelocker::{LockCondition, LockerErrorCode, WITHDRAW_MAX};
+use pricelocker_client as client;
+use pricelocker_client::TokenAsset;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signer};
+
+
+impl TestEnv {
+    async fn set_guardians(&mut self, guardians: &[Pubkey], threshold: u8, penalty_bps: u16, penalty_destination: &Pubkey) -> std::result::Result<(), solana_program_test::BanksClientError> {
+        let instruction = client::set_guardians(&pricelocker::ID, &self.authority.pubkey(), LOCKER_NAME, guardians, threshold, penalty_bps, penalty_destination);
+        self.process(&[instruction], &[]).await
+    }
+
+    async fn guardian_release(&mut self, lock_id: u8, guardians: &[&Keypair], penalty_destination: &Pubkey, token: Option<&TokenAsset>) -> std::result::Result<(), solana_program_test::BanksClientError> {
+        let guardian_keys: Vec<Pubkey> = guardians.iter().map(|guardian| guardian.pubkey()).collect();
+        let instruction = client::guardian_release(&pricelocker::ID, &self.authority.pubkey(), LOCKER_NAME, lock_id, &guardian_keys, penalty_destination, token);
+        self.process(&[instruction], guardians).await
+    }
+}
+
+
+#[tokio::test]
+async fn guardians_release_a_lock_early_with_a_penalty() {
+    let mut env = TestEnv::start().await;
+    env.create_locker(0).await;
+    let treasury = env.fund_new_wallet(1_000_000_000).await;
+    let guardians = [Keypair::new(), Keypair::new(), Keypair::new()];
+    let guardian_keys: Vec<Pubkey> = guardians.iter().map(|guardian| guardian.pubkey()).collect();
+
+    // 2 of 3 guardians, with a penalty of 10%
+    env.set_guardians(&guardian_keys, 2, 1_000, &treasury.pubkey()).await.unwrap();
+
+    let conditions = [LockCondition::Time { strike_time: START_TS as u32 + 3_600 }];
+    let instruction = client::deposit_and_lock(&pricelocker::ID, &env.authority.pubkey(), LOCKER_NAME, 500_000, None, None, &conditions, None);
+    env.process(&[instruction], &[]).await.unwrap();
+
+    let result = env.guardian_release(0, &[&guardians[0]], &treasury.pubkey(), None).await;
+    assert_error(result, LockerErrorCode::NotEnoughGuardianApprovals);
+
+    // Signers that aren't guardians, or the same guardian twice, don't count
+    let stranger = Keypair::new();
+    let result = env.guardian_release(0, &[&guardians[0], &stranger], &treasury.pubkey(), None).await;
+    assert_error(result, LockerErrorCode::NotEnoughGuardianApprovals);
+
+    let result = env.guardian_release(0, &[&guardians[0], &guardians[2]], &stranger.pubkey(), None).await;
+    assert_error(result, LockerErrorCode::InvalidPenaltyDestination);
+
+    let treasury_before = env.lamports(&treasury.pubkey()).await;
+    let locker_before = env.lamports(&env.locker_address()).await;
+    env.guardian_release(0, &[&guardians[0], &guardians[2]], &treasury.pubkey(), None).await.unwrap();
+
+    assert_eq!(env.lamports(&treasury.pubkey()).await, treasury_before + 50_000);
+    let locker = env.fetch_locker().await;
+    assert!(!locker.locks[0].is_locked());
+    assert_eq!(locker.locked_balance, 0);
+
+    // The authority withdraws the rest
+    let instruction = client::withdraw_unlocked_funds(&pricelocker::ID, &env.authority.pubkey(), LOCKER_NAME, WITHDRAW_MAX, None);
+    env.process(&[instruction], &[]).await.unwrap();
+    assert_eq!(env.lamports(&env.locker_address()).await, locker_before - 500_000);
+
+    let result = env.guardian_release(0, &[&guardians[0], &guardians[1]], &treasury.pubkey(), None).await;
+    assert_error(result, LockerErrorCode::LockAlreadyUnlocked);
+}
+
+#[tokio::test]
+async fn guardian_penalty_of_a_beneficiary_lock_is_paid_in_tokens_to_the_beneficiary() {
+    let mut env = TestEnv::start().await;
+    env.create_locker(0).await;
+    let beneficiary = env.fund_new_wallet(1_000_000_000).await;
+    let guardian = Keypair::new();
+    let token = env.create_mint(&spl_token::ID, &[], |_| vec![]).await;
+    env.mint_to_authority(&token, 1_000_000).await;
+
+    env.set_guardians(&[guardian.pubkey()], 1, 250, &beneficiary.pubkey()).await.unwrap();
+
+    let conditions = [LockCondition::Time { strike_time: START_TS as u32 + 3_600 }];
+    let instruction = client::deposit_and_lock(&pricelocker::ID, &env.authority.pubkey(), LOCKER_NAME, 400_000, Some(&token), None, &conditions, Some(beneficiary.pubkey()));
+    env.process(&[instruction], &[]).await.unwrap();
+
+    // The associated token account of the beneficiary is created for the penalty
+    env.guardian_release(0, &[&guardian], &beneficiary.pubkey(), Some(&token)).await.unwrap();
+    let beneficiary_token_address = client::wallet_token_address(&beneficiary.pubkey(), &token);
+    assert_eq!(env.token_balance(&beneficiary_token_address).await, 10_000);
+
+    // The beneficiary claims what is left after the penalty
+    let instruction = client::claim_beneficiary_funds(&pricelocker::ID, &beneficiary.pubkey(), &env.authority.pubkey(), LOCKER_NAME, 0, WITHDRAW_MAX, Some(&token));
+    env.process(&[instruction], &[&beneficiary]).await.unwrap();
+    assert_eq!(env.token_balance(&beneficiary_token_address).await, 400_000);
+    let locker_token_address = client::locker_token_address(&env.locker_address(), &token);
+    assert_eq!(env.token_balance(&locker_token_address).await, 0);
+}
+
+#[tokio::test]
+async fn guardian_penalty_can_not_divert_the_funds_of_a_beneficiary() {
+    let mut env = TestEnv::start().await;
+    env.create_locker(0).await;
+    let beneficiary = Pubkey::new_unique();
+    let charity = Pubkey::new_unique();
+    let guardian = Keypair::new();
+    env.set_guardians(&[guardian.pubkey()], 1, 250, &charity).await.unwrap();
+
+    let conditions = [LockCondition::Time { strike_time: START_TS as u32 + 3_600 }];
+    let instruction = client::deposit_and_lock(&pricelocker::ID, &env.authority.pubkey(), LOCKER_NAME, 400_000, None, None, &conditions, Some(beneficiary));
+    env.process(&[instruction], &[]).await.unwrap();
+
+    let result = env.guardian_release(0, &[&guardian], &charity, None).await;
+    assert_error(result, LockerErrorCode::PenaltyNotToTheBeneficiary);
+}
+
+#[tokio::test]
+async fn guardians_can_not_change_while_funds_are_locked() {
+    let mut env = TestEnv::start().await;
+    env.create_locker(0).await;
+    let treasury = Pubkey::new_unique();
+    let guardian = Pubkey::new_unique();
+
+    let result = env.guardian_release(0, &[], &treasury, None).await;
+    assert_error(result, LockerErrorCode::NoGuardians);
+
+    // Thresholds out of range, duplicate guardians and penalties above 100% are rejected
+    for (guardians, threshold, penalty_bps) in [(vec![guardian], 0, 0), (vec![guardian], 2, 0), (vec![guardian, guardian], 1, 0), (vec![guardian], 1, 10_001)] {
+        let result = env.set_guardians(&guardians, threshold, penalty_bps, &treasury).await;
+        assert_error(result, LockerErrorCode::InvalidGuardianConfig);
+    }
+
+    // The authority can't approve its own release or receive its penalty
+    let authority = env.authority.pubkey();
+    let result = env.set_guardians(&[authority], 1, 500, &treasury).await;
+    assert_error(result, LockerErrorCode::InvalidGuardianConfig);
+    let result = env.set_guardians(&[guardian], 1, 500, &authority).await;
+    assert_error(result, LockerErrorCode::InvalidGuardianConfig);
+
+    env.set_guardians(&[guardian], 1, 500, &treasury).await.unwrap();
+    assert_eq!(env.fetch_locker().await.guardians.unwrap().guardians, vec![guardian]);
+
+    let conditions = [LockCondition::Time { strike_time: START_TS as u32 + 60 }];
+    let instruction = client::deposit_and_lock(&pricelocker::ID, &env.authority.pubkey(), LOCKER_NAME, 100_000, None, None, &conditions, None);
+    env.process(&[instruction], &[]).await.unwrap();
+
+    // The authority can't swap in its own guardians or lower the penalty
+    let result = env.set_guardians(&[env.authority.pubkey()], 1, 0, &treasury).await;
+    assert_error(result, LockerErrorCode::GuardiansLocked);
+    let result = env.set_guardians(&[], 0, 0, &treasury).await;
+    assert_error(result, LockerErrorCode::GuardiansLocked);
+
+    // Once unlocked, the guardians can be removed
+    env.set_time(START_TS + 60).await;
+    let instruction = client::time_unlock_funds(&pricelocker::ID, &env.authority.pubkey(), LOCKER_NAME, 0);
+    env.process(&[instruction], &[]).await.unwrap();
+    env.set_guardians(&[], 0, 0, &treasury).await.unwrap();
+    assert!(env.fetch_locker().await.guardians.is_none());
+}
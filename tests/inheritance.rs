@@ -0,0 +1,124 @@
+mod common;
+
+use common::*;
+use pricelocker::{LockCondition, LockerErrorCode, WITHDRAW_MAX};
+use pricelocker_client as client;
+use pricelocker_client::CrankLocker;
+use solana_sdk::signature::{Keypair, Signer};
+
+
+const PERIOD: u32 = 3_600;
+
+impl TestEnv {
+    // Lock a deposit for the heir, unlocked when the authority was inactive for the period
+    async fn lock_for_heir(&mut self, heir: &Keypair, amount: u32) {
+        let conditions = [LockCondition::Inactivity { period: PERIOD }];
+        let instruction = client::deposit_and_lock(&pricelocker::ID, &self.authority.pubkey(), LOCKER_NAME, amount, None, None, &conditions, Some(heir.pubkey()));
+        self.process(&[instruction], &[]).await.unwrap();
+    }
+
+    // Crank as a keeper, which is not activity of the authority
+    async fn crank(&mut self, keeper: &Keypair) {
+        let lockers = [CrankLocker { locker: self.locker_address(), price_feed: None }];
+        let instruction = client::crank_unlocks(&pricelocker::ID, &keeper.pubkey(), &lockers);
+        self.process(&[instruction], &[keeper]).await.unwrap();
+    }
+}
+
+
+#[tokio::test]
+async fn heir_inherits_after_the_authority_was_inactive() {
+    let mut env = TestEnv::start().await;
+    env.create_locker(0).await;
+    let heir = env.fund_new_wallet(1_000_000_000).await;
+    env.lock_for_heir(&heir, 500_000).await;
+
+    // Checking in restarts the period
+    env.set_time(START_TS + 3_000).await;
+    let instruction = client::heartbeat(&pricelocker::ID, &env.authority.pubkey(), LOCKER_NAME);
+    env.process(&[instruction], &[]).await.unwrap();
+    assert_eq!(env.fetch_locker().await.last_activity_ts, START_TS as u32 + 3_000);
+
+    env.set_time(START_TS + PERIOD as i64 + 100).await;
+    env.crank(&heir).await;
+    assert!(env.fetch_locker().await.locks[0].is_locked());
+
+    // Inactive for the whole period since the last check-in
+    env.set_time(START_TS + 3_000 + PERIOD as i64).await;
+    env.crank(&heir).await;
+    assert!(!env.fetch_locker().await.locks[0].is_locked());
+
+    // The funds are inherited, the authority can't withdraw them
+    let result = env.process(&[client::withdraw_unlocked_funds(&pricelocker::ID, &env.authority.pubkey(), LOCKER_NAME, 1, None)], &[]).await;
+    assert_error(result, LockerErrorCode::PayoutAmountExceedsAvailableBalance);
+
+    let lamports_before = env.lamports(&heir.pubkey()).await;
+    let instruction = client::claim_beneficiary_funds(&pricelocker::ID, &heir.pubkey(), &env.authority.pubkey(), LOCKER_NAME, 0, WITHDRAW_MAX, None);
+    env.process(&[instruction], &[&heir]).await.unwrap();
+    assert_eq!(env.lamports(&heir.pubkey()).await, lamports_before + 500_000);
+}
+
+#[tokio::test]
+async fn any_instruction_of_the_authority_checks_in() {
+    let mut env = TestEnv::start().await;
+    env.create_locker(0).await;
+    let heir = Keypair::new();
+    env.lock_for_heir(&heir, 500_000).await;
+
+    env.set_time(START_TS + PERIOD as i64 - 1).await;
+    let instruction = client::deposit_funds(&pricelocker::ID, &env.authority.pubkey(), LOCKER_NAME, 100_000, None, None);
+    env.process(&[instruction], &[]).await.unwrap();
+    assert_eq!(env.fetch_locker().await.last_activity_ts, START_TS as u32 + PERIOD - 1);
+
+    // Unlocking as the authority checks in before the locks are evaluated
+    env.set_time(START_TS + 2 * PERIOD as i64 - 1).await;
+    let instruction = client::time_unlock_funds(&pricelocker::ID, &env.authority.pubkey(), LOCKER_NAME, 0);
+    let result = env.process(&[instruction], &[]).await;
+    assert_error(result, LockerErrorCode::AuthorityStillActive);
+}
+
+#[tokio::test]
+async fn inactivity_lock_needs_an_heir() {
+    let mut env = TestEnv::start().await;
+    env.create_locker(0).await;
+
+    let conditions = [LockCondition::Inactivity { period: PERIOD }];
+    let instruction = client::deposit_and_lock(&pricelocker::ID, &env.authority.pubkey(), LOCKER_NAME, 500_000, None, None, &conditions, None);
+    let result = env.process(&[instruction], &[]).await;
+    assert_error(result, LockerErrorCode::InactivityLockWithoutHeir);
+}
+
+#[tokio::test]
+async fn authority_can_revoke_an_inactivity_lock_while_active() {
+    let mut env = TestEnv::start().await;
+    env.create_locker(0).await;
+    let heir = Keypair::new();
+    env.lock_for_heir(&heir, 500_000).await;
+
+    // An inheritance joined with a time lock stays promised to the heir under the time lock
+    let conditions = [LockCondition::Time { strike_time: START_TS as u32 + 10 * PERIOD }, LockCondition::Inactivity { period: PERIOD }];
+    let instruction = client::deposit_and_lock(&pricelocker::ID, &env.authority.pubkey(), LOCKER_NAME, 200_000, None, None, &conditions, Some(heir.pubkey()));
+    env.process(&[instruction], &[]).await.unwrap();
+    let result = env.process(&[client::revoke_inactivity_lock(&pricelocker::ID, &env.authority.pubkey(), LOCKER_NAME, 2)], &[]).await;
+    assert_error(result, LockerErrorCode::InactivityLockJoined);
+    let result = env.process(&[client::revoke_inactivity_lock(&pricelocker::ID, &env.authority.pubkey(), LOCKER_NAME, 1)], &[]).await;
+    assert_error(result, LockerErrorCode::NotAnInactivityLock);
+
+    // Revoking checks in and releases the funds to the authority
+    env.set_time(START_TS + PERIOD as i64 - 1).await;
+    let instruction = client::revoke_inactivity_lock(&pricelocker::ID, &env.authority.pubkey(), LOCKER_NAME, 0);
+    env.process(std::slice::from_ref(&instruction), &[]).await.unwrap();
+    let locker = env.fetch_locker().await;
+    assert_eq!(locker.last_activity_ts, START_TS as u32 + PERIOD - 1);
+    assert!(!locker.locks[0].is_locked());
+    assert_eq!(locker.locks[0].beneficiary(), None);
+
+    let instruction_withdraw = client::withdraw_unlocked_funds(&pricelocker::ID, &env.authority.pubkey(), LOCKER_NAME, 500_000, None);
+    env.process(&[instruction_withdraw], &[]).await.unwrap();
+    let instruction_claim = client::claim_beneficiary_funds(&pricelocker::ID, &heir.pubkey(), &env.authority.pubkey(), LOCKER_NAME, 0, WITHDRAW_MAX, None);
+    let result = env.process(&[instruction_claim], &[&heir]).await;
+    assert_error(result, LockerErrorCode::NotTheBeneficiary);
+
+    let result = env.process(&[instruction], &[]).await;
+    assert_error(result, LockerErrorCode::LockAlreadyUnlocked);
+}
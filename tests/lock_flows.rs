@@ -0,0 +1,372 @@
+mod common;
+
+use anchor_lang::error::ErrorCode as AnchorErrorCode;
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::{InstructionData, Owner};
+use common::*;
+use pricelocker::{Lock, LockCondition, LockerErrorCode, PythErrorCode, SkippedLock, UnlockResult};
+use pricelocker_client as client;
+use solana_sdk::signature::Signer;
+
+
+#[tokio::test]
+async fn create_locker_and_deposit_sol() {
+    let mut env = TestEnv::start().await;
+    env.create_locker(0).await;
+
+    let locker_address = env.locker_address();
+    let lamports_before = env.lamports(&locker_address).await;
+
+    let instruction = client::deposit_funds(&pricelocker::ID, &env.authority.pubkey(), LOCKER_NAME, 1_000_000, None, None);
+    env.process(&[instruction], &[]).await.unwrap();
+
+    assert_eq!(env.lamports(&locker_address).await, lamports_before + 1_000_000);
+
+    let locker = env.fetch_locker().await;
+    assert_eq!(locker.authority, env.authority.pubkey());
+    assert_eq!(locker.creation_ts, START_TS as u32);
+    assert!(locker.locks.is_empty());
+}
+
+#[tokio::test]
+async fn create_pricefeed_account_registers_pyth_account() {
+    let mut env = TestEnv::start().await;
+    let feed = env.create_price_feed(100, START_TS).await;
+
+    let account = env.get_account(&feed.price_feed).await.unwrap();
+    let pricefeed_account = client::decode_pricefeed_account(&account.data).unwrap();
+    assert_eq!(pricefeed_account.pricefeed_alias, PRICEFEED_ALIAS);
+    assert_eq!(pricefeed_account.pricefeed_id, feed.pyth_price_account);
+}
+
+#[tokio::test]
+async fn time_lock_rejects_zero_amount() {
+    let mut env = TestEnv::start().await;
+    env.create_locker(1_000_000).await;
+
+    let result = env.time_lock(LOCKER_NAME, START_TS + 60, 0).await;
+    assert_error(result, LockerErrorCode::PayoutAmountNotPositive);
+}
+
+#[tokio::test]
+async fn time_lock_rejects_amount_above_balance() {
+    let mut env = TestEnv::start().await;
+    env.create_locker(1_000_000).await;
+
+    // Only the deposit can be locked, not the rent-exempt minimum of the locker
+    let result = env.time_lock(LOCKER_NAME, START_TS + 60, 1_000_001).await;
+    assert_error(result, LockerErrorCode::PayoutAmountExceedsAvailableBalance);
+}
+
+#[tokio::test]
+async fn joined_lock_locks_the_same_funds() {
+    let mut env = TestEnv::start().await;
+    env.create_locker(1_000_000).await;
+    env.create_price_feed(90, START_TS).await;
+    let lamports = 1_000_000;
+
+    // A price lock on all funds, joined with a time lock on the same funds
+    env.price_lock(LOCKER_NAME, 100, lamports).await.unwrap();
+    let instruction = client::time_lock_funds(&pricelocker::ID, &env.authority.pubkey(), LOCKER_NAME, START_TS as u32 + 60, lamports, None, Some(0));
+    env.process(&[instruction], &[]).await.unwrap();
+
+    // An unjoined lock can't lock the same funds again
+    let result = env.time_lock(LOCKER_NAME, START_TS + 60, 1).await;
+    assert_error(result, LockerErrorCode::PayoutAmountExceedsAvailableBalance);
+
+    // Joining a lock that doesn't exist is rejected
+    let instruction = client::time_lock_funds(&pricelocker::ID, &env.authority.pubkey(), LOCKER_NAME, START_TS as u32 + 60, 1, None, Some(5));
+    let result = env.process(&[instruction], &[]).await;
+    assert_error(result, LockerErrorCode::InvalidJoin);
+}
+
+#[tokio::test]
+async fn deposit_and_lock_locks_the_deposit() {
+    let mut env = TestEnv::start().await;
+    env.create_locker(0).await;
+
+    // The deposit unlocks at the strike time or at the strike price, whichever comes first
+    let conditions = [LockCondition::Time { strike_time: START_TS as u32 + 60 }, LockCondition::Price { strike_price: 100, price_feed: price_feed_address() }];
+    let instruction = client::deposit_and_lock(&pricelocker::ID, &env.authority.pubkey(), LOCKER_NAME, 500_000, None, None, &conditions, None);
+    env.process(&[instruction], &[]).await.unwrap();
+
+    // The locker grows for the lot and the locks, so it also holds extra rent
+    let locker_address = env.locker_address();
+    let lamports_after_deposit = env.lamports(&locker_address).await;
+
+    let locker = env.fetch_locker().await;
+    assert_eq!(locker.locked_balance, 500_000);
+    assert_eq!(locker.locks, vec![
+        Lock::TimeLock { id: 0, strike_time: START_TS as u32 + 60, amount: 500_000, token_mint: None, locked: true, join: None, lot: Some(0), beneficiary: None, early_exit: None },
+        Lock::PriceLock { id: 1, strike_price: 100, price_feed: price_feed_address(), amount: 500_000, token_mint: None, locked: true, join: Some(0), lot: Some(0), beneficiary: None },
+    ]);
+
+    // Nothing of the deposit is withdrawable until a condition is met
+    let instruction = client::withdraw_unlocked_funds(&pricelocker::ID, &env.authority.pubkey(), LOCKER_NAME, 1, None);
+    let result = env.process(&[instruction], &[]).await;
+    assert_error(result, LockerErrorCode::PayoutAmountExceedsAvailableBalance);
+
+    env.set_time(START_TS + 60).await;
+    let instructions = [
+        client::time_unlock_funds(&pricelocker::ID, &env.authority.pubkey(), LOCKER_NAME, 0),
+        client::withdraw_unlocked_funds(&pricelocker::ID, &env.authority.pubkey(), LOCKER_NAME, 500_000, None),
+    ];
+    env.process(&instructions, &[]).await.unwrap();
+    assert_eq!(env.lamports(&locker_address).await, lamports_after_deposit - 500_000);
+}
+
+#[tokio::test]
+async fn deposit_and_lock_rejects_missing_conditions_and_zero_amount() {
+    let mut env = TestEnv::start().await;
+    env.create_locker(0).await;
+
+    let instruction = client::deposit_and_lock(&pricelocker::ID, &env.authority.pubkey(), LOCKER_NAME, 500_000, None, None, &[], None);
+    let result = env.process(&[instruction], &[]).await;
+    assert_error(result, LockerErrorCode::NoLockConditions);
+
+    let conditions = [LockCondition::Time { strike_time: START_TS as u32 + 60 }];
+    let instruction = client::deposit_and_lock(&pricelocker::ID, &env.authority.pubkey(), LOCKER_NAME, 0, None, None, &conditions, None);
+    let result = env.process(&[instruction], &[]).await;
+    assert_error(result, LockerErrorCode::PayoutAmountNotPositive);
+}
+
+#[tokio::test]
+async fn time_locks_unlock_after_strike_time() {
+    let mut env = TestEnv::start().await;
+    env.create_locker(1_000_000).await;
+
+    env.time_lock(LOCKER_NAME, START_TS + 60, 400_000).await.unwrap();
+    env.time_lock(LOCKER_NAME, START_TS + 120, 300_000).await.unwrap();
+
+    let locker = env.fetch_locker().await;
+    assert_eq!(locker.locks.len(), 2);
+    assert_eq!(locker.locked_balance, 700_000);
+    assert!(locker.locks.iter().all(Lock::is_locked));
+
+    env.set_time(START_TS + 60).await;
+    let instruction = client::time_unlock_funds(&pricelocker::ID, &env.authority.pubkey(), LOCKER_NAME, 0);
+    env.process(&[instruction], &[]).await.unwrap();
+
+    let locker = env.fetch_locker().await;
+    assert!(!locker.locks[0].is_locked());
+    assert!(locker.locks[1].is_locked());
+}
+
+#[tokio::test]
+async fn price_lock_unlocks_when_price_reaches_strike_price() {
+    let mut env = TestEnv::start().await;
+    env.create_locker(1_000_000).await;
+    let feed = env.create_price_feed(90, START_TS).await;
+
+    env.price_lock(LOCKER_NAME, 100, 500_000).await.unwrap();
+
+    let locker = env.fetch_locker().await;
+    assert!(matches!(locker.locks[0], Lock::PriceLock { strike_price: 100, amount: 500_000, locked: true, .. }));
+
+    env.set_pyth_price(&feed.pyth_price_account, MockPrice::trading(100, START_TS));
+    let instruction = client::price_unlock_funds(&pricelocker::ID, &env.authority.pubkey(), LOCKER_NAME, &feed, 0);
+    env.process(&[instruction], &[]).await.unwrap();
+
+    let locker = env.fetch_locker().await;
+    assert!(!locker.locks[0].is_locked());
+}
+
+#[tokio::test]
+async fn price_unlock_rejects_pyth_account_of_other_feed() {
+    let mut env = TestEnv::start().await;
+    env.create_locker(1_000_000).await;
+    let mut feed = env.create_price_feed(100, START_TS).await;
+    env.price_lock(LOCKER_NAME, 100, 500_000).await.unwrap();
+
+    // A valid Pyth account, but not the one registered in the price feed account
+    feed.pyth_price_account = Pubkey::new_unique();
+    env.set_pyth_price(&feed.pyth_price_account, MockPrice::trading(1_000, START_TS));
+
+    let instruction = client::price_unlock_funds(&pricelocker::ID, &env.authority.pubkey(), LOCKER_NAME, &feed, 0);
+    let result = env.process(&[instruction], &[]).await;
+    assert_error(result, PythErrorCode::InvalidArgument);
+}
+
+#[tokio::test]
+async fn price_unlock_rejects_invalid_pyth_account_data() {
+    let mut env = TestEnv::start().await;
+    env.create_locker(1_000_000).await;
+    let feed = env.create_price_feed(100, START_TS).await;
+    env.price_lock(LOCKER_NAME, 100, 500_000).await.unwrap();
+
+    // Owned by the oracle, but not in the Pyth price account layout
+    env.set_account(&feed.pyth_price_account, vec![1; 64], pricelocker::PriceFeed::owner());
+
+    let instruction = client::price_unlock_funds(&pricelocker::ID, &env.authority.pubkey(), LOCKER_NAME, &feed, 0);
+    let result = env.process(&[instruction], &[]).await;
+    assert_error(result, PythErrorCode::PythError);
+}
+
+#[tokio::test]
+async fn price_unlock_rejects_account_not_owned_by_oracle() {
+    let mut env = TestEnv::start().await;
+    env.create_locker(1_000_000).await;
+    let feed = env.create_price_feed(100, START_TS).await;
+    env.price_lock(LOCKER_NAME, 100, 500_000).await.unwrap();
+
+    env.set_account(&feed.pyth_price_account, vec![0; 3312], Pubkey::new_unique());
+
+    let instruction = client::price_unlock_funds(&pricelocker::ID, &env.authority.pubkey(), LOCKER_NAME, &feed, 0);
+    let result = env.process(&[instruction], &[]).await;
+    assert_error(result, anchor_error(AnchorErrorCode::AccountOwnedByWrongProgram));
+}
+
+#[tokio::test]
+async fn locker_rejects_other_authority() {
+    let mut env = TestEnv::start().await;
+    env.create_locker(1_000_000).await;
+
+    // The locker address is derived from the authority, so another signer can't pass the seeds constraint
+    let other = solana_sdk::signature::Keypair::new();
+    let mut instruction = client::time_lock_funds(&pricelocker::ID, &other.pubkey(), LOCKER_NAME, START_TS as u32, 1, None, None);
+    instruction.accounts[1].pubkey = env.locker_address();
+
+    let result = env.process(&[instruction], &[&other]).await;
+    assert_error(result, anchor_error(AnchorErrorCode::ConstraintSeeds));
+}
+
+
+#[tokio::test]
+async fn time_unlock_fails_before_strike_time() {
+    let mut env = TestEnv::start().await;
+    env.create_locker(1_000_000).await;
+    env.time_lock(LOCKER_NAME, START_TS + 60, 400_000).await.unwrap();
+
+    env.set_time(START_TS + 59).await;
+    let instruction = client::time_unlock_funds(&pricelocker::ID, &env.authority.pubkey(), LOCKER_NAME, 0);
+    let result = env.process(&[instruction], &[]).await;
+    assert_error(result, LockerErrorCode::TimeLowerThanStrikeTime);
+    assert!(env.fetch_locker().await.locks[0].is_locked());
+}
+
+#[tokio::test]
+async fn time_unlock_fails_without_lock_at_index() {
+    let mut env = TestEnv::start().await;
+    env.create_locker(1_000_000).await;
+
+    let instruction = client::time_unlock_funds(&pricelocker::ID, &env.authority.pubkey(), LOCKER_NAME, 0);
+    let result = env.process(&[instruction], &[]).await;
+    assert_error(result, LockerErrorCode::NoLockAtIndex);
+}
+
+#[tokio::test]
+async fn time_unlock_fails_on_price_lock() {
+    let mut env = TestEnv::start().await;
+    env.create_locker(1_000_000).await;
+    env.create_price_feed(90, START_TS).await;
+    env.price_lock(LOCKER_NAME, 100, 400_000).await.unwrap();
+
+    let instruction = client::time_unlock_funds(&pricelocker::ID, &env.authority.pubkey(), LOCKER_NAME, 0);
+    let result = env.process(&[instruction], &[]).await;
+    assert_error(result, LockerErrorCode::NotATimeLock);
+    assert!(env.fetch_locker().await.locks[0].is_locked());
+}
+
+#[tokio::test]
+async fn time_unlock_all_skips_ineligible_locks() {
+    let mut env = TestEnv::start().await;
+    env.create_locker(1_000_000).await;
+    env.create_price_feed(90, START_TS).await;
+    env.time_lock(LOCKER_NAME, START_TS + 60, 100_000).await.unwrap();
+    env.price_lock(LOCKER_NAME, 100, 100_000).await.unwrap();
+    env.time_lock(LOCKER_NAME, START_TS + 120, 100_000).await.unwrap();
+    env.time_lock(LOCKER_NAME, START_TS + 30, 100_000).await.unwrap();
+
+    env.set_time(START_TS + 60).await;
+    let instruction = client::time_unlock_funds(&pricelocker::ID, &env.authority.pubkey(), LOCKER_NAME, 255);
+    let result: UnlockResult = env.simulate(instruction.clone()).await.unwrap();
+    assert_eq!(result, UnlockResult {
+        unlocked: vec![0, 3],
+        skipped: vec![
+            SkippedLock { lock_id: 1, reason: LockerErrorCode::NotATimeLock },
+            SkippedLock { lock_id: 2, reason: LockerErrorCode::TimeLowerThanStrikeTime },
+        ],
+    });
+
+    env.process(&[instruction], &[]).await.unwrap();
+    let locked: Vec<bool> = env.fetch_locker().await.locks.iter().map(Lock::is_locked).collect();
+    assert_eq!(locked, vec![false, true, true, false]);
+}
+
+#[tokio::test]
+async fn price_unlock_fails_below_strike_price() {
+    let mut env = TestEnv::start().await;
+    env.create_locker(1_000_000).await;
+    let feed = env.create_price_feed(99, START_TS).await;
+    env.price_lock(LOCKER_NAME, 100, 500_000).await.unwrap();
+
+    // The confidence interval is not taken into account, only the aggregate price
+    env.set_pyth_price(&feed.pyth_price_account, MockPrice { conf: 10, ..MockPrice::trading(99, START_TS) });
+
+    let instruction = client::price_unlock_funds(&pricelocker::ID, &env.authority.pubkey(), LOCKER_NAME, &feed, 0);
+    let result = env.process(&[instruction], &[]).await;
+    assert_error(result, LockerErrorCode::StrikePriceTooLow);
+    assert!(env.fetch_locker().await.locks[0].is_locked());
+}
+
+#[tokio::test]
+async fn price_unlock_fails_on_stale_price() {
+    let mut env = TestEnv::start().await;
+    env.create_locker(1_000_000).await;
+    let feed = env.create_price_feed(100, START_TS).await;
+    env.price_lock(LOCKER_NAME, 100, 500_000).await.unwrap();
+
+    // Prices older than 60 seconds are rejected as offline
+    env.set_time(START_TS + 61).await;
+    let instruction = client::price_unlock_funds(&pricelocker::ID, &env.authority.pubkey(), LOCKER_NAME, &feed, 0);
+    let result = env.process(&[instruction], &[]).await;
+    assert_error(result, PythErrorCode::PythOffline);
+    assert!(env.fetch_locker().await.locks[0].is_locked());
+}
+
+#[tokio::test]
+async fn price_unlock_fails_on_time_lock() {
+    let mut env = TestEnv::start().await;
+    env.create_locker(1_000_000).await;
+    let feed = env.create_price_feed(100, START_TS).await;
+    env.time_lock(LOCKER_NAME, START_TS, 500_000).await.unwrap();
+
+    let instruction = client::price_unlock_funds(&pricelocker::ID, &env.authority.pubkey(), LOCKER_NAME, &feed, 0);
+    let result = env.process(&[instruction], &[]).await;
+    assert_error(result, LockerErrorCode::NotAPriceLock);
+    assert!(env.fetch_locker().await.locks[0].is_locked());
+}
+
+#[tokio::test]
+async fn price_unlock_all_skips_ineligible_locks() {
+    let mut env = TestEnv::start().await;
+    env.create_locker(1_000_000).await;
+    let feed = env.create_price_feed(150, START_TS).await;
+    env.price_lock(LOCKER_NAME, 100, 100_000).await.unwrap();
+    env.price_lock(LOCKER_NAME, 200, 100_000).await.unwrap();
+    env.time_lock(LOCKER_NAME, START_TS, 100_000).await.unwrap();
+
+    let instruction = client::price_unlock_funds(&pricelocker::ID, &env.authority.pubkey(), LOCKER_NAME, &feed, 255);
+    let result: UnlockResult = env.simulate(instruction).await.unwrap();
+    assert_eq!(result, UnlockResult {
+        unlocked: vec![0],
+        skipped: vec![
+            SkippedLock { lock_id: 1, reason: LockerErrorCode::StrikePriceTooLow },
+            SkippedLock { lock_id: 2, reason: LockerErrorCode::NotAPriceLock },
+        ],
+    });
+}
+
+#[tokio::test]
+async fn create_pricefeed_account_rejects_invalid_pricefeed_id() {
+    let mut env = TestEnv::start().await;
+
+    let mut instruction = client::create_pricefeed_account(&pricelocker::ID, &env.authority.pubkey(), PRICEFEED_ALIAS, &Pubkey::new_unique());
+    instruction.data = pricelocker::instruction::CreatePricefeedAccount {
+        pricefeed_alias: PRICEFEED_ALIAS.to_string(),
+        pricefeed_id_string: "not-a-public-key".to_string(),
+    }.data();
+
+    let result = env.process(&[instruction], &[]).await;
+    assert_error(result, LockerErrorCode::InvalidPricefeedId);
+}
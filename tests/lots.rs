@@ -0,0 +1,132 @@
+mod common;
+
+use common::*;
+use pricelocker::{Lock, LockCondition, LockerErrorCode, LockerStatus, Lot};
+use pricelocker_client as client;
+use solana_sdk::signature::Signer;
+
+
+impl TestEnv {
+    async fn deposit(&mut self, amount: u32) {
+        let instruction = client::deposit_funds(&pricelocker::ID, &self.authority.pubkey(), LOCKER_NAME, amount, None, None);
+        self.process(&[instruction], &[]).await.unwrap();
+    }
+
+    async fn withdraw(&mut self, amount: u32) {
+        let instruction = client::withdraw_unlocked_funds(&pricelocker::ID, &self.authority.pubkey(), LOCKER_NAME, amount, None);
+        self.process(&[instruction], &[]).await.unwrap();
+    }
+}
+
+
+#[tokio::test]
+async fn deposits_are_recorded_as_lots() {
+    let mut env = TestEnv::start().await;
+    env.create_locker(0).await;
+    let feed = env.create_price_feed(100, START_TS).await;
+
+    // The price at the deposit is recorded when a price feed is passed
+    let instruction = client::deposit_funds(&pricelocker::ID, &env.authority.pubkey(), LOCKER_NAME, 300_000, None, Some(&feed));
+    env.process(&[instruction], &[]).await.unwrap();
+
+    env.set_time(START_TS + 60).await;
+    env.deposit(200_000).await;
+
+    assert_eq!(env.fetch_locker().await.lots, vec![
+        Lot { id: 0, token_mint: None, amount: 300_000, deposit_ts: START_TS as u32, deposit_price: Some(100) },
+        Lot { id: 1, token_mint: None, amount: 200_000, deposit_ts: START_TS as u32 + 60, deposit_price: None },
+    ]);
+}
+
+#[tokio::test]
+async fn withdrawals_consume_oldest_lots_first() {
+    let mut env = TestEnv::start().await;
+    env.create_locker(300_000).await;
+    env.deposit(200_000).await;
+
+    env.withdraw(400_000).await;
+
+    // The first lot is consumed entirely and removed, the ids of the other lots don't change
+    let locker = env.fetch_locker().await;
+    assert_eq!(locker.lots, vec![Lot { id: 1, token_mint: None, amount: 100_000, deposit_ts: START_TS as u32, deposit_price: None }]);
+
+    env.deposit(50_000).await;
+    let lot_ids: Vec<u16> = env.fetch_locker().await.lots.iter().map(|lot| lot.id).collect();
+    assert_eq!(lot_ids, vec![1, 2]);
+}
+
+#[tokio::test]
+async fn withdrawals_skip_locked_funds_of_lots() {
+    let mut env = TestEnv::start().await;
+    env.create_locker(300_000).await;
+    env.deposit(200_000).await;
+
+    let conditions = [LockCondition::Time { strike_time: START_TS as u32 + 3_600 }];
+    let instruction = client::lock_lot(&pricelocker::ID, &env.authority.pubkey(), LOCKER_NAME, 0, 250_000, None, &conditions, None);
+    env.process(&[instruction], &[]).await.unwrap();
+
+    // Only the unlocked 50_000 of the first lot can be consumed, the rest comes from the second lot
+    env.withdraw(150_000).await;
+
+    let amounts: Vec<(u16, u32)> = env.fetch_locker().await.lots.iter().map(|lot| (lot.id, lot.amount)).collect();
+    assert_eq!(amounts, vec![(0, 250_000), (1, 100_000)]);
+}
+
+#[tokio::test]
+async fn relative_time_lock_unlocks_after_deposit() {
+    let mut env = TestEnv::start().await;
+    env.create_locker(300_000).await;
+
+    // Locked later, but relative to the time of the deposit
+    env.set_time(START_TS + 30).await;
+    let conditions = [LockCondition::AfterDeposit { duration: 60 }];
+    let instruction = client::lock_lot(&pricelocker::ID, &env.authority.pubkey(), LOCKER_NAME, 0, 300_000, None, &conditions, None);
+    env.process(&[instruction], &[]).await.unwrap();
+
+    let locker = env.fetch_locker().await;
+    assert_eq!(locker.locks, vec![
+        Lock::TimeLock { id: 0, strike_time: START_TS as u32 + 60, amount: 300_000, token_mint: None, locked: true, join: None, lot: Some(0), beneficiary: None, early_exit: None },
+    ]);
+
+    env.set_time(START_TS + 60).await;
+    let instruction = client::time_unlock_funds(&pricelocker::ID, &env.authority.pubkey(), LOCKER_NAME, 0);
+    env.process(&[instruction], &[]).await.unwrap();
+    assert!(!env.fetch_locker().await.locks[0].is_locked());
+}
+
+#[tokio::test]
+async fn lock_lot_is_limited_to_the_lot() {
+    let mut env = TestEnv::start().await;
+    env.create_locker(300_000).await;
+    env.deposit(200_000).await;
+
+    // The locker holds enough funds, but the lot doesn't
+    let conditions = [LockCondition::Price { strike_price: 100, price_feed: price_feed_address() }];
+    let instruction = client::lock_lot(&pricelocker::ID, &env.authority.pubkey(), LOCKER_NAME, 1, 200_001, None, &conditions, None);
+    let result = env.process(&[instruction], &[]).await;
+    assert_error(result, LockerErrorCode::PayoutAmountExceedsAvailableBalance);
+
+    let instruction = client::lock_lot(&pricelocker::ID, &env.authority.pubkey(), LOCKER_NAME, 2, 1, None, &conditions, None);
+    let result = env.process(&[instruction], &[]).await;
+    assert_error(result, LockerErrorCode::InvalidLot);
+}
+
+#[tokio::test]
+async fn locker_grows_without_touching_deposits() {
+    let mut env = TestEnv::start().await;
+    env.create_locker(0).await;
+
+    let locker_address = env.locker_address();
+    let space_before = env.get_account(&locker_address).await.unwrap().data.len();
+
+    for _ in 0..30 {
+        env.deposit(10_000).await;
+    }
+
+    // The authority pays the rent of the extra space, so all deposits stay withdrawable
+    assert!(env.get_account(&locker_address).await.unwrap().data.len() > space_before);
+    let instruction = client::get_locker_status(&pricelocker::ID, &locker_address, None, &[]);
+    let status: LockerStatus = env.simulate(instruction).await.unwrap();
+    assert_eq!(status.assets[0].withdrawable, 300_000);
+    assert_eq!(status.lots.len(), 30);
+}
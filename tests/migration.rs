@@ -0,0 +1,96 @@
+mod common;
+
+use anchor_lang::{AnchorSerialize, Discriminator};
+use common::*;
+use pricelocker::{LegacyLock, LegacyLocker, Lock, Locker, LockerErrorCode};
+use pricelocker_client as client;
+use solana_sdk::signature::Signer;
+
+
+impl TestEnv {
+    // Write a locker in the legacy layout, with the size create_new_locker allocated before lots were added
+    fn set_legacy_locker(&mut self, locks: Vec<LegacyLock>) {
+        let (address, bump) = client::find_locker_address(&pricelocker::ID, &self.authority.pubkey(), LOCKER_NAME);
+        let legacy_locker = LegacyLocker {
+            authority: self.authority.pubkey(),
+            creation_ts: START_TS as u32,
+            locked_balance: 0,
+            locks,
+            locked: false,
+            staked: false,
+            bump,
+        };
+
+        let mut data = Locker::DISCRIMINATOR.to_vec();
+        data.extend(legacy_locker.try_to_vec().unwrap());
+        data.resize(LegacyLocker::SPACE, 0);
+        self.set_account(&address, data, pricelocker::ID);
+    }
+}
+
+fn legacy_time_lock(id: u8, strike_time: u32, amount: u32) -> LegacyLock {
+    LegacyLock::TimeLock { id, strike_time, amount, token_mint: None, locked: true, join: None }
+}
+
+fn legacy_price_lock(id: u8, strike_price: u32, amount: u32) -> LegacyLock {
+    LegacyLock::PriceLock { id, strike_price, amount, token_mint: None, locked: true, join: None }
+}
+
+
+#[tokio::test]
+async fn legacy_locker_is_migrated_with_its_locks() {
+    let mut env = TestEnv::start().await;
+    let feed = env.create_price_feed(90, START_TS).await;
+    env.set_legacy_locker(vec![legacy_time_lock(0, START_TS as u32 + 60, 300_000), legacy_price_lock(1, 100, 200_000)]);
+
+    let instruction = client::migrate_locker(&pricelocker::ID, &env.authority.pubkey(), LOCKER_NAME, Some(&feed.price_feed));
+    env.process(&[instruction], &[]).await.unwrap();
+
+    let locker = env.fetch_locker().await;
+    assert_eq!(locker.locks, vec![
+        Lock::TimeLock { id: 0, strike_time: START_TS as u32 + 60, amount: 300_000, token_mint: None, locked: true, join: None, lot: None, beneficiary: None, early_exit: None },
+        Lock::PriceLock { id: 1, strike_price: 100, price_feed: feed.price_feed, amount: 200_000, token_mint: None, locked: true, join: None, lot: None, beneficiary: None },
+    ]);
+    assert_eq!(locker.locked_balance, 500_000);
+    assert!(locker.lots.is_empty());
+
+    // The migrated locks unlock like any other lock
+    env.set_time(START_TS + 60).await;
+    env.set_pyth_price(&feed.pyth_price_account, MockPrice::trading(100, START_TS + 60));
+    let instructions = [
+        client::time_unlock_funds(&pricelocker::ID, &env.authority.pubkey(), LOCKER_NAME, 0),
+        client::price_unlock_funds(&pricelocker::ID, &env.authority.pubkey(), LOCKER_NAME, &feed, 1),
+    ];
+    env.process(&instructions, &[]).await.unwrap();
+    assert!(env.fetch_locker().await.locks.iter().all(|lock_item| !lock_item.is_locked()));
+}
+
+#[tokio::test]
+async fn only_legacy_lockers_are_migrated() {
+    let mut env = TestEnv::start().await;
+    env.set_legacy_locker(vec![legacy_time_lock(0, START_TS as u32 + 60, 300_000)]);
+
+    let instruction = client::migrate_locker(&pricelocker::ID, &env.authority.pubkey(), LOCKER_NAME, None);
+    env.process(std::slice::from_ref(&instruction), &[]).await.unwrap();
+
+    // A migrated locker is never migrated again
+    env.set_time(START_TS + 1).await;
+    let result = env.process(&[instruction], &[]).await;
+    assert_error(result, LockerErrorCode::NotALegacyLocker);
+
+    // Neither is a locker created in the current layout
+    env.create_named_locker("rainy-day", 1_000_000).await;
+    let instruction = client::migrate_locker(&pricelocker::ID, &env.authority.pubkey(), "rainy-day", None);
+    let result = env.process(&[instruction], &[]).await;
+    assert_error(result, LockerErrorCode::NotALegacyLocker);
+}
+
+#[tokio::test]
+async fn legacy_price_locks_need_a_price_feed() {
+    let mut env = TestEnv::start().await;
+    env.set_legacy_locker(vec![legacy_price_lock(0, 100, 200_000)]);
+
+    let instruction = client::migrate_locker(&pricelocker::ID, &env.authority.pubkey(), LOCKER_NAME, None);
+    let result = env.process(&[instruction], &[]).await;
+    assert_error(result, LockerErrorCode::MissingPriceFeedAccounts);
+}
@@ -0,0 +1,39 @@
+// Price locks against the mock oracle, run with: cargo test --features localnet
+#![cfg(feature = "localnet")]
+
+mod common;
+
+use common::*;
+use pricelocker_client as client;
+use solana_sdk::signature::{Keypair, Signer};
+
+
+#[tokio::test]
+async fn price_lock_unlocks_at_mock_oracle_price() {
+    let mut env = TestEnv::start().await;
+    env.create_locker(1_000_000).await;
+    let feed = env.create_mock_price_feed(90).await;
+    env.price_lock(LOCKER_NAME, 100, 500_000).await.unwrap();
+
+    let admin = env.authority.pubkey();
+    env.process(&[set_price(&admin, PRICEFEED_ALIAS, 100, START_TS)], &[]).await.unwrap();
+
+    let instruction = client::price_unlock_funds(&pricelocker::ID, &admin, LOCKER_NAME, &feed, 0);
+    env.process(&[instruction], &[]).await.unwrap();
+
+    assert!(!env.fetch_locker().await.locks[0].is_locked());
+}
+
+#[tokio::test]
+async fn mock_oracle_rejects_other_admin() {
+    let mut env = TestEnv::start().await;
+    env.create_mock_price_feed(90).await;
+
+    // The price account is derived from the admin, so another signer can't pass the seeds constraint
+    let other = Keypair::new();
+    let mut instruction = set_price(&other.pubkey(), PRICEFEED_ALIAS, 100, START_TS);
+    instruction.accounts[1].pubkey = find_price_address(&env.authority.pubkey(), PRICEFEED_ALIAS);
+
+    let result = env.process(&[instruction], &[&other]).await;
+    assert_error(result, anchor_error(anchor_lang::error::ErrorCode::ConstraintSeeds));
+}
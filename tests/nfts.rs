@@ -0,0 +1,80 @@
+mod common;
+
+use anchor_spl::token::spl_token;
+use common::*;
+use pricelocker::{LockCondition, LockerErrorCode, NftStatus};
+use pricelocker_client as client;
+use solana_sdk::signature::Signer;
+
+
+#[tokio::test]
+async fn nft_is_locked_until_the_condition_is_met() {
+    let mut env = TestEnv::start().await;
+    env.create_locker(0).await;
+    let nft = env.create_nft(&spl_token::ID).await;
+    env.mint_to_authority(&nft, 1).await;
+
+    let conditions = [LockCondition::Time { strike_time: START_TS as u32 + 60 }];
+    let instruction = client::deposit_nft(&pricelocker::ID, &env.authority.pubkey(), LOCKER_NAME, &nft, 1, &conditions, None);
+    env.process(&[instruction], &[]).await.unwrap();
+
+    let locker_token_address = client::locker_token_address(&env.locker_address(), &nft);
+    assert_eq!(env.token_balance(&locker_token_address).await, 1);
+
+    let instruction = client::get_locker_nfts(&pricelocker::ID, &env.locker_address(), &[nft]);
+    let nfts: Vec<NftStatus> = env.simulate(instruction).await.unwrap();
+    assert_eq!(nfts, vec![NftStatus { mint: nft.mint, amount: 1, locked: 1, lock_ids: vec![0] }]);
+
+    let instruction = client::withdraw_nft(&pricelocker::ID, &env.authority.pubkey(), LOCKER_NAME, &nft, 1);
+    let result = env.process(&[instruction], &[]).await;
+    assert_error(result, LockerErrorCode::PayoutAmountExceedsAvailableBalance);
+
+    // Once unlocked, the NFT returns to the authority and the token account of the locker is closed
+    env.set_time(START_TS + 60).await;
+    let instructions = [
+        client::time_unlock_funds(&pricelocker::ID, &env.authority.pubkey(), LOCKER_NAME, 0),
+        client::withdraw_nft(&pricelocker::ID, &env.authority.pubkey(), LOCKER_NAME, &nft, 1),
+    ];
+    env.process(&instructions, &[]).await.unwrap();
+
+    let authority_token_address = client::wallet_token_address(&env.authority.pubkey(), &nft);
+    assert_eq!(env.token_balance(&authority_token_address).await, 1);
+    assert!(env.get_account(&locker_token_address).await.is_none());
+    assert!(env.fetch_locker().await.lots.is_empty());
+
+    let instruction = client::get_locker_nfts(&pricelocker::ID, &env.locker_address(), &[nft]);
+    let nfts: Vec<NftStatus> = env.simulate(instruction).await.unwrap();
+    assert!(nfts.is_empty());
+}
+
+#[tokio::test]
+async fn semi_fungible_tokens_are_partially_withdrawn() {
+    let mut env = TestEnv::start().await;
+    env.create_locker(0).await;
+    let token = env.create_nft(&spl_token::ID).await;
+    env.mint_to_authority(&token, 10).await;
+
+    let instructions = [
+        client::deposit_nft(&pricelocker::ID, &env.authority.pubkey(), LOCKER_NAME, &token, 4, &[LockCondition::Time { strike_time: START_TS as u32 + 60 }], None),
+        client::deposit_nft(&pricelocker::ID, &env.authority.pubkey(), LOCKER_NAME, &token, 6, &[], None),
+        client::withdraw_nft(&pricelocker::ID, &env.authority.pubkey(), LOCKER_NAME, &token, 5),
+    ];
+    env.process(&instructions, &[]).await.unwrap();
+
+    // The token account of the locker stays open while it holds tokens
+    let instruction = client::get_locker_nfts(&pricelocker::ID, &env.locker_address(), &[token]);
+    let nfts: Vec<NftStatus> = env.simulate(instruction).await.unwrap();
+    assert_eq!(nfts, vec![NftStatus { mint: token.mint, amount: 5, locked: 4, lock_ids: vec![0] }]);
+}
+
+#[tokio::test]
+async fn mint_with_decimals_is_not_an_nft() {
+    let mut env = TestEnv::start().await;
+    env.create_locker(0).await;
+    let token = env.create_mint(&spl_token::ID, &[], |_| vec![]).await;
+    env.mint_to_authority(&token, 1).await;
+
+    let instruction = client::deposit_nft(&pricelocker::ID, &env.authority.pubkey(), LOCKER_NAME, &token, 1, &[], None);
+    let result = env.process(&[instruction], &[]).await;
+    assert_error(result, LockerErrorCode::NotAnNft);
+}
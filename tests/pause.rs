@@ -0,0 +1,146 @@
+mod common;
+
+use common::*;
+use pricelocker::{CircuitBreaker, CrankUnlocksResult, Fees, LockerErrorCode};
+use pricelocker_client as client;
+use pricelocker_client::CrankLocker;
+use solana_sdk::signature::{Keypair, Signer};
+
+
+impl TestEnv {
+    async fn set_paused(&mut self, paused: bool) {
+        let instruction = client::set_paused(&pricelocker::ID, &self.authority.pubkey(), paused);
+        self.process(&[instruction], &[]).await.unwrap();
+    }
+}
+
+
+#[tokio::test]
+async fn pause_blocks_deposits_and_locks_but_not_withdrawals() {
+    let mut env = TestEnv::start().await;
+    env.create_locker(1_000_000).await;
+    env.init_config(Fees::default()).await.unwrap();
+
+    // Only the admin pauses
+    let other = Keypair::new();
+    let instruction = client::set_paused(&pricelocker::ID, &other.pubkey(), true);
+    let result = env.process(&[instruction], &[&other]).await;
+    assert_error(result, LockerErrorCode::NotTheAdmin);
+
+    env.set_paused(true).await;
+
+    let instruction = client::deposit_funds(&pricelocker::ID, &env.authority.pubkey(), LOCKER_NAME, 1_000, None, None);
+    let result = env.process(&[instruction], &[]).await;
+    assert_error(result, LockerErrorCode::ProgramPaused);
+
+    let result = env.time_lock(LOCKER_NAME, START_TS + 3_600, 500_000).await;
+    assert_error(result, LockerErrorCode::ProgramPaused);
+
+    let locker_before = env.lamports(&env.locker_address()).await;
+    let instruction = client::withdraw_unlocked_funds(&pricelocker::ID, &env.authority.pubkey(), LOCKER_NAME, 400_000, None);
+    env.process(&[instruction], &[]).await.unwrap();
+    assert_eq!(env.lamports(&env.locker_address()).await, locker_before - 400_000);
+
+    env.set_paused(false).await;
+
+    let instruction = client::deposit_funds(&pricelocker::ID, &env.authority.pubkey(), LOCKER_NAME, 1_000, None, None);
+    env.process(&[instruction], &[]).await.unwrap();
+    env.time_lock(LOCKER_NAME, START_TS + 3_600, 500_000).await.unwrap();
+}
+
+#[tokio::test]
+async fn pause_halts_price_unlocks_while_the_crank_unlocks_time_locks() {
+    let mut env = TestEnv::start().await;
+    env.create_locker(1_000_000).await;
+    let feed = env.create_price_feed(100, START_TS).await;
+    env.time_lock(LOCKER_NAME, START_TS + 60, 300_000).await.unwrap();
+    env.price_lock(LOCKER_NAME, 100, 500_000).await.unwrap();
+    env.init_config(Fees::default()).await.unwrap();
+    env.set_paused(true).await;
+
+    env.set_time(START_TS + 60).await;
+    env.set_pyth_price(&feed.pyth_price_account, MockPrice::trading(100, START_TS + 60));
+
+    let instruction = client::price_unlock_funds(&pricelocker::ID, &env.authority.pubkey(), LOCKER_NAME, &feed, 1);
+    let result = env.process(&[instruction], &[]).await;
+    assert_error(result, LockerErrorCode::ProgramPaused);
+
+    let lockers = [CrankLocker { locker: env.locker_address(), price_feed: Some(feed) }];
+    let instruction = client::crank_unlocks(&pricelocker::ID, &env.authority.pubkey(), &lockers);
+    let results: Vec<CrankUnlocksResult> = env.simulate(instruction).await.unwrap();
+    assert_eq!(results, vec![CrankUnlocksResult { locker: lockers[0].locker, unlocked: vec![0], skipped: 1 }]);
+}
+
+#[tokio::test]
+async fn circuit_breaker_halts_price_unlocks_until_the_window_passed() {
+    let mut env = TestEnv::start().await;
+    env.create_locker(1_000_000).await;
+    let feed = env.create_price_feed(100, START_TS).await;
+    env.price_lock(LOCKER_NAME, 150, 500_000).await.unwrap();
+    env.init_config(Fees::default()).await.unwrap();
+
+    // An enabled circuit breaker needs a window
+    let instruction = client::set_circuit_breaker(&pricelocker::ID, &env.authority.pubkey(), CircuitBreaker { max_price_move_bps: 1_000, window_secs: 0 });
+    let result = env.process(&[instruction], &[]).await;
+    assert_error(result, LockerErrorCode::InvalidCircuitBreaker);
+
+    let instruction = client::set_circuit_breaker(&pricelocker::ID, &env.authority.pubkey(), CircuitBreaker { max_price_move_bps: 1_000, window_secs: 300 });
+    env.process(&[instruction], &[]).await.unwrap();
+
+    // The first price unlock starts the window at a price of 100, the lock is skipped
+    let instruction = client::price_unlock_funds(&pricelocker::ID, &env.authority.pubkey(), LOCKER_NAME, &feed, 255);
+    env.process(&[instruction], &[]).await.unwrap();
+
+    // A move of 60% within the window halts the price unlocks, also in the crank
+    env.set_time(START_TS + 60).await;
+    env.set_pyth_price(&feed.pyth_price_account, MockPrice::trading(160, START_TS + 60));
+    let instruction = client::price_unlock_funds(&pricelocker::ID, &env.authority.pubkey(), LOCKER_NAME, &feed, 0);
+    let result = env.process(&[instruction], &[]).await;
+    assert_error(result, LockerErrorCode::CircuitBreakerTripped);
+
+    let lockers = [CrankLocker { locker: env.locker_address(), price_feed: Some(feed) }];
+    let instruction = client::crank_unlocks(&pricelocker::ID, &env.authority.pubkey(), &lockers);
+    let results: Vec<CrankUnlocksResult> = env.simulate(instruction).await.unwrap();
+    assert_eq!(results, vec![CrankUnlocksResult { locker: lockers[0].locker, unlocked: vec![], skipped: 1 }]);
+
+    // Once the window passed and the EMA price followed the move, the price starts a new window and unlocks the lock
+    env.set_time(START_TS + 300).await;
+    env.set_pyth_price(&feed.pyth_price_account, MockPrice::trading(160, START_TS + 300));
+    let instruction = client::price_unlock_funds(&pricelocker::ID, &env.authority.pubkey(), LOCKER_NAME, &feed, 0);
+    env.process(&[instruction], &[]).await.unwrap();
+    assert!(!env.fetch_locker().await.locks[0].is_locked());
+}
+
+#[tokio::test]
+async fn circuit_breaker_checks_the_first_unlock_after_the_window() {
+    let mut env = TestEnv::start().await;
+    env.create_locker(1_000_000).await;
+    let feed = env.create_price_feed(100, START_TS).await;
+    env.price_lock(LOCKER_NAME, 150, 500_000).await.unwrap();
+    env.init_config(Fees::default()).await.unwrap();
+    let instruction = client::set_circuit_breaker(&pricelocker::ID, &env.authority.pubkey(), CircuitBreaker { max_price_move_bps: 1_000, window_secs: 300 });
+    env.process(&[instruction], &[]).await.unwrap();
+
+    // A manipulated price for the very first unlock is compared with the EMA price
+    env.set_pyth_price(&feed.pyth_price_account, MockPrice { ema_price: 100, ..MockPrice::trading(160, START_TS) });
+    let instruction = client::price_unlock_funds(&pricelocker::ID, &env.authority.pubkey(), LOCKER_NAME, &feed, 0);
+    let result = env.process(&[instruction], &[]).await;
+    assert_error(result, LockerErrorCode::CircuitBreakerTripped);
+
+    // The same for the first unlock after the window, the reference of the previous window is no longer used
+    env.set_pyth_price(&feed.pyth_price_account, MockPrice::trading(100, START_TS));
+    let instruction = client::price_unlock_funds(&pricelocker::ID, &env.authority.pubkey(), LOCKER_NAME, &feed, 255);
+    env.process(&[instruction], &[]).await.unwrap();
+
+    env.set_time(START_TS + 300).await;
+    env.set_pyth_price(&feed.pyth_price_account, MockPrice { ema_price: 100, ..MockPrice::trading(160, START_TS + 300) });
+    let instruction = client::price_unlock_funds(&pricelocker::ID, &env.authority.pubkey(), LOCKER_NAME, &feed, 0);
+    let result = env.process(&[instruction], &[]).await;
+    assert_error(result, LockerErrorCode::CircuitBreakerTripped);
+
+    let lockers = [CrankLocker { locker: env.locker_address(), price_feed: Some(feed) }];
+    let instruction = client::crank_unlocks(&pricelocker::ID, &env.authority.pubkey(), &lockers);
+    let results: Vec<CrankUnlocksResult> = env.simulate(instruction).await.unwrap();
+    assert_eq!(results, vec![CrankUnlocksResult { locker: lockers[0].locker, unlocked: vec![], skipped: 1 }]);
+    assert!(env.fetch_locker().await.locks[0].is_locked());
+}
@@ -0,0 +1,174 @@
+mod common;
+
+use anchor_spl::token::spl_token;
+use anchor_spl::token_2022::spl_token_2022;
+use anchor_spl::token_2022::spl_token_2022::extension::ExtensionType;
+use common::*;
+use pricelocker::{Lock, LockCondition, LockerErrorCode, Lot, WITHDRAW_MAX};
+use pricelocker_client as client;
+use pricelocker_client::TokenAsset;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signer;
+
+
+impl TestEnv {
+    async fn withdraw_tokens(&mut self, token: &TokenAsset, amount: u32) -> std::result::Result<(), solana_program_test::BanksClientError> {
+        let instruction = client::withdraw_unlocked_funds(&pricelocker::ID, &self.authority.pubkey(), LOCKER_NAME, amount, Some(token));
+        self.process(&[instruction], &[]).await
+    }
+}
+
+
+#[tokio::test]
+async fn spl_tokens_are_deposited_locked_and_withdrawn() {
+    let mut env = TestEnv::start().await;
+    env.create_locker(0).await;
+    let token = env.create_mint(&spl_token::ID, &[], |_| vec![]).await;
+    env.mint_to_authority(&token, 1_000_000).await;
+
+    // The first deposit creates the token account of the locker
+    let conditions = [LockCondition::Time { strike_time: START_TS as u32 + 60 }];
+    let instructions = [
+        client::deposit_and_lock(&pricelocker::ID, &env.authority.pubkey(), LOCKER_NAME, 600_000, Some(&token), None, &conditions, None),
+        client::deposit_funds(&pricelocker::ID, &env.authority.pubkey(), LOCKER_NAME, 400_000, Some(&token), None),
+    ];
+    env.process(&instructions, &[]).await.unwrap();
+
+    let locker_token_address = client::locker_token_address(&env.locker_address(), &token);
+    assert_eq!(env.token_balance(&locker_token_address).await, 1_000_000);
+    let lot_amounts: Vec<(Option<Pubkey>, u32)> = env.fetch_locker().await.lots.iter().map(|lot| (lot.token_mint, lot.amount)).collect();
+    assert_eq!(lot_amounts, vec![(Some(token.mint), 600_000), (Some(token.mint), 400_000)]);
+
+    // Only the tokens that are not locked can be withdrawn, signed by the locker
+    let result = env.withdraw_tokens(&token, 400_001).await;
+    assert_error(result, LockerErrorCode::PayoutAmountExceedsAvailableBalance);
+    env.withdraw_tokens(&token, WITHDRAW_MAX).await.unwrap();
+
+    let authority_token_address = client::wallet_token_address(&env.authority.pubkey(), &token);
+    assert_eq!(env.token_balance(&authority_token_address).await, 400_000);
+    assert_eq!(env.token_balance(&locker_token_address).await, 600_000);
+}
+
+#[tokio::test]
+async fn transfer_fee_locks_the_received_amount() {
+    let mut env = TestEnv::start().await;
+    env.create_locker(0).await;
+
+    // 1% transfer fee
+    let token = env.create_mint(&spl_token_2022::ID, &[ExtensionType::TransferFeeConfig], |mint| vec![
+        spl_token_2022::extension::transfer_fee::instruction::initialize_transfer_fee_config(&spl_token_2022::ID, mint, None, None, 100, u64::MAX).unwrap(),
+    ]).await;
+    env.mint_to_authority(&token, 1_000_000).await;
+
+    let conditions = [LockCondition::Time { strike_time: START_TS as u32 + 60 }];
+    let instruction = client::deposit_and_lock(&pricelocker::ID, &env.authority.pubkey(), LOCKER_NAME, 100_000, Some(&token), None, &conditions, None);
+    env.process(&[instruction], &[]).await.unwrap();
+
+    // The fee is withheld in the token account of the locker, the lot and the lock hold what was received
+    let locker_token_address = client::locker_token_address(&env.locker_address(), &token);
+    assert_eq!(env.token_balance(&locker_token_address).await, 99_000);
+    assert_eq!(env.withheld_fee(&locker_token_address).await, 1_000);
+
+    let locker = env.fetch_locker().await;
+    assert_eq!(locker.lots, vec![Lot { id: 0, token_mint: Some(token.mint), amount: 99_000, deposit_ts: START_TS as u32, deposit_price: None }]);
+    assert_eq!(locker.locks, vec![
+        Lock::TimeLock { id: 0, strike_time: START_TS as u32 + 60, amount: 99_000, token_mint: Some(token.mint), locked: true, join: None, lot: Some(0), beneficiary: None, early_exit: None },
+    ]);
+
+    // Once unlocked, the whole received amount can be withdrawn, the authority pays the fee of the withdrawal
+    env.set_time(START_TS + 60).await;
+    let instruction = client::time_unlock_funds(&pricelocker::ID, &env.authority.pubkey(), LOCKER_NAME, 0);
+    env.process(&[instruction], &[]).await.unwrap();
+    env.withdraw_tokens(&token, WITHDRAW_MAX).await.unwrap();
+
+    let authority_token_address = client::wallet_token_address(&env.authority.pubkey(), &token);
+    assert_eq!(env.token_balance(&authority_token_address).await, 900_000 + 98_010);
+    assert_eq!(env.token_balance(&locker_token_address).await, 0);
+    assert!(env.fetch_locker().await.lots.is_empty());
+}
+
+#[tokio::test]
+async fn mint_with_permanent_delegate_is_rejected() {
+    let mut env = TestEnv::start().await;
+    env.create_locker(0).await;
+
+    // The permanent delegate could transfer the locked tokens out of the locker
+    let delegate = Pubkey::new_unique();
+    let token = env.create_mint(&spl_token_2022::ID, &[ExtensionType::PermanentDelegate], |mint| vec![
+        spl_token_2022::instruction::initialize_permanent_delegate(&spl_token_2022::ID, mint, &delegate).unwrap(),
+    ]).await;
+    env.mint_to_authority(&token, 1_000_000).await;
+
+    let instruction = client::deposit_funds(&pricelocker::ID, &env.authority.pubkey(), LOCKER_NAME, 100_000, Some(&token), None);
+    let result = env.process(&[instruction], &[]).await;
+    assert_error(result, LockerErrorCode::UnsupportedMintExtension);
+}
+
+#[tokio::test]
+async fn token_2022_without_extensions_is_accepted() {
+    let mut env = TestEnv::start().await;
+    env.create_locker(0).await;
+    let token = env.create_mint(&spl_token_2022::ID, &[], |_| vec![]).await;
+    env.mint_to_authority(&token, 1_000_000).await;
+
+    let instruction = client::deposit_funds(&pricelocker::ID, &env.authority.pubkey(), LOCKER_NAME, 250_000, Some(&token), None);
+    env.process(&[instruction], &[]).await.unwrap();
+    env.withdraw_tokens(&token, 100_000).await.unwrap();
+
+    let locker_token_address = client::locker_token_address(&env.locker_address(), &token);
+    assert_eq!(env.token_balance(&locker_token_address).await, 150_000);
+}
+
+#[tokio::test]
+async fn wrapped_sol_is_locked_like_a_token_and_unwrapped_on_withdrawal() {
+    let mut env = TestEnv::start().await;
+    env.create_locker(0).await;
+    let token = TokenAsset::wrapped_sol();
+
+    let instruction = client::deposit_wrapped_sol(&pricelocker::ID, &env.authority.pubkey(), LOCKER_NAME, 500_000, None);
+    env.process(&[instruction], &[]).await.unwrap();
+
+    let locker_token_address = client::locker_token_address(&env.locker_address(), &token);
+    assert_eq!(env.token_balance(&locker_token_address).await, 500_000);
+    assert_eq!(env.fetch_locker().await.lots[0].token_mint, Some(token.mint));
+
+    // The lock is checked against the wrapped SOL, not the SOL of the locker
+    let instruction = client::time_lock_funds(&pricelocker::ID, &env.authority.pubkey(), LOCKER_NAME, START_TS as u32 + 60, 300_000, Some(token), None);
+    env.process(&[instruction], &[]).await.unwrap();
+
+    let result = env.process(&[client::withdraw_unwrapped_sol(&pricelocker::ID, &env.authority.pubkey(), LOCKER_NAME, 200_001)], &[]).await;
+    assert_error(result, LockerErrorCode::PayoutAmountExceedsAvailableBalance);
+
+    // The SOL leaves the native mint token account of the locker, and the authority receives it as SOL
+    //  while its native mint token account is closed again
+    let authority = env.authority.pubkey();
+    let locker_lamports_before = env.lamports(&locker_token_address).await;
+    let lamports_before = env.lamports(&authority).await;
+    env.process(&[client::withdraw_unwrapped_sol(&pricelocker::ID, &authority, LOCKER_NAME, 200_000)], &[]).await.unwrap();
+
+    assert_eq!(env.lamports(&locker_token_address).await, locker_lamports_before - 200_000);
+    // The authority also pays the transaction fee, which is far below the withdrawn amount
+    assert!(env.lamports(&authority).await > lamports_before);
+    assert!(env.get_account(&client::wallet_token_address(&authority, &token)).await.is_none());
+    assert_eq!(env.token_balance(&locker_token_address).await, 300_000);
+}
+
+#[tokio::test]
+async fn token_locks_are_limited_to_the_token_balance() {
+    let mut env = TestEnv::start().await;
+    env.create_locker(1_000_000).await;
+    env.create_price_feed(90, START_TS).await;
+    let token = env.create_mint(&spl_token::ID, &[], |_| vec![]).await;
+    env.mint_to_authority(&token, 1_000_000).await;
+
+    let instruction = client::deposit_funds(&pricelocker::ID, &env.authority.pubkey(), LOCKER_NAME, 100_000, Some(&token), None);
+    env.process(&[instruction], &[]).await.unwrap();
+
+    // The locker holds more SOL, but only 100_000 of the token
+    let instruction = client::price_lock_funds(&pricelocker::ID, &env.authority.pubkey(), LOCKER_NAME, &price_feed_address(), 100, 100_001, Some(token), None);
+    let result = env.process(&[instruction], &[]).await;
+    assert_error(result, LockerErrorCode::PayoutAmountExceedsAvailableBalance);
+
+    let instruction = client::price_lock_funds(&pricelocker::ID, &env.authority.pubkey(), LOCKER_NAME, &price_feed_address(), 100, 100_000, Some(token), None);
+    env.process(&[instruction], &[]).await.unwrap();
+}
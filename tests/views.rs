@@ -0,0 +1,132 @@
+mod common;
+
+use anchor_lang::prelude::Pubkey;
+use common::*;
+use pricelocker::{AssetStatus, LockVerification, LockerErrorCode, LockerStatus};
+use pricelocker_client as client;
+use pricelocker_client::TokenAsset;
+use solana_sdk::signature::Signer;
+
+
+#[tokio::test]
+async fn locker_status_reports_balances_and_locks() {
+    let mut env = TestEnv::start().await;
+    env.create_locker(1_000_000).await;
+    let feed = env.create_price_feed(100, START_TS + 60).await;
+
+    env.time_lock(LOCKER_NAME, START_TS + 60, 300_000).await.unwrap();
+    env.time_lock(LOCKER_NAME, START_TS + 3_600, 200_000).await.unwrap();
+    env.price_lock(LOCKER_NAME, 100, 100_000).await.unwrap();
+
+    // SPL tokens held by the token account of the locker
+    let token_mint = Pubkey::new_unique();
+    let token = TokenAsset::spl_token(token_mint);
+    let locker_address = env.locker_address();
+    env.set_token_account(&client::locker_token_address(&locker_address, &token), &token_mint, &locker_address, 5_000);
+
+    env.set_time(START_TS + 60).await;
+    let instruction = client::get_locker_status(&pricelocker::ID, &locker_address, Some(&feed), &[token]);
+    let status: LockerStatus = env.simulate(instruction).await.unwrap();
+
+    assert_eq!(status.locker, locker_address);
+    assert_eq!(status.authority, env.authority.pubkey());
+    assert_eq!(status.assets, vec![
+        AssetStatus { token_mint: None, total: 1_000_000, locked: 600_000, unlockable_now: 400_000, withdrawable: 400_000 },
+        AssetStatus { token_mint: Some(token_mint), total: 5_000, locked: 0, unlockable_now: 0, withdrawable: 5_000 },
+    ]);
+
+    let unlockable: Vec<bool> = status.locks.iter().map(|lock_status| lock_status.unlockable_now).collect();
+    assert_eq!(unlockable, vec![true, false, true]);
+}
+
+#[tokio::test]
+async fn locker_status_rejects_token_account_of_other_owner() {
+    let mut env = TestEnv::start().await;
+    env.create_locker(1_000_000).await;
+
+    let token_mint = Pubkey::new_unique();
+    let token = TokenAsset::spl_token(token_mint);
+    let locker_address = env.locker_address();
+    env.set_token_account(&client::locker_token_address(&locker_address, &token), &token_mint, &Pubkey::new_unique(), 5_000);
+
+    let instruction = client::get_locker_status(&pricelocker::ID, &locker_address, None, &[token]);
+    let result = env.simulate::<LockerStatus>(instruction).await.map(|_| ());
+    assert_error(result, LockerErrorCode::InvalidRemainingAccounts);
+}
+
+#[tokio::test]
+async fn verify_lock_counts_time_locks_until_requested_time() {
+    let mut env = TestEnv::start().await;
+    env.create_locker(1_000_000).await;
+    env.create_price_feed(90, START_TS).await;
+    env.time_lock(LOCKER_NAME, START_TS + 3_600, 300_000).await.unwrap();
+    env.time_lock(LOCKER_NAME, START_TS + 60, 200_000).await.unwrap();
+    env.price_lock(LOCKER_NAME, 100, 100_000).await.unwrap();
+
+    let locker_address = env.locker_address();
+    let owner = env.authority.pubkey();
+
+    // Only the first time lock is locked until at least the requested time
+    let instruction = client::verify_lock(&pricelocker::ID, &locker_address, &owner, None, 300_000, START_TS as u32 + 600);
+    let verification: LockVerification = env.simulate(instruction).await.unwrap();
+    assert_eq!(verification, LockVerification { locker: locker_address, owner, token_mint: None, locked_amount: 300_000, satisfied: true });
+
+    let instruction = client::verify_lock(&pricelocker::ID, &locker_address, &owner, None, 300_001, START_TS as u32 + 600);
+    let verification: LockVerification = env.simulate(instruction).await.unwrap();
+    assert!(!verification.satisfied);
+
+    // Another wallet than the authority never satisfies the requirement
+    let other = Pubkey::new_unique();
+    let instruction = client::verify_lock(&pricelocker::ID, &locker_address, &other, None, 1, START_TS as u32);
+    let verification: LockVerification = env.simulate(instruction).await.unwrap();
+    assert!(!verification.satisfied);
+}
+
+#[tokio::test]
+async fn verify_lock_counts_no_locks_of_a_locker_with_guardians() {
+    let mut env = TestEnv::start().await;
+    env.create_locker(1_000_000).await;
+    let guardians = [Pubkey::new_unique(), Pubkey::new_unique()];
+    let instruction = client::set_guardians(&pricelocker::ID, &env.authority.pubkey(), LOCKER_NAME, &guardians, 2, 1_000, &Pubkey::new_unique());
+    env.process(&[instruction], &[]).await.unwrap();
+    env.time_lock(LOCKER_NAME, START_TS + 3_600, 300_000).await.unwrap();
+
+    // The guardians can release the time lock at any moment
+    let (locker_address, owner) = (env.locker_address(), env.authority.pubkey());
+    let instruction = client::verify_lock(&pricelocker::ID, &locker_address, &owner, None, 1, START_TS as u32 + 600);
+    let verification: LockVerification = env.simulate(instruction).await.unwrap();
+    assert_eq!(verification.locked_amount, 0);
+    assert!(!verification.satisfied);
+}
+
+#[tokio::test]
+async fn require_lock_fails_when_requirement_not_met() {
+    let mut env = TestEnv::start().await;
+    env.create_locker(1_000_000).await;
+    env.time_lock(LOCKER_NAME, START_TS + 3_600, 300_000).await.unwrap();
+
+    let locker_address = env.locker_address();
+    let owner = env.authority.pubkey();
+
+    let instruction = client::require_lock(&pricelocker::ID, &locker_address, &owner, None, 300_000, START_TS as u32 + 3_600);
+    env.process(&[instruction], &[]).await.unwrap();
+
+    let instruction = client::require_lock(&pricelocker::ID, &locker_address, &owner, None, 300_000, START_TS as u32 + 3_601);
+    let result = env.process(&[instruction], &[]).await;
+    assert_error(result, LockerErrorCode::LockRequirementNotMet);
+}
+
+#[tokio::test]
+async fn verify_lock_rejects_token_account_of_other_owner() {
+    let mut env = TestEnv::start().await;
+    env.create_locker(1_000_000).await;
+
+    let token_mint = Pubkey::new_unique();
+    let token = TokenAsset::spl_token(token_mint);
+    let locker_address = env.locker_address();
+    env.set_token_account(&client::locker_token_address(&locker_address, &token), &token_mint, &Pubkey::new_unique(), 5_000);
+
+    let instruction = client::verify_lock(&pricelocker::ID, &locker_address, &env.authority.pubkey(), Some(token), 1, START_TS as u32);
+    let result = env.simulate::<LockVerification>(instruction).await.map(|_| ());
+    assert_error(result, LockerErrorCode::InvalidTokenAccount);
+}
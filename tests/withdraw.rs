@@ -0,0 +1,80 @@
+mod common;
+
+use anchor_lang::prelude::{Pubkey, Rent};
+use common::*;
+use pricelocker::{LockerErrorCode, WITHDRAW_MAX};
+use pricelocker_client as client;
+use pricelocker_client::TokenAsset;
+use solana_sdk::signature::Signer;
+
+
+// Lamports of the locker that can never be withdrawn
+async fn rent_exempt_minimum(env: &mut TestEnv, address: &Pubkey) -> u64 {
+    let data_len = env.get_account(address).await.unwrap().data.len();
+    let rent: Rent = env.context.banks_client.get_sysvar().await.unwrap();
+    rent.minimum_balance(data_len)
+}
+
+
+#[tokio::test]
+async fn withdraw_is_limited_to_withdrawable_balance() {
+    let mut env = TestEnv::start().await;
+    env.create_locker(1_000_000).await;
+    env.time_lock(LOCKER_NAME, START_TS + 60, 600_000).await.unwrap();
+
+    let locker_address = env.locker_address();
+    let lamports_before = env.lamports(&locker_address).await;
+
+    let instruction = client::withdraw_unlocked_funds(&pricelocker::ID, &env.authority.pubkey(), LOCKER_NAME, 400_001, None);
+    let result = env.process(&[instruction], &[]).await;
+    assert_error(result, LockerErrorCode::PayoutAmountExceedsAvailableBalance);
+
+    let instruction = client::withdraw_unlocked_funds(&pricelocker::ID, &env.authority.pubkey(), LOCKER_NAME, 400_000, None);
+    env.process(&[instruction], &[]).await.unwrap();
+
+    assert_eq!(env.lamports(&locker_address).await, lamports_before - 400_000);
+    assert_eq!(env.fetch_locker().await.locked_balance, 600_000);
+}
+
+#[tokio::test]
+async fn withdraw_max_keeps_rent_exempt_minimum() {
+    let mut env = TestEnv::start().await;
+    env.create_locker(1_000_000).await;
+    env.time_lock(LOCKER_NAME, START_TS + 60, 600_000).await.unwrap();
+
+    // Once the lock is unlocked, everything but the rent-exempt minimum is withdrawable
+    env.set_time(START_TS + 60).await;
+    let instructions = [
+        client::time_unlock_funds(&pricelocker::ID, &env.authority.pubkey(), LOCKER_NAME, 0),
+        client::withdraw_unlocked_funds(&pricelocker::ID, &env.authority.pubkey(), LOCKER_NAME, WITHDRAW_MAX, None),
+    ];
+    env.process(&instructions, &[]).await.unwrap();
+
+    let locker_address = env.locker_address();
+    let rent_exempt_minimum = rent_exempt_minimum(&mut env, &locker_address).await;
+    assert_eq!(env.lamports(&locker_address).await, rent_exempt_minimum);
+    assert_eq!(env.fetch_locker().await.locked_balance, 0);
+
+    // Nothing is left to withdraw
+    let instruction = client::withdraw_unlocked_funds(&pricelocker::ID, &env.authority.pubkey(), LOCKER_NAME, WITHDRAW_MAX, None);
+    let result = env.process(&[instruction], &[]).await;
+    assert_error(result, LockerErrorCode::PayoutAmountNotPositive);
+}
+
+#[tokio::test]
+async fn withdraw_tokens_rejects_token_account_of_other_owner() {
+    let mut env = TestEnv::start().await;
+    env.create_locker(1_000_000).await;
+
+    // The token balance is read from the token account, which must belong to the locker
+    let token_mint = Pubkey::new_unique();
+    let token = TokenAsset::spl_token(token_mint);
+    let locker_address = env.locker_address();
+    env.set_token_account(&client::locker_token_address(&locker_address, &token), &token_mint, &Pubkey::new_unique(), 5_000);
+    env.set_token_account(&client::wallet_token_address(&env.authority.pubkey(), &token), &token_mint, &env.authority.pubkey(), 0);
+    env.set_mint(&token_mint);
+
+    let instruction = client::withdraw_unlocked_funds(&pricelocker::ID, &env.authority.pubkey(), LOCKER_NAME, 1_000, Some(&token));
+    let result = env.process(&[instruction], &[]).await;
+    assert_error(result, LockerErrorCode::InvalidTokenAccount);
+}
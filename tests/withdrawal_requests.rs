@@ -0,0 +1,166 @@
+mod common;
+
+use common::*;
+use pricelocker::{LockCondition, LockerErrorCode};
+use pricelocker_client as client;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signer};
+
+
+const COOLDOWN: i64 = 24 * 60 * 60;
+
+
+impl TestEnv {
+    async fn set_cooldown(&mut self, cooldown_secs: u32) {
+        let instruction = client::set_withdrawal_cooldown(&pricelocker::ID, &self.authority.pubkey(), LOCKER_NAME, cooldown_secs);
+        self.process(&[instruction], &[]).await.unwrap();
+    }
+
+    async fn request_withdrawal(&mut self, amount: u32, destination: &Pubkey) -> std::result::Result<(), solana_program_test::BanksClientError> {
+        let instruction = client::request_withdrawal(&pricelocker::ID, &self.authority.pubkey(), LOCKER_NAME, amount, None, destination);
+        self.process(&[instruction], &[]).await
+    }
+
+    async fn execute_withdrawal(&mut self, destination: &Pubkey) -> std::result::Result<(), solana_program_test::BanksClientError> {
+        let instruction = client::execute_withdrawal(&pricelocker::ID, &self.authority.pubkey(), LOCKER_NAME, None, destination);
+        self.process(&[instruction], &[]).await
+    }
+}
+
+
+#[tokio::test]
+async fn withdrawals_wait_for_the_cooldown() {
+    let mut env = TestEnv::start().await;
+    env.create_locker(5_000_000).await;
+    let savings = Pubkey::new_unique();
+    env.set_cooldown(COOLDOWN as u32).await;
+
+    let instruction = client::withdraw_unlocked_funds(&pricelocker::ID, &env.authority.pubkey(), LOCKER_NAME, 1_000_000, None);
+    let result = env.process(&[instruction], &[]).await;
+    assert_error(result, LockerErrorCode::WithdrawalRequestRequired);
+
+    let result = env.execute_withdrawal(&savings).await;
+    assert_error(result, LockerErrorCode::NoWithdrawalRequest);
+
+    env.request_withdrawal(1_000_000, &savings).await.unwrap();
+    let request = env.fetch_locker().await.withdrawal_request.unwrap();
+    assert_eq!(request.amount, 1_000_000);
+    assert_eq!(request.destination, savings);
+    assert_eq!(request.executable_ts, START_TS as u32 + COOLDOWN as u32);
+
+    env.set_time(START_TS + COOLDOWN - 1).await;
+    let result = env.execute_withdrawal(&savings).await;
+    assert_error(result, LockerErrorCode::WithdrawalCooldownNotPassed);
+
+    // The destination is fixed by the request
+    env.set_time(START_TS + COOLDOWN).await;
+    let result = env.execute_withdrawal(&Pubkey::new_unique()).await;
+    assert_error(result, LockerErrorCode::NotTheRequestedWithdrawal);
+
+    env.execute_withdrawal(&savings).await.unwrap();
+    assert_eq!(env.lamports(&savings).await, 1_000_000);
+    assert!(env.fetch_locker().await.withdrawal_request.is_none());
+}
+
+#[tokio::test]
+async fn guardians_can_cancel_a_withdrawal_request() {
+    let mut env = TestEnv::start().await;
+    env.create_locker(5_000_000).await;
+    let guardian = Keypair::new();
+    let stranger = Keypair::new();
+    let thief = Pubkey::new_unique();
+    let instruction = client::set_guardians(&pricelocker::ID, &env.authority.pubkey(), LOCKER_NAME, &[guardian.pubkey()], 1, 0, &Pubkey::new_unique());
+    env.process(&[instruction], &[]).await.unwrap();
+    env.set_cooldown(COOLDOWN as u32).await;
+
+    // A compromised key requests everything, a guardian cancels it within the cooldown
+    env.request_withdrawal(client::WITHDRAW_MAX, &thief).await.unwrap();
+
+    let instruction = client::cancel_withdrawal(&pricelocker::ID, &stranger.pubkey(), &env.authority.pubkey(), LOCKER_NAME);
+    let result = env.process(&[instruction], &[&stranger]).await;
+    assert_error(result, LockerErrorCode::NotTheAuthorityOrAGuardian);
+
+    let instruction = client::cancel_withdrawal(&pricelocker::ID, &guardian.pubkey(), &env.authority.pubkey(), LOCKER_NAME);
+    env.process(std::slice::from_ref(&instruction), &[&guardian]).await.unwrap();
+    assert!(env.fetch_locker().await.withdrawal_request.is_none());
+
+    env.set_time(START_TS + COOLDOWN).await;
+    let result = env.execute_withdrawal(&thief).await;
+    assert_error(result, LockerErrorCode::NoWithdrawalRequest);
+
+    let result = env.process(&[instruction], &[&guardian]).await;
+    assert_error(result, LockerErrorCode::NoWithdrawalRequest);
+}
+
+#[tokio::test]
+async fn shorter_cooldown_takes_effect_after_the_current_one() {
+    let mut env = TestEnv::start().await;
+    env.create_locker(5_000_000).await;
+    env.set_cooldown(COOLDOWN as u32).await;
+
+    // Removing the cooldown can't be used to withdraw right away
+    env.set_cooldown(0).await;
+    let cooldown = env.fetch_locker().await.withdrawal_cooldown;
+    assert_eq!(cooldown.cooldown_secs, COOLDOWN as u32);
+    assert_eq!(cooldown.pending.unwrap().active_ts, START_TS as u32 + COOLDOWN as u32);
+
+    let instruction = client::withdraw_unlocked_funds(&pricelocker::ID, &env.authority.pubkey(), LOCKER_NAME, 1_000_000, None);
+    let result = env.process(std::slice::from_ref(&instruction), &[]).await;
+    assert_error(result, LockerErrorCode::WithdrawalRequestRequired);
+
+    env.set_time(START_TS + COOLDOWN).await;
+    env.process(&[instruction], &[]).await.unwrap();
+    let result = env.request_withdrawal(1_000_000, &env.authority.pubkey()).await;
+    assert_error(result, LockerErrorCode::NoWithdrawalCooldown);
+}
+
+#[tokio::test]
+async fn cooldown_can_not_be_skipped_through_a_beneficiary() {
+    let mut env = TestEnv::start().await;
+    env.create_locker(0).await;
+    let thief = Pubkey::new_unique();
+    env.set_cooldown(COOLDOWN as u32).await;
+
+    // A lock that unlocks right away to a beneficiary would pay out through a claim without the cooldown
+    for condition in [LockCondition::Time { strike_time: 0 }, LockCondition::Inactivity { period: 1 }] {
+        let instruction = client::deposit_and_lock(&pricelocker::ID, &env.authority.pubkey(), LOCKER_NAME, 1_000_000, None, None, &[condition], Some(thief));
+        let result = env.process(&[instruction], &[]).await;
+        assert_error(result, LockerErrorCode::WithdrawalCooldownActive);
+    }
+
+    // Lifting the cooldown takes as long as the cooldown, after that beneficiaries can be set again
+    env.set_cooldown(0).await;
+    env.set_time(START_TS + COOLDOWN).await;
+    let instruction = client::deposit_and_lock(&pricelocker::ID, &env.authority.pubkey(), LOCKER_NAME, 1_000_000, None, None, &[LockCondition::Time { strike_time: 0 }], Some(thief));
+    env.process(&[instruction], &[]).await.unwrap();
+}
+
+#[tokio::test]
+async fn cooldown_can_not_be_skipped_through_an_early_exit_penalty() {
+    let mut env = TestEnv::start().await;
+    env.create_locker(0).await;
+    let thief = Pubkey::new_unique();
+    env.set_cooldown(COOLDOWN as u32).await;
+
+    // A full penalty paid right away by an early exit would move the funds without the cooldown
+    let conditions = [LockCondition::TimeWithEarlyExit { strike_time: START_TS as u32 + 3_600, max_penalty_bps: 10_000, penalty_destination: thief }];
+    let instruction = client::deposit_and_lock(&pricelocker::ID, &env.authority.pubkey(), LOCKER_NAME, 1_000_000, None, None, &conditions, None);
+    let result = env.process(&[instruction], &[]).await;
+    assert_error(result, LockerErrorCode::WithdrawalCooldownActive);
+    assert!(env.fetch_locker().await.locks.is_empty());
+}
+
+#[tokio::test]
+async fn cooldown_can_not_be_skipped_through_guardians() {
+    let mut env = TestEnv::start().await;
+    env.create_locker(5_000_000).await;
+    let guardian = Keypair::new();
+    let thief = Pubkey::new_unique();
+    env.set_cooldown(COOLDOWN as u32).await;
+
+    // Guardians of the attacker would release the locks with a full penalty to the attacker
+    let instruction = client::set_guardians(&pricelocker::ID, &env.authority.pubkey(), LOCKER_NAME, &[guardian.pubkey()], 1, 10_000, &thief);
+    let result = env.process(&[instruction], &[]).await;
+    assert_error(result, LockerErrorCode::WithdrawalCooldownActive);
+    assert!(env.fetch_locker().await.guardians.is_none());
+}